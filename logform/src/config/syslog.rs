@@ -25,3 +25,55 @@ pub fn colors() -> HashMap<String, String> {
         ("debug".to_string(), "blue".to_string()),
     ])
 }
+
+/// Maps a level name onto its syslog severity (0 = emerg ... 7 = debug).
+///
+/// Besides the syslog names this understands the npm, cli and rust level sets
+/// (`fatal`, `warn`, `http`, `verbose`, `trace`, `silly`, ...), case-insensitively.
+pub fn severity(level: &str) -> Option<u8> {
+    let severity = match level.to_ascii_lowercase().as_str() {
+        "emerg" | "emergency" | "panic" => 0,
+        "alert" => 1,
+        "crit" | "critical" | "fatal" => 2,
+        "error" | "err" => 3,
+        "warning" | "warn" => 4,
+        "notice" => 5,
+        "info" | "help" | "data" | "prompt" | "input" => 6,
+        "debug" | "http" | "verbose" | "trace" | "silly" => 7,
+        _ => return None,
+    };
+    Some(severity)
+}
+
+/// Maps a facility name (`kern`, `user`, `daemon`, `local0`...`local7`, ...) onto
+/// its syslog facility code.
+pub fn facility(name: &str) -> Option<u8> {
+    let facility = match name.to_ascii_lowercase().as_str() {
+        "kern" => 0,
+        "user" => 1,
+        "mail" => 2,
+        "daemon" => 3,
+        "auth" => 4,
+        "syslog" => 5,
+        "lpr" => 6,
+        "news" => 7,
+        "uucp" => 8,
+        "cron" => 9,
+        "authpriv" => 10,
+        "ftp" => 11,
+        "ntp" => 12,
+        "security" => 13,
+        "console" => 14,
+        "solaris-cron" => 15,
+        "local0" => 16,
+        "local1" => 17,
+        "local2" => 18,
+        "local3" => 19,
+        "local4" => 20,
+        "local5" => 21,
+        "local6" => 22,
+        "local7" => 23,
+        _ => return None,
+    };
+    Some(facility)
+}
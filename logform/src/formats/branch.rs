@@ -0,0 +1,143 @@
+use super::{Format, FormatError};
+use crate::LogInfo;
+use indexmap::IndexMap;
+use std::sync::Arc;
+
+type SharedFormat = Arc<dyn Format<Input = LogInfo> + Send + Sync>;
+
+/// Sends each entry through the format registered for its level, or through
+/// the default format (if any) for other levels. Entries without a matching
+/// format pass through unchanged.
+#[derive(Clone, Default)]
+pub struct Branch {
+    levels: IndexMap<String, SharedFormat>,
+    default: Option<SharedFormat>,
+}
+
+impl Branch {
+    pub fn new() -> Self {
+        Self {
+            levels: IndexMap::new(),
+            default: None,
+        }
+    }
+
+    pub fn with_level<F>(self, level: &str, format: F) -> Self
+    where
+        F: Format<Input = LogInfo> + Send + Sync + 'static,
+    {
+        self.with_levels([level], format)
+    }
+
+    /// Uses one format for several levels.
+    pub fn with_levels<F>(
+        mut self,
+        levels: impl IntoIterator<Item = impl Into<String>>,
+        format: F,
+    ) -> Self
+    where
+        F: Format<Input = LogInfo> + Send + Sync + 'static,
+    {
+        let format: SharedFormat = Arc::new(format);
+        for level in levels {
+            self.levels.insert(level.into(), format.clone());
+        }
+        self
+    }
+
+    pub fn with_default<F>(mut self, format: F) -> Self
+    where
+        F: Format<Input = LogInfo> + Send + Sync + 'static,
+    {
+        self.default = Some(Arc::new(format));
+        self
+    }
+}
+
+impl Branch {
+    fn format_for(&self, info: &LogInfo) -> Option<&SharedFormat> {
+        self.levels
+            .get(info.level.as_ref())
+            .or(self.default.as_ref())
+    }
+}
+
+impl Format for Branch {
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        match self.format_for(&info) {
+            Some(format) => format.transform(info),
+            None => Some(info),
+        }
+    }
+
+    fn try_transform(&self, info: LogInfo) -> Result<Option<Self::Input>, FormatError> {
+        match self.format_for(&info) {
+            Some(format) => format.try_transform(info),
+            None => Ok(Some(info)),
+        }
+    }
+
+    /// Lists the arms in the order they were added, levels that share a
+    /// format together, e.g. `branch(error, warn => JsonFormat; _ => SimpleFormat)`.
+    fn describe(&self) -> String {
+        let mut arms: Vec<(Vec<&str>, &SharedFormat)> = Vec::new();
+        for (level, format) in &self.levels {
+            match arms.iter_mut().find(|(_, arm)| Arc::ptr_eq(arm, format)) {
+                Some((levels, _)) => levels.push(level),
+                None => arms.push((vec![level], format)),
+            }
+        }
+        let mut arms: Vec<String> = arms
+            .into_iter()
+            .map(|(levels, format)| format!("{} => {}", levels.join(", "), format.describe()))
+            .collect();
+        if let Some(format) = &self.default {
+            arms.push(format!("_ => {}", format.describe()));
+        }
+        format!("branch({})", arms.join("; "))
+    }
+}
+
+pub fn branch() -> Branch {
+    Branch::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{filter, json, simple};
+
+    #[test]
+    fn test_branch_by_level() {
+        let format = branch()
+            .with_levels(["error", "warn"], json())
+            .with_level("debug", filter(|_| false))
+            .with_default(simple());
+
+        let error = format.transform(LogInfo::new("error", "Boom")).unwrap();
+        let info = format.transform(LogInfo::new("info", "Fine")).unwrap();
+
+        assert_eq!(
+            error.formatted.as_deref(),
+            Some(r#"{"level":"error","message":"Boom"}"#)
+        );
+        assert_eq!(info.formatted.as_deref(), Some("info: Fine"));
+        assert!(format.transform(LogInfo::new("debug", "noise")).is_none());
+        assert_eq!(
+            format.describe(),
+            "branch(error, warn => JsonFormat; debug => FilterFormat; _ => SimpleFormat)"
+        );
+    }
+
+    #[test]
+    fn test_branch_without_default_passes_through() {
+        let format = branch().with_level("error", json());
+        let info = format.transform(LogInfo::new("info", "Fine")).unwrap();
+
+        assert!(info.formatted.is_none());
+        assert_eq!(info.message, "Fine");
+        assert_eq!(format.describe(), "branch(error => JsonFormat)");
+    }
+}
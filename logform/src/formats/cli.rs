@@ -1,6 +1,8 @@
-use super::{colorize::Colorizer, pad_levels::Padder, Format};
+use super::{
+    colorize::Colorizer, into_string, pad_levels::Padder, simple::write_rest, Finalizer, Format,
+};
 use crate::{config, LogInfo};
-use std::collections::HashSet;
+use std::{collections::HashSet, io};
 
 #[derive(Clone)]
 pub struct CliFormat {
@@ -62,14 +64,39 @@ impl CliFormat {
     }
 
     fn transform(&self, info: LogInfo) -> Option<LogInfo> {
-        let mut transformed_info = self.padder.transform(info)?;
-        transformed_info = self.colorizer.transform(transformed_info)?;
+        let mut transformed_info = self.styled(info)?;
 
         transformed_info.message =
             format!("{}:{}", transformed_info.level, transformed_info.message);
 
+        let mut line = transformed_info.message.clone().into_bytes();
+        let line = write_rest(&transformed_info.meta, &mut line).and_then(|()| into_string(line));
+        transformed_info.formatted = Some(line.unwrap_or_else(|e| {
+            eprintln!("[logform::cli] Failed to serialize meta: {}", e);
+            transformed_info.message.clone()
+        }));
+
         Some(transformed_info)
     }
+
+    /// The entry with its message padded and level and message colored.
+    fn styled(&self, info: LogInfo) -> Option<LogInfo> {
+        let transformed_info = self.padder.transform(info)?;
+        self.colorizer.transform(transformed_info)
+    }
+}
+
+/// Writes `level:message` as `transform` styles them, then meta as JSON like
+/// `simple()`.
+impl Finalizer for CliFormat {
+    fn write_to(&self, info: &LogInfo, writer: &mut dyn io::Write) -> io::Result<()> {
+        // Only the level and message are styled, so leave the meta behind
+        let bare = LogInfo::new(info.level.clone(), info.message.as_str());
+        if let Some(styled) = self.styled(bare) {
+            write!(writer, "{}:{}", styled.level, styled.message)?;
+        }
+        write_rest(&info.meta, writer)
+    }
 }
 
 impl Format for CliFormat {
@@ -1,7 +1,12 @@
 use super::Format;
 use crate::{config, LogInfo};
 use colored::*;
-use std::{collections::HashMap, sync::Once};
+use serde_json::Value;
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    sync::{Once, OnceLock},
+};
 
 #[derive(Clone, Debug)]
 enum MixedColorType {
@@ -12,8 +17,12 @@ enum MixedColorType {
 impl MixedColorType {
     fn as_vec(&self) -> Vec<String> {
         match self {
-            MixedColorType::Single(color) => vec![color.clone()],
-            MixedColorType::Multiple(colors) => colors.clone(),
+            MixedColorType::Single(color) => color.split_whitespace().map(str::to_string).collect(),
+            MixedColorType::Multiple(colors) => colors
+                .iter()
+                .flat_map(|color| color.split_whitespace())
+                .map(str::to_string)
+                .collect(),
         }
     }
 }
@@ -32,12 +41,73 @@ impl From<Vec<String>> for MixedColorType {
 
 static INIT: Once = Once::new();
 
+thread_local! {
+    static TERMINAL: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Runs `f` knowing whether the output it formats goes to a terminal, for
+/// colorizing formats left on automatic detection. `None` means unknown and
+/// keeps colors on. `Logger` wraps each transport's formatting in this with
+/// the transport's `is_terminal()`.
+pub fn with_terminal<R>(terminal: Option<bool>, f: impl FnOnce() -> R) -> R {
+    let previous = TERMINAL.replace(terminal);
+    let result = f();
+    TERMINAL.set(previous);
+    result
+}
+
+/// `NO_COLOR` and `CLICOLOR_FORCE`, ignoring empty values. Read once per process.
+fn env_vars() -> &'static (Option<String>, Option<String>) {
+    static VARS: OnceLock<(Option<String>, Option<String>)> = OnceLock::new();
+    VARS.get_or_init(|| {
+        let var = |name| std::env::var(name).ok().filter(|value| !value.is_empty());
+        (var("NO_COLOR"), var("CLICOLOR_FORCE"))
+    })
+}
+
+/// Whether automatic detection allows colors: off when `NO_COLOR` is set, on
+/// when `CLICOLOR_FORCE` is set to anything but `0`, and otherwise off only
+/// for a destination known not to be a terminal.
+fn detect_colors(
+    no_color: Option<&str>,
+    clicolor_force: Option<&str>,
+    terminal: Option<bool>,
+) -> bool {
+    match (no_color, clicolor_force) {
+        (Some(_), _) => false,
+        (None, Some(force)) if force != "0" => true,
+        _ => terminal != Some(false),
+    }
+}
+
+pub(crate) fn colors_enabled() -> bool {
+    let (no_color, clicolor_force) = env_vars();
+    detect_colors(
+        no_color.as_deref(),
+        clicolor_force.as_deref(),
+        TERMINAL.get(),
+    )
+}
+
+/// Colors levels and messages by level, and meta values by key.
+///
+/// A color spec is a `colored` name (`red`, `bright_blue`, `on_yellow`), a
+/// style (`bold`, `italic`, `underline`, `dimmed`, ...), a hex color
+/// (`#ff8800`, `#f80`) or a 256-color palette index (`color(208)`), each
+/// usable as a background with `on_` (`on_#202020`, `on_color(236)`). Specs
+/// combine as an array or space-separated: `"bold #ff8800 on_black"`.
+///
+/// Unless forced with `with_enabled`, colors are left out when `NO_COLOR` is
+/// set or the destination is known not to be a terminal (see
+/// [`with_terminal`]); a `CLICOLOR_FORCE` other than `0` keeps them on.
 #[derive(Clone)]
 pub struct Colorizer {
     all_colors: HashMap<String, MixedColorType>,
+    meta_colors: HashMap<String, MixedColorType>,
     all: bool,
     level: bool,
     message: bool,
+    enabled: Option<bool>,
 }
 
 impl Default for Colorizer {
@@ -63,12 +133,20 @@ impl Colorizer {
 
         Self {
             all_colors,
+            meta_colors: HashMap::new(),
             all: false,
             level: true,
             message: false,
+            enabled: None,
         }
     }
 
+    /// Forces colors on or off instead of detecting support.
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
     pub fn with_all(mut self, all: bool) -> Self {
         self.all = all;
         self
@@ -100,14 +178,25 @@ impl Colorizer {
         self
     }
 
+    /// Colors the meta value at top-level `key`. Strings, numbers and booleans
+    /// are replaced by their colored text; other values are left alone.
+    pub fn with_meta_color(mut self, key: &str, color: Value) -> Self {
+        Colorizer::add_colors(&mut self.meta_colors, [(key.to_string(), color)]);
+        self
+    }
+
+    pub(crate) fn enabled(&self) -> bool {
+        self.enabled.unwrap_or_else(colors_enabled)
+    }
+
     fn add_colors<T>(all_colors: &mut HashMap<String, MixedColorType>, colors: T)
     where
         T: IntoIterator<Item = (String, serde_json::Value)>,
     {
         for (level, color_val) in colors {
             let color_entry: MixedColorType = match color_val {
-                serde_json::Value::String(color_str) => color_str.into(),
-                serde_json::Value::Array(color_arr) => color_arr
+                Value::String(color_str) => color_str.into(),
+                Value::Array(color_arr) => color_arr
                     .into_iter()
                     .filter_map(|v| v.as_str().map(|s| s.to_string()))
                     .collect::<Vec<_>>()
@@ -121,22 +210,41 @@ impl Colorizer {
         }
     }
 
-    fn colorize(&self, level: &str, message: &str) -> String {
-        if let Some(color_entry) = self.all_colors.get(level) {
-            color_entry
+    fn paint(colors: Option<&MixedColorType>, text: &str) -> String {
+        match colors {
+            Some(colors) => colors
                 .as_vec()
                 .iter()
-                .fold(message.normal(), |msg, color| apply_color(msg, color))
-                .to_string()
-        } else {
-            message.to_string()
+                .fold(text.normal(), |styled, color| apply_color(styled, color))
+                .to_string(),
+            None => text.to_string(),
+        }
+    }
+
+    pub(crate) fn colorize(&self, level: &str, message: &str) -> String {
+        if !self.enabled() {
+            return message.to_string();
         }
+        Self::paint(self.all_colors.get(level), message)
     }
 
     fn transform(&self, mut info: LogInfo) -> Option<LogInfo> {
+        if !self.enabled() {
+            return Some(info);
+        }
+        for (key, colors) in &self.meta_colors {
+            if let Some(value) = info.meta.get_mut(key) {
+                let text = match value {
+                    Value::String(s) => s.clone(),
+                    Value::Number(_) | Value::Bool(_) => value.to_string(),
+                    _ => continue,
+                };
+                *value = Value::String(Self::paint(Some(colors), &text));
+            }
+        }
         let original_level = info.level.clone();
         if self.all || self.level {
-            info.level = self.colorize(&original_level, &info.level);
+            info.level = self.colorize(&original_level, &info.level).into();
         }
         if self.all || self.message {
             info.message = self.colorize(&original_level, &info.message);
@@ -153,8 +261,17 @@ impl Format for Colorizer {
     }
 }
 
-fn apply_color(message: impl Into<colored::ColoredString>, color: &str) -> colored::ColoredString {
+pub(crate) fn apply_color(
+    message: impl Into<colored::ColoredString>,
+    color: &str,
+) -> colored::ColoredString {
     let message = message.into();
+    if let Some(color) = color.strip_prefix("on_").and_then(parse_color) {
+        return message.on_color(color);
+    }
+    if let Some(color) = parse_color(color) {
+        return message.color(color);
+    }
     match color {
         "black" => message.black(),
         "red" => message.red(),
@@ -200,6 +317,75 @@ fn apply_color(message: impl Into<colored::ColoredString>, color: &str) -> color
     }
 }
 
+/// Parses `#rrggbb`, `#rgb` and `color(N)` specs.
+fn parse_color(spec: &str) -> Option<Color> {
+    if let Some(hex) = spec.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+        let (r, g, b) = match hex.len() {
+            6 => (
+                channel(&hex[0..2])?,
+                channel(&hex[2..4])?,
+                channel(&hex[4..6])?,
+            ),
+            3 => (
+                channel(&hex[0..1])? * 17,
+                channel(&hex[1..2])? * 17,
+                channel(&hex[2..3])? * 17,
+            ),
+            _ => return None,
+        };
+        return Some(Color::TrueColor { r, g, b });
+    }
+    let index = spec.strip_prefix("color(")?.strip_suffix(')')?;
+    index.trim().parse().ok().map(palette_color)
+}
+
+/// The xterm 256-color palette: the 16 named colors, a 6×6×6 color cube and
+/// a 24-step gray ramp.
+fn palette_color(index: u8) -> Color {
+    const NAMED: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+        Color::BrightBlack,
+        Color::BrightRed,
+        Color::BrightGreen,
+        Color::BrightYellow,
+        Color::BrightBlue,
+        Color::BrightMagenta,
+        Color::BrightCyan,
+        Color::BrightWhite,
+    ];
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match index {
+        0..=15 => NAMED[index as usize],
+        16..=231 => {
+            let cube = index - 16;
+            Color::TrueColor {
+                r: LEVELS[(cube / 36) as usize],
+                g: LEVELS[(cube / 6 % 6) as usize],
+                b: LEVELS[(cube % 6) as usize],
+            }
+        }
+        _ => {
+            let gray = 8 + 10 * (index - 232);
+            Color::TrueColor {
+                r: gray,
+                g: gray,
+                b: gray,
+            }
+        }
+    }
+}
+
 pub fn colorize() -> Colorizer {
     Colorizer::new()
 }
@@ -254,4 +440,106 @@ mod tests {
             "Warning message should be colorized"
         );
     }
+
+    #[test]
+    fn test_colorizer_color_specs() {
+        assert!(matches!(
+            parse_color("#ff8800"),
+            Some(Color::TrueColor {
+                r: 255,
+                g: 136,
+                b: 0
+            })
+        ));
+        assert!(matches!(
+            parse_color("#0f8"),
+            Some(Color::TrueColor {
+                r: 0,
+                g: 255,
+                b: 136
+            })
+        ));
+        assert!(matches!(parse_color("color(9)"), Some(Color::BrightRed)));
+        assert!(matches!(
+            parse_color("color(208)"),
+            Some(Color::TrueColor {
+                r: 255,
+                g: 135,
+                b: 0
+            })
+        ));
+        assert!(matches!(
+            parse_color("color(244)"),
+            Some(Color::TrueColor {
+                r: 128,
+                g: 128,
+                b: 128
+            })
+        ));
+        assert!(parse_color("#ff88").is_none());
+        assert!(parse_color("#+f+").is_none());
+        assert!(parse_color("color(256)").is_none());
+
+        let colorizer = Colorizer::new()
+            .with_enabled(true)
+            .with_color("info", json!("bold italic on_color(236)"));
+        let level = colorizer.colorize("info", "info");
+        assert!(level.contains("\x1b[") && level.contains('1') && level.contains('3'));
+    }
+
+    #[test]
+    fn test_colorizer_meta_colors() {
+        let colorizer = Colorizer::new()
+            .with_enabled(true)
+            .with_level(false)
+            .with_meta_color("status", json!(["green", "bold"]))
+            .with_meta_color("user", json!("#00afff"));
+        let info = LogInfo::new("info", "Request")
+            .with_meta("status", 200)
+            .with_meta("user", json!({"id": 1}))
+            .with_meta("path", "/");
+
+        let result = colorizer.transform(info).unwrap();
+        assert_eq!(result.meta["status"], json!("\x1b[1;32m200\x1b[0m"));
+        assert_eq!(result.meta["user"], json!({"id": 1}));
+        assert_eq!(result.meta["path"], json!("/"));
+        assert_eq!(result.level, "info");
+    }
+
+    #[test]
+    fn test_colorizer_detection() {
+        let cases = [
+            // (NO_COLOR, CLICOLOR_FORCE, terminal, colors)
+            (Some("1"), Some("1"), Some(true), false),
+            (Some("1"), None, None, false),
+            (None, Some("1"), Some(false), true),
+            (None, Some("0"), Some(false), false),
+            (None, Some("0"), Some(true), true),
+            (None, None, Some(false), false),
+            (None, None, Some(true), true),
+            (None, None, None, true),
+        ];
+        for (no_color, clicolor_force, terminal, expected) in cases {
+            assert_eq!(
+                detect_colors(no_color, clicolor_force, terminal),
+                expected,
+                "NO_COLOR={no_color:?} CLICOLOR_FORCE={clicolor_force:?} terminal={terminal:?}"
+            );
+        }
+
+        assert_eq!(with_terminal(Some(false), || TERMINAL.get()), Some(false));
+        assert_eq!(TERMINAL.get(), None);
+
+        let info = LogInfo::new("error", "Disk full");
+        let forced = with_terminal(Some(false), || {
+            colorize()
+                .with_enabled(true)
+                .transform(info.clone())
+                .unwrap()
+        });
+        assert!(forced.level.contains("\x1b["));
+
+        let disabled = colorize().with_enabled(false).transform(info).unwrap();
+        assert_eq!(disabled.level, "error");
+    }
 }
@@ -0,0 +1,230 @@
+use super::Format;
+use crate::{parse::CsvParser, path::FieldPath, LogInfo};
+use serde_json::Value;
+
+#[derive(Clone)]
+pub(crate) enum Column {
+    Level,
+    Message,
+    Timestamp,
+    Meta(FieldPath),
+}
+
+impl Column {
+    /// Parses a column path: `level`, `message`, `timestamp`, or a meta path
+    /// such as `user.id` (a leading `meta.` is optional).
+    pub(crate) fn parse(path: &str) -> Result<Self, String> {
+        match path {
+            "level" => Ok(Column::Level),
+            "message" => Ok(Column::Message),
+            "timestamp" => Ok(Column::Timestamp),
+            _ => {
+                let parsed = FieldPath::parse(path.strip_prefix("meta.").unwrap_or(path))?;
+                if parsed.has_wildcards() {
+                    return Err(format!("Column '{}' has wildcards", path));
+                }
+                Ok(Column::Meta(parsed))
+            }
+        }
+    }
+}
+
+/// Writes `field` to `out`, quoted as RFC 4180 requires: when it holds the
+/// delimiter, a quote or a line break, with quotes doubled.
+pub(crate) fn push_field(out: &mut String, field: &str, delimiter: char) {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        out.push('"');
+        out.push_str(&field.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(field);
+    }
+}
+
+/// Writes one delimited line per entry with a fixed, ordered set of columns,
+/// for exports that spreadsheets and audit tools read.
+///
+/// Columns are `level`, `message`, `timestamp` (the `timestamp` meta value,
+/// else the entry's time in RFC 3339) or meta paths in
+/// [`FieldPath`](crate::FieldPath) syntax (`user.id`, `items[0].sku`). Missing values and nulls
+/// are empty, strings are written as-is and other values as JSON. Fields are
+/// quoted per RFC 4180; lines end with the transport's `\n`, so a message with
+/// line breaks spans several physical lines inside its quotes.
+///
+/// Defaults to `timestamp,level,message` separated by commas; `header()` gives
+/// the matching header line and `parser()` reads lines back.
+#[derive(Clone)]
+pub struct CsvFormat {
+    columns: Vec<(String, Column)>,
+    delimiter: char,
+}
+
+impl Default for CsvFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvFormat {
+    pub fn new() -> Self {
+        Self {
+            columns: Vec::new(),
+            delimiter: ',',
+        }
+        .with_columns(&["timestamp", "level", "message"])
+    }
+
+    /// Replaces the columns. Invalid paths, and paths with wildcards, are
+    /// reported and skipped.
+    pub fn with_columns(mut self, columns: &[&str]) -> Self {
+        self.columns = columns
+            .iter()
+            .filter_map(|path| match Column::parse(path) {
+                Ok(column) => Some((path.to_string(), column)),
+                Err(e) => {
+                    eprintln!("[logform::csv] Ignoring column: {}", e);
+                    None
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Defaults to `,`; use `'\t'` for TSV.
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// The header line naming the columns, without a line break.
+    pub fn header(&self) -> String {
+        let mut header = String::new();
+        for (index, (name, _)) in self.columns.iter().enumerate() {
+            if index > 0 {
+                header.push(self.delimiter);
+            }
+            push_field(&mut header, name, self.delimiter);
+        }
+        header
+    }
+
+    /// A parser for the lines this format writes, for `FileTransport` queries.
+    pub fn parser(&self) -> CsvParser {
+        let columns: Vec<&str> = self.columns.iter().map(|(name, _)| name.as_str()).collect();
+        CsvParser::new(&columns).with_delimiter(self.delimiter)
+    }
+
+    fn field(column: &Column, info: &LogInfo) -> String {
+        let text = |value: &Value| match value {
+            Value::String(s) => s.clone(),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        };
+        match column {
+            Column::Level => info.level.to_string(),
+            Column::Message => info.message.clone(),
+            Column::Timestamp => match info.meta.get("timestamp") {
+                Some(value) => text(value),
+                None => info
+                    .timestamp
+                    .as_ref()
+                    .map(LogInfo::format_timestamp)
+                    .unwrap_or_default(),
+            },
+            Column::Meta(path) => path
+                .resolve(&info.meta)
+                .first()
+                .map_or(String::new(), |v| text(v)),
+        }
+    }
+
+    pub fn transform(&self, info: LogInfo) -> Option<LogInfo> {
+        let mut line = String::new();
+        for (index, (_, column)) in self.columns.iter().enumerate() {
+            if index > 0 {
+                line.push(self.delimiter);
+            }
+            push_field(&mut line, &Self::field(column, &info), self.delimiter);
+        }
+        Some(LogInfo {
+            formatted: Some(line),
+            ..info
+        })
+    }
+}
+
+impl Format for CsvFormat {
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        self.transform(info)
+    }
+}
+
+pub fn csv() -> CsvFormat {
+    CsvFormat::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Parser;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    #[test]
+    fn test_csv_columns_and_quoting() {
+        let format = csv().with_columns(&[
+            "timestamp",
+            "level",
+            "message",
+            "user.id",
+            "meta.tags",
+            "missing",
+            "a[*]",
+        ]);
+        assert_eq!(
+            format.header(),
+            "timestamp,level,message,user.id,meta.tags,missing"
+        );
+
+        let mut info = LogInfo::new("info", "Said \"hi\", then\nleft")
+            .with_meta("user", json!({"id": 7}))
+            .with_meta("tags", json!(["a", "b"]));
+        info.timestamp = Some(Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap());
+
+        let result = format.transform(info).unwrap();
+        assert_eq!(
+            result.formatted.unwrap(),
+            "2024-05-01T12:00:00Z,info,\"Said \"\"hi\"\", then\nleft\",7,\"[\"\"a\"\",\"\"b\"\"]\","
+        );
+    }
+
+    #[test]
+    fn test_csv_tsv_round_trip() {
+        let format = csv()
+            .with_columns(&["timestamp", "level", "message", "http.status", "user"])
+            .with_delimiter('\t');
+        let info = LogInfo::new("warn", "Slow\trequest")
+            .with_meta("timestamp", "2024-05-01T12:00:00Z")
+            .with_meta("http", json!({"status": 503}))
+            .with_meta("user", "bob");
+
+        let line = format.transform(info).unwrap().formatted.unwrap();
+        assert_eq!(
+            line,
+            "2024-05-01T12:00:00Z\twarn\t\"Slow\trequest\"\t503\tbob"
+        );
+
+        let parsed = format.parser().parse(&line).unwrap();
+        assert_eq!(parsed.level, "warn");
+        assert_eq!(parsed.message, "Slow\trequest");
+        assert_eq!(parsed.meta["http"], json!({"status": 503}));
+        assert_eq!(parsed.meta["user"], json!("bob"));
+        assert_eq!(
+            parsed.timestamp,
+            Some(Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap())
+        );
+        assert!(format.parser().is_header(&format.header()));
+    }
+}
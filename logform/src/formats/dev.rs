@@ -0,0 +1,346 @@
+use super::{colorize::Colorizer, Format};
+use crate::{config, utils::format_json::format_json_consistently, LogInfo};
+use chrono::Local;
+use colored::Colorize;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// Meta keys rendered in the header or handled specially rather than listed.
+const RESERVED: [&str; 8] = [
+    "timestamp",
+    "label",
+    "target",
+    "error",
+    "stack",
+    "splat",
+    "padding",
+    "level",
+];
+
+/// Renders entries for reading in a terminal during development:
+///
+/// ```text
+/// 12:04:31.207 info  [api] app::db: Connected
+///     pool: 8
+///     peer: {
+///       host: 'db-1',
+///       port: 5432
+///     }
+/// 12:04:32.911 error [api] Query failed
+///     error: connection reset (std::io::Error)
+///       caused by: broken pipe
+/// ```
+///
+/// The header holds the time (a `timestamp` meta value as-is, else the
+/// entry's time in local time), the level colored and padded to the longest
+/// known level, `[label]` and `target:` when present, and the message, whose
+/// further lines are indented under its first. Other meta follows one key per
+/// line, then the error recorded by [`LogInfo::with_error`] with its causes and
+/// backtrace, and a `stack` set by `errors().with_text(true)`.
+///
+/// Colors follow `colorize()`: off for non-terminals or with `NO_COLOR`, unless
+/// forced with `with_colorize`.
+#[derive(Clone)]
+pub struct DevFormat {
+    colorizer: Colorizer,
+    level_width: usize,
+    time_format: String,
+    label: bool,
+    target: bool,
+}
+
+impl Default for DevFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DevFormat {
+    pub fn new() -> Self {
+        Self {
+            colorizer: Colorizer::new(),
+            level_width: Self::longest(config::rust::levels().into_keys()),
+            time_format: "%H:%M:%S%.3f".to_string(),
+            label: true,
+            target: true,
+        }
+    }
+
+    fn longest(levels: impl IntoIterator<Item = impl Into<String>>) -> usize {
+        let levels: HashSet<String> = levels.into_iter().map(Into::into).collect();
+        levels
+            .iter()
+            .map(|level| level.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Levels the level column is padded for (default: the `rust` levels).
+    pub fn with_levels(mut self, levels: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.level_width = Self::longest(levels);
+        self
+    }
+
+    /// A `chrono` format string for the entry's time (default `%H:%M:%S%.3f`).
+    pub fn with_time_format(mut self, format: &str) -> Self {
+        self.time_format = format.to_string();
+        self
+    }
+
+    /// Colors used for levels (default: the `colorize()` defaults).
+    pub fn with_colorizer(mut self, colorizer: Colorizer) -> Self {
+        self.colorizer = colorizer;
+        self
+    }
+
+    /// Forces colors on or off instead of detecting support.
+    pub fn with_colorize(mut self, colorize: bool) -> Self {
+        self.colorizer = self.colorizer.with_enabled(colorize);
+        self
+    }
+
+    /// Whether to show the `label` meta value in the header (default `true`).
+    pub fn with_label(mut self, label: bool) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Whether to show the `target` meta value in the header (default `true`).
+    pub fn with_target(mut self, target: bool) -> Self {
+        self.target = target;
+        self
+    }
+
+    fn text(value: &Value) -> String {
+        match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    fn header(&self, info: &LogInfo, colors: bool) -> (String, usize) {
+        let mut header = String::new();
+        let mut width = 0;
+        let mut push = |plain: String, styled: String| {
+            width += plain.chars().count() + 1;
+            header.push_str(&styled);
+            header.push(' ');
+        };
+
+        let time = match info.meta.get("timestamp") {
+            Some(value) => Some(Self::text(value)),
+            None => info.timestamp.map(|time| {
+                time.with_timezone(&Local)
+                    .format(&self.time_format)
+                    .to_string()
+            }),
+        };
+        if let Some(time) = time {
+            let styled = if colors {
+                time.dimmed().to_string()
+            } else {
+                time.clone()
+            };
+            push(time, styled);
+        }
+
+        let level = format!("{:<1$}", info.level, self.level_width);
+        let styled = if colors {
+            let padding = &level[info.level.len()..];
+            format!(
+                "{}{}",
+                self.colorizer.colorize(&info.level, &info.level),
+                padding
+            )
+        } else {
+            level.clone()
+        };
+        push(level, styled);
+
+        if let Some(label) = info.meta.get("label").filter(|_| self.label) {
+            let label = format!("[{}]", Self::text(label));
+            let styled = if colors {
+                label.bold().to_string()
+            } else {
+                label.clone()
+            };
+            push(label, styled);
+        }
+        if let Some(target) = info.meta.get("target").filter(|_| self.target) {
+            let target = format!("{}:", Self::text(target));
+            let styled = if colors {
+                target.dimmed().to_string()
+            } else {
+                target.clone()
+            };
+            push(target, styled);
+        }
+        (header, width)
+    }
+
+    fn push_meta(out: &mut String, key: &str, value: &Value, colors: bool) {
+        let key = if colors {
+            key.cyan().to_string()
+        } else {
+            key.to_string()
+        };
+        let value = format_json_consistently(value, 4, colors);
+        out.push_str(&format!("\n    {}: {}", key, value.trim()));
+    }
+
+    fn push_error(out: &mut String, error: &Map<String, Value>, colors: bool) {
+        let message = error.get("message").map(Self::text).unwrap_or_default();
+        let mut line = match error.get("type").and_then(Value::as_str) {
+            Some(kind) => format!("{} ({})", message, kind),
+            None => message,
+        };
+        if colors {
+            line = line.red().to_string();
+        }
+        out.push_str(&format!("\n    error: {}", line));
+
+        if let Some(Value::Array(chain)) = error.get("chain") {
+            for cause in chain {
+                out.push_str(&format!("\n      caused by: {}", Self::text(cause)));
+            }
+        }
+        if let Some(backtrace) = error.get("backtrace") {
+            Self::push_block(out, "backtrace", &Self::text(backtrace), colors);
+        }
+    }
+
+    fn push_block(out: &mut String, name: &str, text: &str, colors: bool) {
+        out.push_str(&format!("\n      {}:", name));
+        for line in text.lines() {
+            let line = if colors {
+                line.dimmed().to_string()
+            } else {
+                line.to_string()
+            };
+            out.push_str(&format!("\n        {}", line));
+        }
+    }
+
+    pub fn transform(&self, info: LogInfo) -> Option<LogInfo> {
+        let colors = self.colorizer.enabled();
+        let (mut out, width) = self.header(&info, colors);
+
+        let indent = " ".repeat(width);
+        for (index, line) in info.message.lines().enumerate() {
+            if index > 0 {
+                out.push('\n');
+                out.push_str(&indent);
+            }
+            out.push_str(line);
+        }
+        let out_len = out.trim_end().len();
+        out.truncate(out_len);
+
+        for (key, value) in &info.meta {
+            if RESERVED.contains(&key.as_str()) || key.as_str() == "message" {
+                continue;
+            }
+            Self::push_meta(&mut out, key, value, colors);
+        }
+        match info.meta.get("error") {
+            Some(Value::Object(error)) => Self::push_error(&mut out, error, colors),
+            Some(other) => Self::push_meta(&mut out, "error", other, colors),
+            None => {}
+        }
+        if let Some(stack) = info.meta.get("stack") {
+            Self::push_block(&mut out, "stack", &Self::text(stack), colors);
+        }
+
+        Some(LogInfo {
+            formatted: Some(out),
+            ..info
+        })
+    }
+}
+
+impl Format for DevFormat {
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        self.transform(info)
+    }
+}
+
+pub fn dev() -> DevFormat {
+    DevFormat::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::uncolorize::strip_colors;
+    use serde_json::json;
+
+    #[test]
+    fn test_dev_format_plain() {
+        let info = LogInfo::new("info", "Connected\nretried twice")
+            .with_meta("timestamp", "12:04:31.207")
+            .with_meta("label", "api")
+            .with_meta("target", "app::db")
+            .with_meta("pool", 8)
+            .with_meta("peer", json!({"host": "db-1", "port": 5432}));
+
+        let result = dev().with_colorize(false).transform(info).unwrap();
+        let lines = [
+            "12:04:31.207 info  [api] app::db: Connected".to_string(),
+            format!("{}retried twice", " ".repeat(34)),
+            "    pool: 8".to_string(),
+            "    peer: {".to_string(),
+            "      host: 'db-1',".to_string(),
+            "      port: 5432".to_string(),
+            "    }".to_string(),
+        ];
+        assert_eq!(result.formatted.unwrap(), lines.join("\n"));
+    }
+
+    #[test]
+    fn test_dev_format_error_chain() {
+        let info = LogInfo::new("error", "Query failed")
+            .with_meta(
+                "error",
+                json!({
+                    "message": "connection reset",
+                    "type": "std::io::Error",
+                    "chain": ["broken pipe"],
+                    "backtrace": "0: main\n1: start"
+                }),
+            )
+            .with_meta("stack", "at a\nat b");
+
+        let result = dev()
+            .with_levels(["error", "info"])
+            .with_colorize(false)
+            .transform(info)
+            .unwrap();
+        assert_eq!(
+            result.formatted.unwrap(),
+            "error Query failed\n\
+             \x20   error: connection reset (std::io::Error)\n\
+             \x20     caused by: broken pipe\n\
+             \x20     backtrace:\n\
+             \x20       0: main\n\
+             \x20       1: start\n\
+             \x20     stack:\n\
+             \x20       at a\n\
+             \x20       at b"
+        );
+    }
+
+    #[test]
+    fn test_dev_format_colors() {
+        let info = LogInfo::new("warn", "Slow query").with_meta("ms", 1200);
+        let result = dev()
+            .with_label(false)
+            .with_colorize(true)
+            .transform(info.with_meta("label", "api"))
+            .unwrap();
+        let formatted = result.formatted.unwrap();
+        assert!(formatted.contains("\x1b[33mwarn\x1b[0m"));
+        assert_eq!(strip_colors(&formatted), "warn  Slow query\n    ms: 1200");
+    }
+}
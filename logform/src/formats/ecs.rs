@@ -0,0 +1,235 @@
+use super::{formatted, FormatError};
+use crate::{utils::host::hostname, LogInfo};
+use serde_json::{Map, Value};
+
+/// ECS version the emitted documents declare in `ecs.version`.
+pub const ECS_VERSION: &str = "8.11.0";
+
+/// Renders entries as Elastic Common Schema JSON documents.
+///
+/// The entry maps onto `@timestamp`, `log.level`, `message` and `ecs.version`,
+/// plus `host.hostname`, `process.pid` and (when set) `service.name`. The
+/// `target`, `file` and `line` meta keys set by the `log` backend and
+/// `winston_tracing` become `log.logger` and `log.origin.file.name/line`, and an
+/// `error` object recorded by [`LogInfo::with_error`] becomes `error.message`,
+/// `error.type` and `error.stack_trace`. Remaining meta is kept, with dotted
+/// keys (`http.request.method`) nested into objects.
+#[derive(Clone)]
+pub struct EcsFormat {
+    service_name: Option<String>,
+    hostname: String,
+}
+
+impl Default for EcsFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EcsFormat {
+    pub fn new() -> Self {
+        Self {
+            service_name: None,
+            hostname: hostname().to_string(),
+        }
+    }
+
+    pub fn with_service_name(mut self, name: &str) -> Self {
+        self.service_name = Some(name.to_string());
+        self
+    }
+
+    /// Overrides `host.hostname` (default: this machine's hostname).
+    pub fn with_hostname(mut self, hostname: &str) -> Self {
+        self.hostname = hostname.to_string();
+        self
+    }
+
+    /// Inserts `value` at the dotted `path`, merging into existing objects.
+    /// If a non-object value is in the way, the dotted key is kept flat.
+    fn insert_path(object: &mut Map<String, Value>, path: &str, value: Value) {
+        match path.split_once('.') {
+            Some((head, rest)) if !head.is_empty() && !rest.is_empty() => {
+                let child = object
+                    .entry(head.to_string())
+                    .or_insert_with(|| Value::Object(Map::new()));
+                match child {
+                    Value::Object(child) => Self::insert_path(child, rest, value),
+                    _ => {
+                        object.insert(path.to_string(), value);
+                    }
+                }
+            }
+            _ => match (object.get_mut(path), value) {
+                (Some(Value::Object(existing)), Value::Object(value)) => {
+                    for (key, value) in value {
+                        Self::insert_path(existing, &key, value);
+                    }
+                }
+                (_, value) => {
+                    object.insert(path.to_string(), value);
+                }
+            },
+        }
+    }
+
+    fn insert_error(doc: &mut Map<String, Value>, error: Value) {
+        match error {
+            Value::Object(mut error) => {
+                if let Some(backtrace) = error.remove("backtrace") {
+                    error.insert("stack_trace".to_string(), backtrace);
+                }
+                Self::insert_path(doc, "error", Value::Object(error));
+            }
+            Value::String(message) => {
+                Self::insert_path(doc, "error.message", Value::String(message));
+            }
+            other => Self::insert_path(doc, "error", other),
+        }
+    }
+
+    pub fn try_transform(&self, info: LogInfo) -> Result<Option<LogInfo>, FormatError> {
+        let timestamp = info.time();
+
+        let mut doc = Map::new();
+        doc.insert(
+            "@timestamp".to_string(),
+            Value::String(LogInfo::format_timestamp(&timestamp)),
+        );
+        Self::insert_path(&mut doc, "log.level", Value::from(info.level.as_ref()));
+        doc.insert("message".to_string(), Value::from(info.message.as_str()));
+        Self::insert_path(&mut doc, "ecs.version", Value::from(ECS_VERSION));
+        if let Some(service_name) = &self.service_name {
+            Self::insert_path(&mut doc, "service.name", Value::from(service_name.as_str()));
+        }
+        Self::insert_path(
+            &mut doc,
+            "host.hostname",
+            Value::from(self.hostname.as_str()),
+        );
+        Self::insert_path(&mut doc, "process.pid", Value::from(std::process::id()));
+
+        for (key, value) in info.meta.iter() {
+            let value = value.clone();
+            match key.as_ref() {
+                "timestamp" => {}
+                "target" => Self::insert_path(&mut doc, "log.logger", value),
+                "file" => Self::insert_path(&mut doc, "log.origin.file.name", value),
+                "line" => Self::insert_path(&mut doc, "log.origin.file.line", value),
+                "error" => Self::insert_error(&mut doc, value),
+                key => Self::insert_path(&mut doc, key, value),
+            }
+        }
+
+        let output = serde_json::to_string(&doc);
+        formatted("EcsFormat", info, output)
+    }
+}
+
+fallible_format!(EcsFormat);
+
+pub fn ecs() -> EcsFormat {
+    EcsFormat::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Format;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    fn render(format: &EcsFormat, info: LogInfo) -> Value {
+        let result = format.transform(info).unwrap();
+        serde_json::from_str(result.formatted.as_deref().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_ecs_format() {
+        let time = Utc.with_ymd_and_hms(2024, 4, 1, 12, 30, 0).unwrap();
+        let info = LogInfo::new("warn", "Disk almost full")
+            .with_timestamp(time)
+            .with_meta("target", "app::disk")
+            .with_meta("file", "src/disk.rs")
+            .with_meta("line", 42);
+
+        let parsed = render(&ecs().with_service_name("api").with_hostname("web-1"), info);
+        assert_eq!(
+            parsed,
+            json!({
+                "@timestamp": "2024-04-01T12:30:00Z",
+                "log": {
+                    "level": "warn",
+                    "logger": "app::disk",
+                    "origin": {"file": {"name": "src/disk.rs", "line": 42}}
+                },
+                "message": "Disk almost full",
+                "ecs": {"version": ECS_VERSION},
+                "service": {"name": "api"},
+                "host": {"hostname": "web-1"},
+                "process": {"pid": std::process::id()}
+            })
+        );
+    }
+
+    #[test]
+    fn test_ecs_maps_error_object() {
+        let info = LogInfo::new("error", "Request failed").with_meta(
+            "error",
+            json!({
+                "message": "connection refused",
+                "type": "std::io::error::Error",
+                "chain": ["os error 111"],
+                "backtrace": "0: main"
+            }),
+        );
+
+        let parsed = render(&ecs(), info);
+        assert_eq!(parsed["error"]["message"], "connection refused");
+        assert_eq!(parsed["error"]["type"], "std::io::error::Error");
+        assert_eq!(parsed["error"]["stack_trace"], "0: main");
+        assert!(parsed["error"].get("backtrace").is_none());
+    }
+
+    #[test]
+    fn test_ecs_nests_dotted_keys() {
+        let info = LogInfo::new("info", "Handled")
+            .with_meta("http.request.method", "GET")
+            .with_meta("http.response.status_code", 200)
+            .with_meta("http", json!({"version": "1.1"}))
+            .with_meta("user", "bob")
+            .with_meta("user.id", 7)
+            .with_meta("timestamp", "2024-04-01T12:30:00.5Z");
+
+        let parsed = render(&ecs(), info);
+        assert_eq!(
+            parsed["http"],
+            json!({
+                "request": {"method": "GET"},
+                "response": {"status_code": 200},
+                "version": "1.1"
+            })
+        );
+        assert_eq!(parsed["user"], "bob");
+        assert_eq!(parsed["user.id"], 7);
+        assert_eq!(parsed["@timestamp"], "2024-04-01T12:30:00.500Z");
+        assert!(parsed.get("timestamp").is_none());
+    }
+
+    #[test]
+    fn test_ecs_keeps_meta_on_the_entry() {
+        let info = LogInfo::new("info", "Handled")
+            .with_meta("target", "app")
+            .with_meta("user", "bob")
+            .with_meta("timestamp", "2024-04-01T12:30:00Z");
+
+        let result = ecs().transform(info).unwrap();
+        assert_eq!(result.meta.get("target"), Some(&json!("app")));
+        assert_eq!(result.meta.get("user"), Some(&json!("bob")));
+        assert_eq!(
+            result.meta.get("timestamp"),
+            Some(&json!("2024-04-01T12:30:00Z"))
+        );
+        assert!(result.formatted.is_some());
+    }
+}
@@ -0,0 +1,175 @@
+use super::Format;
+use crate::{
+    utils::host::{exe_name, hostname},
+    LogInfo,
+};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// The thread's name and id. std's `ThreadId` has no stable numeric form,
+    /// so the id is a number handed out the first time a thread is captured:
+    /// unique within the process, but not the one `ThreadId`'s Debug prints.
+    static THREAD: (Option<String>, u64) = (
+        std::thread::current().name().map(str::to_string),
+        NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed),
+    );
+}
+
+/// Records the current thread as `thread_name` (if the thread is named) and
+/// `thread_id` in meta.
+///
+/// `Logger` applies formats on its worker thread, so register this as the
+/// logger's call-site hook for `enrich()` to report the logging thread.
+pub fn capture_thread(info: &mut LogInfo) {
+    THREAD.with(|(name, id)| {
+        if let Some(name) = name {
+            info.meta.insert("thread_name", Value::String(name.clone()));
+        }
+        info.meta.insert("thread_id", Value::from(*id));
+    });
+}
+
+/// Adds host and process details plus static service attributes to meta:
+/// `hostname`, `pid`, `exe`, and `service`, `version` and `env` when set. Keys
+/// already in meta are left alone. Everything is computed once, when the
+/// format is built.
+///
+/// Thread details come from [`capture_thread`], run where the entry is logged.
+/// With [`with_thread(true)`](Self::with_thread), entries that weren't captured
+/// get the thread running the format, which is only the logging thread when
+/// the format runs outside a `Logger`.
+#[derive(Clone)]
+pub struct EnrichFormat {
+    fields: Vec<(&'static str, Value)>,
+    thread: bool,
+}
+
+impl Default for EnrichFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EnrichFormat {
+    pub fn new() -> Self {
+        Self {
+            fields: vec![
+                ("hostname", Value::String(hostname().to_string())),
+                ("pid", Value::from(std::process::id())),
+                ("exe", Value::String(exe_name().to_string())),
+            ],
+            thread: false,
+        }
+    }
+
+    fn with_field(mut self, key: &'static str, value: &str) -> Self {
+        self.fields.retain(|(k, _)| *k != key);
+        self.fields.push((key, Value::String(value.to_string())));
+        self
+    }
+
+    pub fn with_service(self, service: &str) -> Self {
+        self.with_field("service", service)
+    }
+
+    pub fn with_version(self, version: &str) -> Self {
+        self.with_field("version", version)
+    }
+
+    pub fn with_env(self, env: &str) -> Self {
+        self.with_field("env", env)
+    }
+
+    /// Overrides the detected hostname.
+    pub fn with_hostname(self, hostname: &str) -> Self {
+        self.with_field("hostname", hostname)
+    }
+
+    /// Whether to record the thread running the format on entries that have
+    /// no captured thread (default `false`).
+    pub fn with_thread(mut self, thread: bool) -> Self {
+        self.thread = thread;
+        self
+    }
+
+    pub fn transform(&self, mut info: LogInfo) -> Option<LogInfo> {
+        for (key, value) in &self.fields {
+            if !info.meta.contains_key(key) {
+                info.meta.insert(*key, value.clone());
+            }
+        }
+        if self.thread && !info.meta.contains_key("thread_id") {
+            capture_thread(&mut info);
+        }
+        Some(info)
+    }
+}
+
+impl Format for EnrichFormat {
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        self.transform(info)
+    }
+}
+
+pub fn enrich() -> EnrichFormat {
+    EnrichFormat::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_enrich_format() {
+        let format = enrich()
+            .with_service("checkout")
+            .with_version("1.4.2")
+            .with_env("prod")
+            .with_hostname("web-1");
+        let info = LogInfo::new("info", "Order placed").with_meta("env", "staging");
+
+        let result = format.transform(info).unwrap();
+        assert_eq!(result.meta["hostname"], json!("web-1"));
+        assert_eq!(result.meta["pid"], json!(std::process::id()));
+        assert_eq!(result.meta["exe"], json!(exe_name()));
+        assert_eq!(result.meta["service"], json!("checkout"));
+        assert_eq!(result.meta["version"], json!("1.4.2"));
+        assert_eq!(result.meta["env"], json!("staging"));
+        assert!(!result.meta.contains_key("thread_id"));
+
+        let result = enrich()
+            .with_thread(true)
+            .transform(LogInfo::new("info", "x"))
+            .unwrap();
+        assert!(result.meta["thread_id"].is_u64());
+    }
+
+    #[test]
+    fn test_enrich_keeps_captured_thread() {
+        let mut info = LogInfo::new("info", "From a worker");
+        let captured = std::thread::Builder::new()
+            .name("worker-7".to_string())
+            .spawn(move || {
+                capture_thread(&mut info);
+                info
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+
+        let id = captured.meta["thread_id"].clone();
+        let mut here = LogInfo::new("info", "here");
+        capture_thread(&mut here);
+        assert_ne!(here.meta["thread_id"], id);
+
+        let result = enrich().with_thread(true).transform(captured).unwrap();
+        assert_eq!(result.meta["thread_name"], json!("worker-7"));
+        assert_eq!(result.meta["thread_id"], id);
+    }
+}
@@ -0,0 +1,159 @@
+use super::Format;
+use crate::LogInfo;
+use serde_json::Value;
+
+/// Handles the structured `error` object recorded by [`LogInfo::with_error`].
+///
+/// By default the object is kept structured (for `json()` and friends), an empty
+/// `message` is filled from the error's message, and the backtrace is dropped
+/// unless `with_stack(true)` is set. With `with_text(true)` the error is rendered
+/// into the message instead (`message: error: cause: ...`), and the backtrace, if
+/// kept, is moved to a `stack` meta key — which suits `simple()`, `cli()` and `printf`.
+#[derive(Clone, Default)]
+pub struct ErrorsFormat {
+    stack: bool,
+    text: bool,
+}
+
+impl ErrorsFormat {
+    pub fn new() -> Self {
+        Self {
+            stack: false,
+            text: false,
+        }
+    }
+
+    pub fn with_stack(mut self, stack: bool) -> Self {
+        self.stack = stack;
+        self
+    }
+
+    pub fn with_text(mut self, text: bool) -> Self {
+        self.text = text;
+        self
+    }
+
+    fn render(error: &serde_json::Map<String, Value>) -> String {
+        let mut rendered = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        if let Some(Value::Array(chain)) = error.get("chain") {
+            for cause in chain {
+                rendered.push_str(": ");
+                match cause {
+                    Value::String(s) => rendered.push_str(s),
+                    other => rendered.push_str(&other.to_string()),
+                }
+            }
+        }
+        rendered
+    }
+}
+
+impl Format for ErrorsFormat {
+    type Input = LogInfo;
+
+    fn transform(&self, mut info: LogInfo) -> Option<Self::Input> {
+        let Some(Value::Object(mut error)) = info.meta.remove("error") else {
+            return Some(info);
+        };
+
+        let backtrace = error.remove("backtrace");
+
+        if self.text {
+            let rendered = Self::render(&error);
+            info.message = if info.message.is_empty() {
+                rendered
+            } else {
+                format!("{}: {}", info.message, rendered)
+            };
+            if let (true, Some(backtrace)) = (self.stack, backtrace) {
+                info.meta.insert("stack", backtrace);
+            }
+            return Some(info);
+        }
+
+        if info.message.is_empty() {
+            if let Some(Value::String(message)) = error.get("message") {
+                info.message = message.clone();
+            }
+        }
+        if let (true, Some(backtrace)) = (self.stack, backtrace) {
+            error.insert("backtrace".to_string(), backtrace);
+        }
+        info.meta.insert("error", Value::Object(error));
+
+        Some(info)
+    }
+}
+
+pub fn errors() -> ErrorsFormat {
+    ErrorsFormat::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn error_info(message: &str) -> LogInfo {
+        LogInfo::new("error", message).with_meta(
+            "error",
+            json!({
+                "message": "connection refused",
+                "type": "std::io::error::Error",
+                "chain": ["os error 111"],
+                "backtrace": "0: main"
+            }),
+        )
+    }
+
+    #[test]
+    fn test_errors_keeps_structure_and_drops_backtrace() {
+        let result = errors().transform(error_info("")).unwrap();
+
+        assert_eq!(result.message, "connection refused");
+        assert_eq!(result.meta["error"]["type"], json!("std::io::error::Error"));
+        assert_eq!(result.meta["error"]["chain"], json!(["os error 111"]));
+        assert!(result.meta["error"].get("backtrace").is_none());
+    }
+
+    #[test]
+    fn test_errors_with_stack_keeps_backtrace() {
+        let result = errors()
+            .with_stack(true)
+            .transform(error_info("request failed"))
+            .unwrap();
+
+        assert_eq!(result.message, "request failed");
+        assert_eq!(result.meta["error"]["backtrace"], json!("0: main"));
+    }
+
+    #[test]
+    fn test_errors_text_renders_chain_into_message() {
+        let result = errors()
+            .with_text(true)
+            .with_stack(true)
+            .transform(error_info("request failed"))
+            .unwrap();
+
+        assert_eq!(
+            result.message,
+            "request failed: connection refused: os error 111"
+        );
+        assert!(!result.meta.contains_key("error"));
+        assert_eq!(result.meta["stack"], json!("0: main"));
+    }
+
+    #[test]
+    fn test_errors_without_error_is_unchanged() {
+        let info = LogInfo::new("info", "nothing to see").with_meta("key", "value");
+        let result = errors().with_text(true).transform(info).unwrap();
+
+        assert_eq!(result.message, "nothing to see");
+        assert_eq!(result.meta["key"], json!("value"));
+    }
+}
@@ -0,0 +1,307 @@
+use super::Format;
+use crate::{path::FieldPath, LogInfo, Meta};
+use serde_json::Value;
+
+#[derive(Clone)]
+enum Op {
+    Rename(FieldPath, FieldPath),
+    Move(Vec<FieldPath>, FieldPath),
+    Flatten(Option<FieldPath>),
+    Unflatten,
+    Drop(FieldPath),
+    Default(FieldPath, Value),
+}
+
+/// Reshapes meta with operations over paths in [`FieldPath`](crate::FieldPath)
+/// syntax (`user.id`, `items[0].price`, `headers.*`), applied in the order
+/// they were added. A leading `meta.` is optional.
+///
+/// Renames and moves never overwrite a non-object value on the way to their
+/// target; the value then stays where it was. Invalid paths, and wildcards
+/// where a single target is needed, are reported and the operation ignored.
+#[derive(Clone, Default)]
+pub struct FieldsFormat {
+    ops: Vec<Op>,
+}
+
+impl FieldsFormat {
+    pub fn new() -> Self {
+        Self { ops: Vec::new() }
+    }
+
+    fn parse(path: &str, wildcards: bool) -> Option<FieldPath> {
+        match FieldPath::parse(path.strip_prefix("meta.").unwrap_or(path)) {
+            Ok(parsed) if !wildcards && parsed.has_wildcards() => {
+                eprintln!("[logform::fields] Ignoring path with wildcards: '{}'", path);
+                None
+            }
+            Ok(parsed) => Some(parsed),
+            Err(e) => {
+                eprintln!("[logform::fields] Ignoring invalid path: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Moves the value at `from` to `to`, e.g. `user_id` to `user.id`.
+    pub fn with_rename(mut self, from: &str, to: &str) -> Self {
+        if let (Some(from), Some(to)) = (Self::parse(from, false), Self::parse(to, false)) {
+            self.ops.push(Op::Rename(from, to));
+        }
+        self
+    }
+
+    /// Moves each of `paths` into the object at `into`, under its last field
+    /// name: `with_move(&["method", "status"], "http")` gives `http.method` and
+    /// `http.status`.
+    pub fn with_move(mut self, paths: &[&str], into: &str) -> Self {
+        let paths: Vec<FieldPath> = paths
+            .iter()
+            .filter_map(|path| Self::parse(path, false))
+            .filter(|path| path.split_last().is_some())
+            .collect();
+        if let Some(into) = Self::parse(into, false) {
+            self.ops.push(Op::Move(paths, into));
+        }
+        self
+    }
+
+    /// Replaces the object at `path` with dotted keys in its parent:
+    /// `{"http": {"status": 200}}` becomes `{"http.status": 200}`. `*`
+    /// flattens every top-level object.
+    pub fn with_flatten(mut self, path: &str) -> Self {
+        if path == "*" {
+            self.ops.push(Op::Flatten(None));
+        } else if let Some(path) = Self::parse(path, false) {
+            self.ops.push(Op::Flatten(Some(path)));
+        }
+        self
+    }
+
+    /// Turns dotted top-level keys into nested objects: `{"http.status": 200}`
+    /// becomes `{"http": {"status": 200}}`.
+    pub fn with_unflatten(mut self) -> Self {
+        self.ops.push(Op::Unflatten);
+        self
+    }
+
+    /// Removes every value `path` matches.
+    pub fn with_drop(mut self, path: &str) -> Self {
+        if let Some(path) = Self::parse(path, true) {
+            self.ops.push(Op::Drop(path));
+        }
+        self
+    }
+
+    /// Sets `path` to `value` if nothing is there.
+    pub fn with_default<V: Into<Value>>(mut self, path: &str, value: V) -> Self {
+        if let Some(path) = Self::parse(path, false) {
+            self.ops.push(Op::Default(path, value.into()));
+        }
+        self
+    }
+
+    fn rename(meta: &mut Meta, from: &FieldPath, to: &FieldPath) {
+        if let Some(value) = from.take(meta) {
+            if let Err(value) = to.insert(meta, value) {
+                from.insert(meta, value).ok();
+            }
+        }
+    }
+
+    fn flatten_into(prefix: &str, value: Value, out: &mut Vec<(String, Value)>) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, value) in map {
+                    Self::flatten_into(&format!("{}.{}", prefix, key), value, out);
+                }
+            }
+            other => out.push((prefix.to_string(), other)),
+        }
+    }
+
+    fn flatten(meta: &mut Meta, path: &FieldPath) {
+        let Some((parent, key)) = path.split_last() else {
+            return;
+        };
+        if !matches!(path.resolve(meta).first(), Some(Value::Object(_))) {
+            return;
+        }
+        let Some(value) = path.take(meta) else {
+            return;
+        };
+        let mut flattened = Vec::new();
+        Self::flatten_into(key, value, &mut flattened);
+        match parent.and_then(|parent| parent.get_mut(meta)) {
+            Some(Value::Object(map)) => map.extend(flattened),
+            _ => meta.extend(flattened),
+        }
+    }
+
+    fn flatten_all(meta: &mut Meta) {
+        let keys: Vec<String> = meta
+            .iter()
+            .filter(|(_, value)| value.is_object())
+            .map(|(key, _)| key.to_string())
+            .collect();
+        for key in keys {
+            if let Some(value) = meta.remove(&key) {
+                let mut flattened = Vec::new();
+                Self::flatten_into(&key, value, &mut flattened);
+                meta.extend(flattened);
+            }
+        }
+    }
+
+    fn unflatten(meta: &mut Meta) {
+        let keys: Vec<String> = meta
+            .iter()
+            .map(|(key, _)| key.to_string())
+            .filter(|key| key.contains('.'))
+            .collect();
+        for key in keys {
+            let Some(path) = FieldPath::dotted(&key) else {
+                continue;
+            };
+            let Some(value) = meta.remove(&key) else {
+                continue;
+            };
+            if let Err(value) = path.insert(meta, value) {
+                // Something that isn't an object is in the way; keep the key flat
+                meta.insert(key, value);
+            }
+        }
+    }
+
+    pub fn transform(&self, mut info: LogInfo) -> Option<LogInfo> {
+        let meta = &mut info.meta;
+        for op in &self.ops {
+            match op {
+                Op::Rename(from, to) => Self::rename(meta, from, to),
+                Op::Move(paths, into) => {
+                    for path in paths {
+                        let (_, key) = path.split_last().expect("checked when added");
+                        Self::rename(meta, path, &into.child(key));
+                    }
+                }
+                Op::Flatten(Some(path)) => Self::flatten(meta, path),
+                Op::Flatten(None) => Self::flatten_all(meta),
+                Op::Unflatten => Self::unflatten(meta),
+                Op::Drop(path) => path.visit_mut(meta, &mut |_| false),
+                Op::Default(path, value) => {
+                    if path.resolve(meta).is_empty() {
+                        path.insert(meta, value.clone()).ok();
+                    }
+                }
+            }
+        }
+        Some(info)
+    }
+}
+
+impl Format for FieldsFormat {
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        self.transform(info)
+    }
+}
+
+pub fn fields() -> FieldsFormat {
+    FieldsFormat::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn meta(info: &LogInfo) -> Value {
+        info.meta.to_value()
+    }
+
+    #[test]
+    fn test_fields_rename_and_move() {
+        let info = LogInfo::new("info", "Request")
+            .with_meta("user_id", 7)
+            .with_meta("method", "GET")
+            .with_meta("status", 200)
+            .with_meta("version", "1.1");
+
+        let result = fields()
+            .with_rename("user_id", "meta.user.id")
+            .with_move(&["method", "status"], "http")
+            .with_rename("version", "http.status.code")
+            .transform(info)
+            .unwrap();
+
+        assert_eq!(
+            meta(&result),
+            json!({"version": "1.1", "user": {"id": 7}, "http": {"method": "GET", "status": 200}})
+        );
+    }
+
+    #[test]
+    fn test_fields_flatten_and_unflatten() {
+        let info = LogInfo::new("info", "x")
+            .with_meta("http", json!({"request": {"method": "GET"}, "status": 200}))
+            .with_meta("tags", json!(["a"]))
+            .with_meta("empty", json!({}));
+
+        let flat = fields().with_flatten("*").transform(info).unwrap();
+        assert_eq!(
+            meta(&flat),
+            json!({"tags": ["a"], "http.request.method": "GET", "http.status": 200, "empty": {}})
+        );
+
+        let nested = fields()
+            .with_unflatten()
+            .transform(flat.with_meta("tags.first", "a"))
+            .unwrap();
+        assert_eq!(
+            meta(&nested),
+            json!({
+                "tags": ["a"],
+                "empty": {},
+                "tags.first": "a",
+                "http": {"request": {"method": "GET"}, "status": 200}
+            })
+        );
+
+        let partial = fields()
+            .with_flatten("http.request")
+            .transform(nested)
+            .unwrap();
+        assert_eq!(
+            partial.meta["http"],
+            json!({"status": 200, "request.method": "GET"})
+        );
+    }
+
+    #[test]
+    fn test_fields_drop_and_default() {
+        let info = LogInfo::new("info", "x")
+            .with_meta("password", "hunter2")
+            .with_meta("headers", json!({"a": {"cookie": "c", "accept": "*/*"}}))
+            .with_meta("env", "staging");
+
+        let result = fields()
+            .with_drop("password")
+            .with_drop("headers.*.cookie")
+            .with_default("env", "prod")
+            .with_default("service.name", "checkout")
+            .with_rename("a[*]", "b")
+            .with_drop("a..b")
+            .transform(info)
+            .unwrap();
+
+        assert_eq!(
+            meta(&result),
+            json!({
+                "headers": {"a": {"accept": "*/*"}},
+                "env": "staging",
+                "service": {"name": "checkout"}
+            })
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use super::{Format, FormatError};
+use crate::LogInfo;
+use std::sync::Arc;
+
+type Predicate = Arc<dyn Fn(&LogInfo) -> bool + Send + Sync>;
+
+/// Keeps entries for which the predicate returns `true` and drops the rest.
+#[derive(Clone)]
+pub struct FilterFormat {
+    predicate: Predicate,
+}
+
+impl FilterFormat {
+    pub fn new<P>(predicate: P) -> Self
+    where
+        P: Fn(&LogInfo) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            predicate: Arc::new(predicate),
+        }
+    }
+}
+
+impl Format for FilterFormat {
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        (self.predicate)(&info).then_some(info)
+    }
+}
+
+/// Applies `format` to entries for which the predicate returns `true` and
+/// passes the rest through unchanged.
+#[derive(Clone)]
+pub struct When<F> {
+    predicate: Predicate,
+    format: F,
+}
+
+impl<F> Format for When<F>
+where
+    F: Format<Input = LogInfo>,
+{
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        if (self.predicate)(&info) {
+            self.format.transform(info)
+        } else {
+            Some(info)
+        }
+    }
+
+    fn try_transform(&self, info: LogInfo) -> Result<Option<Self::Input>, FormatError> {
+        if (self.predicate)(&info) {
+            self.format.try_transform(info)
+        } else {
+            Ok(Some(info))
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("when({})", self.format.describe())
+    }
+}
+
+pub fn filter<P>(predicate: P) -> FilterFormat
+where
+    P: Fn(&LogInfo) -> bool + Send + Sync + 'static,
+{
+    FilterFormat::new(predicate)
+}
+
+pub fn when<P, F>(predicate: P, format: F) -> When<F>
+where
+    P: Fn(&LogInfo) -> bool + Send + Sync + 'static,
+    F: Format<Input = LogInfo>,
+{
+    When {
+        predicate: Arc::new(predicate),
+        format,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{json, label};
+
+    #[test]
+    fn test_filter_drops_entries() {
+        let format =
+            filter(|info| info.meta.get("path").and_then(|p| p.as_str()) != Some("/health"));
+
+        let health = LogInfo::new("info", "GET").with_meta("path", "/health");
+        let users = LogInfo::new("info", "GET").with_meta("path", "/users");
+
+        assert!(format.transform(health).is_none());
+        assert!(format.transform(users).is_some());
+    }
+
+    #[test]
+    fn test_when_applies_format_conditionally() {
+        let format = when(
+            |info| info.level == "error",
+            label().with_label("ALERT").with_message(true),
+        )
+        .chain(json());
+
+        let error = format.transform(LogInfo::new("error", "Boom")).unwrap();
+        let info = format.transform(LogInfo::new("info", "Fine")).unwrap();
+
+        assert_eq!(
+            error.formatted.as_deref(),
+            Some(r#"{"level":"error","message":"[ALERT] Boom"}"#)
+        );
+        assert_eq!(
+            info.formatted.as_deref(),
+            Some(r#"{"level":"info","message":"Fine"}"#)
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use crate::LogInfo;
+use std::io;
+
+/// A final format that can write an entry's line straight into a writer.
+///
+/// `transform` puts a finalizer's output in `LogInfo::formatted`, which costs
+/// a `String` per entry that a transport then copies into its writer. A
+/// transport given the finalizer itself (e.g. `WriterTransport::with_finalizer`)
+/// calls `write_to` instead, serializing into a buffer it reuses across
+/// entries. Run the rest of the pipeline (`timestamp()`, `redact()`, ...) as
+/// the format and leave the finalizer off its end.
+///
+/// `write_to` writes exactly what `transform` would store in `formatted`,
+/// without a line break. Implemented by `json()`, `simple()`, `logstash()`,
+/// `cli()` and `printf()`.
+pub trait Finalizer {
+    fn write_to(&self, info: &LogInfo, writer: &mut dyn io::Write) -> io::Result<()>;
+
+    /// Appends the entry and a line break to `buffer`, leaving `buffer` as it
+    /// was if writing fails.
+    fn append_line(&self, info: &LogInfo, buffer: &mut Vec<u8>) -> io::Result<()> {
+        let start = buffer.len();
+        match self.write_to(info, buffer) {
+            Ok(()) => {
+                buffer.push(b'\n');
+                Ok(())
+            }
+            Err(e) => {
+                buffer.truncate(start);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl<F> Finalizer for std::sync::Arc<F>
+where
+    F: Finalizer + ?Sized,
+{
+    fn write_to(&self, info: &LogInfo, writer: &mut dyn io::Write) -> io::Result<()> {
+        (**self).write_to(info, writer)
+    }
+}
+
+impl<F> Finalizer for Box<F>
+where
+    F: Finalizer + ?Sized,
+{
+    fn write_to(&self, info: &LogInfo, writer: &mut dyn io::Write) -> io::Result<()> {
+        (**self).write_to(info, writer)
+    }
+}
+
+/// The text a finalizer wrote into `buffer`, moved into a `String` without a
+/// copy. Fails if the finalizer wrote bytes that aren't UTF-8.
+pub(crate) fn into_string(buffer: Vec<u8>) -> io::Result<String> {
+    String::from_utf8(buffer).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cli, json, logstash, printf, simple, Format};
+    use serde_json::json;
+
+    type Pair = (Box<dyn Finalizer>, Box<dyn Format<Input = LogInfo>>);
+
+    fn streamed(finalizer: &dyn Finalizer, info: &LogInfo) -> String {
+        let mut out = Vec::new();
+        finalizer.write_to(info, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_finalizers_match_transform() {
+        let info = LogInfo::new("warn", "Disk \"almost\" full")
+            .with_meta("timestamp", "2024-05-01T12:00:00Z")
+            .with_meta("mount", "/var")
+            .with_meta("usage", json!({"free_pct": 4}));
+
+        let finalizers: Vec<Pair> = vec![
+            (Box::new(json()), Box::new(json())),
+            (
+                Box::new(json().with_indent(2)),
+                Box::new(json().with_indent(2)),
+            ),
+            (Box::new(simple()), Box::new(simple())),
+            (Box::new(logstash()), Box::new(logstash())),
+            (Box::new(cli()), Box::new(cli())),
+            (
+                Box::new(printf(|info| format!("{} {}", info.level, info.message))),
+                Box::new(printf(|info| format!("{} {}", info.level, info.message))),
+            ),
+        ];
+        for (finalizer, format) in finalizers {
+            let expected = format.transform(info.clone()).unwrap().formatted.unwrap();
+            assert_eq!(streamed(&*finalizer, &info), expected);
+        }
+    }
+}
@@ -1,8 +1,99 @@
+use crate::LogInfo;
+use std::{error::Error, fmt};
+
+/// Why a format failed to transform an entry, as opposed to dropping it on
+/// purpose (`Ok(None)` from [`Format::try_transform`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatError {
+    format: String,
+    message: String,
+}
+
+impl FormatError {
+    pub fn new(format: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            format: format.into(),
+            message: message.into(),
+        }
+    }
+
+    /// The name of the format that failed.
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.format, self.message)
+    }
+}
+
+impl Error for FormatError {}
+
+/// Bridges a fallible transform to [`Format::transform`], reporting the error
+/// on stderr and dropping the entry.
+pub(crate) fn report<T>(result: Result<Option<T>, FormatError>) -> Option<T> {
+    result.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        None
+    })
+}
+
+/// Stores a final format's serialized output in `formatted`, or turns the
+/// serializer's error into a [`FormatError`] for `format`.
+pub(crate) fn formatted<E: fmt::Display>(
+    format: &str,
+    info: LogInfo,
+    output: Result<String, E>,
+) -> Result<Option<LogInfo>, FormatError> {
+    match output {
+        Ok(formatted) => Ok(Some(LogInfo {
+            formatted: Some(formatted),
+            ..info
+        })),
+        Err(e) => Err(FormatError::new(
+            format,
+            format!("failed to serialize entry: {}", e),
+        )),
+    }
+}
+
+/// The type name without its module path or generic parameters.
+fn short_type_name<T: ?Sized>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
 pub trait Format {
     type Input;
 
     fn transform(&self, input: Self::Input) -> Option<Self::Input>;
 
+    /// Like `transform`, but tells a failure apart from a deliberate drop
+    /// (`Ok(None)`). Defaults to `transform`, which never fails; formats that
+    /// can fail override this and have `transform` report the error.
+    fn try_transform(&self, input: Self::Input) -> Result<Option<Self::Input>, FormatError> {
+        Ok(self.transform(input))
+    }
+
+    /// A short name for messages about this format; defaults to the type name
+    /// (e.g. `JsonFormat`).
+    fn name(&self) -> &str {
+        short_type_name::<Self>()
+    }
+
+    /// Describes the format including what it wraps, e.g.
+    /// `TimestampFormat | JsonFormat` for a chain. Defaults to `name()`.
+    fn describe(&self) -> String {
+        self.name().to_string()
+    }
+
     fn chain<F>(self, next: F) -> ChainedFormat<Self, F>
     where
         Self: Sized,
@@ -10,8 +101,78 @@ pub trait Format {
     {
         ChainedFormat { first: self, next }
     }
+
+    /// Runs this format and `other` on copies of each entry and writes the
+    /// output of both, one after the other on separate lines: e.g. a
+    /// human-readable line followed by its JSON. See [`FanoutFormat`].
+    fn fanout<F>(self, other: F) -> FanoutFormat<Self, F>
+    where
+        Self: Sized,
+        F: Format<Input = Self::Input>,
+    {
+        FanoutFormat {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Applies `f` to each entry this format passes on.
+    fn map<G>(self, f: G) -> MapFormat<Self, G>
+    where
+        Self: Sized,
+        G: Fn(Self::Input) -> Self::Input,
+    {
+        MapFormat { format: self, f }
+    }
+}
+
+impl<F> Format for std::sync::Arc<F>
+where
+    F: Format + ?Sized,
+{
+    type Input = F::Input;
+
+    fn transform(&self, input: Self::Input) -> Option<Self::Input> {
+        (**self).transform(input)
+    }
+
+    fn try_transform(&self, input: Self::Input) -> Result<Option<Self::Input>, FormatError> {
+        (**self).try_transform(input)
+    }
+
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn describe(&self) -> String {
+        (**self).describe()
+    }
+}
+
+impl<F> Format for Box<F>
+where
+    F: Format + ?Sized,
+{
+    type Input = F::Input;
+
+    fn transform(&self, input: Self::Input) -> Option<Self::Input> {
+        (**self).transform(input)
+    }
+
+    fn try_transform(&self, input: Self::Input) -> Result<Option<Self::Input>, FormatError> {
+        (**self).try_transform(input)
+    }
+
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn describe(&self) -> String {
+        (**self).describe()
+    }
 }
 
+#[derive(Clone)]
 pub struct ChainedFormat<F1, F2> {
     first: F1,
     next: F2,
@@ -29,6 +190,101 @@ where
             .transform(input)
             .and_then(|res| self.next.transform(res))
     }
+
+    fn try_transform(&self, input: T) -> Result<Option<T>, FormatError> {
+        match self.first.try_transform(input)? {
+            Some(res) => self.next.try_transform(res),
+            None => Ok(None),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("{} | {}", self.first.describe(), self.next.describe())
+    }
+}
+
+/// Writes an entry through two formats; built by [`Format::fanout`].
+///
+/// Each format gets its own copy of the entry. The result is the first
+/// format's entry (or the second's, if the first dropped it) with both
+/// outputs, as transports would print them, joined by a line break. The
+/// entry is dropped only if both formats drop it.
+#[derive(Clone)]
+pub struct FanoutFormat<F1, F2> {
+    first: F1,
+    second: F2,
+}
+
+impl<F1, F2> FanoutFormat<F1, F2>
+where
+    F1: Format<Input = LogInfo>,
+    F2: Format<Input = LogInfo>,
+{
+    fn combine(first: Option<LogInfo>, second: Option<LogInfo>) -> Option<LogInfo> {
+        match (first, second) {
+            (Some(first), Some(second)) => {
+                let formatted = format!("{}\n{}", first, second);
+                Some(LogInfo {
+                    formatted: Some(formatted),
+                    ..first
+                })
+            }
+            (first, second) => first.or(second),
+        }
+    }
+}
+
+impl<F1, F2> Format for FanoutFormat<F1, F2>
+where
+    F1: Format<Input = LogInfo>,
+    F2: Format<Input = LogInfo>,
+{
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<LogInfo> {
+        let first = self.first.transform(info.clone());
+        Self::combine(first, self.second.transform(info))
+    }
+
+    fn try_transform(&self, info: LogInfo) -> Result<Option<LogInfo>, FormatError> {
+        let first = self.first.try_transform(info.clone())?;
+        Ok(Self::combine(first, self.second.try_transform(info)?))
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "fanout({}, {})",
+            self.first.describe(),
+            self.second.describe()
+        )
+    }
+}
+
+/// Applies a function to the output of a format; built by [`Format::map`].
+#[derive(Clone)]
+pub struct MapFormat<F, G> {
+    format: F,
+    f: G,
+}
+
+impl<F, G> Format for MapFormat<F, G>
+where
+    F: Format,
+    G: Fn(F::Input) -> F::Input,
+{
+    type Input = F::Input;
+
+    fn transform(&self, input: Self::Input) -> Option<Self::Input> {
+        self.format.transform(input).map(&self.f)
+    }
+
+    fn try_transform(&self, input: Self::Input) -> Result<Option<Self::Input>, FormatError> {
+        Ok(self.format.try_transform(input)?.map(&self.f))
+    }
+
+    fn describe(&self) -> String {
+        format!("{} | map", self.format.describe())
+    }
 }
 
 #[cfg(test)]
@@ -76,4 +332,80 @@ mod tests {
 
         assert_eq!(result, Some("OLLEH-end".to_string()));
     }
+
+    struct Failing;
+    impl Format for Failing {
+        type Input = String;
+
+        fn transform(&self, input: String) -> Option<Self::Input> {
+            report(self.try_transform(input))
+        }
+
+        fn try_transform(&self, _input: String) -> Result<Option<String>, FormatError> {
+            Err(FormatError::new(self.name(), "out of ink"))
+        }
+    }
+
+    struct DropAll;
+    impl Format for DropAll {
+        type Input = String;
+
+        fn transform(&self, _input: String) -> Option<Self::Input> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_try_transform_tells_failures_from_drops() {
+        let failing = UpperCase.chain(Failing).chain(ReverseFormat);
+        let error = failing.try_transform("hello".to_string()).unwrap_err();
+        assert_eq!(error.format(), "Failing");
+        assert_eq!(error.to_string(), "Failing: out of ink");
+        assert_eq!(failing.transform("hello".to_string()), None);
+
+        let dropping = UpperCase.chain(DropAll).chain(Failing);
+        assert_eq!(dropping.try_transform("hello".to_string()), Ok(None));
+
+        let mapped = UpperCase.map(|s: String| s.replace('L', "_"));
+        assert_eq!(
+            mapped.try_transform("hello".to_string()),
+            Ok(Some("HE__O".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        let format = UpperCase
+            .chain(ReverseFormat)
+            .map(|s: String| s)
+            .chain(AddSuffix("!".to_string()));
+        assert_eq!(UpperCase.name(), "UpperCase");
+        assert_eq!(
+            format.describe(),
+            "UpperCase | ReverseFormat | map | AddSuffix"
+        );
+
+        let boxed: Box<dyn Format<Input = String>> = Box::new(UpperCase.chain(DropAll));
+        assert_eq!(boxed.describe(), "UpperCase | DropAll");
+    }
+
+    #[test]
+    fn test_fanout() {
+        use crate::{json, simple};
+
+        let format = simple().fanout(json());
+        let result = format
+            .try_transform(LogInfo::new("info", "Started").with_meta("port", 80))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result.to_string(),
+            "info: Started {\"port\":80}\n{\"level\":\"info\",\"message\":\"Started\",\"port\":80}"
+        );
+        assert_eq!(format.describe(), "fanout(SimpleFormat, JsonFormat)");
+
+        let half = crate::filter(|_| false).fanout(simple());
+        let result = half.transform(LogInfo::new("warn", "Low disk")).unwrap();
+        assert_eq!(result.to_string(), "warn: Low disk");
+    }
 }
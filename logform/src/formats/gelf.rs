@@ -0,0 +1,249 @@
+use super::{formatted, FormatError};
+use crate::{config, utils::host::hostname, LogInfo};
+use indexmap::IndexMap;
+use serde_json::{Number, Value};
+use std::collections::HashMap;
+
+/// Renders entries as Graylog GELF 1.1 JSON.
+///
+/// The first line of the message becomes `short_message` and a multi-line
+/// message is also sent whole as `full_message`. `timestamp` is epoch seconds
+/// with millisecond fractions and `level` the syslog severity of the level name.
+/// Meta fields are sent as `_`-prefixed additional fields: nested objects are
+/// flattened with `_` (`_http_status`), arrays become JSON strings and `null`s
+/// are skipped. Graylog rejects messages carrying `_id`, so an entry with an
+/// `id` meta key fails with a [`FormatError`]; rename it (e.g. with `fields()`).
+#[derive(Clone)]
+pub struct GelfFormat {
+    host: String,
+    levels: HashMap<String, u8>,
+    default_level: u8,
+}
+
+impl Default for GelfFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GelfFormat {
+    pub fn new() -> Self {
+        Self {
+            host: hostname().to_string(),
+            levels: HashMap::new(),
+            default_level: 6,
+        }
+    }
+
+    /// Overrides the `host` field (default: this machine's hostname).
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.host = host.to_string();
+        self
+    }
+
+    /// Maps a custom level name onto a syslog severity (0-7).
+    pub fn with_level(mut self, level: &str, severity: u8) -> Self {
+        self.levels.insert(level.to_string(), severity.min(7));
+        self
+    }
+
+    /// Severity used for level names with no known mapping (default: 6, info).
+    pub fn with_default_level(mut self, severity: u8) -> Self {
+        self.default_level = severity.min(7);
+        self
+    }
+
+    fn severity(&self, level: &str) -> u8 {
+        self.levels
+            .get(level)
+            .copied()
+            .or_else(|| config::syslog::severity(level))
+            .unwrap_or(self.default_level)
+    }
+
+    fn field_name(key: &str) -> String {
+        key.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+
+    fn push_field(fields: &mut IndexMap<String, Value>, name: String, value: Value) {
+        let value = match value {
+            Value::Null => return,
+            Value::Object(object) => {
+                for (key, value) in object {
+                    Self::push_field(
+                        fields,
+                        format!("{}_{}", name, Self::field_name(&key)),
+                        value,
+                    );
+                }
+                return;
+            }
+            Value::Bool(b) => Value::String(b.to_string()),
+            Value::Array(_) => Value::String(value.to_string()),
+            value => value,
+        };
+        fields.insert(name, value);
+    }
+
+    pub fn try_transform(&self, info: LogInfo) -> Result<Option<LogInfo>, FormatError> {
+        let timestamp = info.time().timestamp_millis() as f64 / 1000.0;
+        let short_message = match info.message.lines().next() {
+            Some(line) if !line.trim().is_empty() => line,
+            // GELF requires a non-empty short_message
+            _ => "-",
+        };
+
+        let mut gelf = IndexMap::new();
+        gelf.insert("version".to_string(), Value::from("1.1"));
+        gelf.insert("host".to_string(), Value::from(self.host.as_str()));
+        gelf.insert("short_message".to_string(), Value::from(short_message));
+        if info.message.contains('\n') {
+            gelf.insert(
+                "full_message".to_string(),
+                Value::from(info.message.as_str()),
+            );
+        }
+        if let Some(timestamp) = Number::from_f64(timestamp) {
+            gelf.insert("timestamp".to_string(), Value::Number(timestamp));
+        }
+        gelf.insert("level".to_string(), Value::from(self.severity(&info.level)));
+
+        for (key, value) in info.meta.iter() {
+            if key == "timestamp" {
+                continue;
+            }
+            let name = Self::field_name(key);
+            if name == "id" && !value.is_null() && !value.is_object() {
+                return Err(FormatError::new(
+                    "GelfFormat",
+                    format!(
+                        "meta key `{}` would be sent as `_id`, which Graylog rejects",
+                        key
+                    ),
+                ));
+            }
+            Self::push_field(&mut gelf, format!("_{}", name), value.clone());
+        }
+
+        let output = serde_json::to_string(&gelf);
+        formatted("GelfFormat", info, output)
+    }
+}
+
+fallible_format!(GelfFormat);
+
+pub fn gelf() -> GelfFormat {
+    GelfFormat::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Format;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    fn render(format: &GelfFormat, info: LogInfo) -> Value {
+        let result = format.transform(info).unwrap();
+        serde_json::from_str(result.formatted.as_deref().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_gelf_format() {
+        let time = Utc.timestamp_millis_opt(1_712_000_000_123).unwrap();
+        let info = LogInfo::new("warn", "Disk almost full")
+            .with_timestamp(time)
+            .with_meta("mount", "/var")
+            .with_meta("free_pct", 4);
+
+        let result = gelf().with_host("web-1").transform(info).unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(
+                r#"{"version":"1.1","host":"web-1","short_message":"Disk almost full","timestamp":1712000000.123,"level":4,"_mount":"/var","_free_pct":4}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_gelf_splits_multiline_message() {
+        let parsed = render(
+            &gelf(),
+            LogInfo::new("error", "Request failed\n  at handler"),
+        );
+
+        assert_eq!(parsed["short_message"], "Request failed");
+        assert_eq!(parsed["full_message"], "Request failed\n  at handler");
+        assert_eq!(parsed["level"], 3);
+        assert!(parsed["host"].as_str().is_some_and(|host| !host.is_empty()));
+    }
+
+    #[test]
+    fn test_gelf_additional_fields() {
+        let info = LogInfo::new("info", "Handled")
+            .with_meta("http", json!({"method": "GET", "status": 200}))
+            .with_meta("tags", json!(["a", "b"]))
+            .with_meta("cached", true)
+            .with_meta("user name", "bob")
+            .with_meta("missing", Value::Null)
+            .with_meta("timestamp", "2024-04-01T12:30:00Z");
+
+        let parsed = render(&gelf(), info);
+        assert_eq!(parsed["_http_method"], "GET");
+        assert_eq!(parsed["_http_status"], 200);
+        assert_eq!(parsed["_tags"], r#"["a","b"]"#);
+        assert_eq!(parsed["_cached"], "true");
+        assert_eq!(parsed["_user_name"], "bob");
+        assert!(parsed.get("_missing").is_none());
+        assert!(parsed.get("_timestamp").is_none());
+        assert_eq!(parsed["timestamp"], json!(1711974600.0));
+    }
+
+    #[test]
+    fn test_gelf_rejects_id_field() {
+        let error = gelf()
+            .try_transform(LogInfo::new("info", "x").with_meta("id", 7))
+            .unwrap_err();
+        assert_eq!(error.format(), "GelfFormat");
+        assert!(error.to_string().contains("_id"));
+
+        let parsed = render(
+            &gelf(),
+            LogInfo::new("info", "x").with_meta("id", json!({"a": 1})),
+        );
+        assert_eq!(parsed["_id_a"], 1);
+    }
+
+    #[test]
+    fn test_gelf_level_mapping() {
+        let format = gelf().with_level("audit", 5).with_default_level(7);
+
+        assert_eq!(render(&format, LogInfo::new("trace", "x"))["level"], 7);
+        assert_eq!(render(&format, LogInfo::new("emerg", "x"))["level"], 0);
+        assert_eq!(render(&format, LogInfo::new("audit", "x"))["level"], 5);
+        assert_eq!(render(&format, LogInfo::new("custom", "x"))["level"], 7);
+        assert_eq!(
+            render(&format, LogInfo::new("info", ""))["short_message"],
+            "-"
+        );
+    }
+
+    #[test]
+    fn test_gelf_keeps_meta_on_the_entry() {
+        let info = LogInfo::new("info", "x")
+            .with_meta("user", "bob")
+            .with_meta("timestamp", "2024-04-01T12:30:00Z");
+
+        let result = gelf().transform(info).unwrap();
+        assert_eq!(result.meta.get("user"), Some(&json!("bob")));
+        assert!(result.meta.get("timestamp").is_some());
+    }
+}
@@ -1,40 +1,388 @@
-use super::Format;
+use super::{formatted, into_string, Finalizer, FormatError};
 use crate::LogInfo;
-use serde_json::{Map, Value};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use serde_json::{
+    ser::{CharEscape, CompactFormatter, Formatter, PrettyFormatter},
+    Value,
+};
+use std::{borrow::Cow, cell::Cell, io, sync::Arc};
 
-pub struct JsonFormat;
+type Replacer = dyn for<'v> Fn(&str, &'v Value) -> Option<Cow<'v, Value>> + Send + Sync;
 
-impl Format for JsonFormat {
-    type Input = LogInfo;
+thread_local! {
+    /// Length of the last entry serialized on this thread, used to size the
+    /// next buffer so a steady stream of similar entries doesn't regrow it.
+    static LAST_LEN: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Serializes entries as one JSON object each.
+///
+/// By default the object holds `level`, `message` and then meta in insertion
+/// order, with a `level` or `message` meta key replacing the field's value in
+/// place. The core keys can be renamed, meta can be nested under a key of its
+/// own, and keys can be sorted. A replacer sees every key and value (array
+/// items under their index) and returns the value to write, or `None` to drop
+/// the key, like `JSON.stringify`'s replacer.
+///
+/// Entries are serialized straight from the `LogInfo`, without building an
+/// intermediate map.
+#[derive(Clone)]
+pub struct JsonFormat {
+    level_key: String,
+    message_key: String,
+    meta_key: Option<String>,
+    replacer: Option<Arc<Replacer>>,
+    sort_keys: bool,
+    indent: usize,
+    ascii: bool,
+}
+
+impl Default for JsonFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonFormat {
+    pub fn new() -> Self {
+        Self {
+            level_key: "level".to_string(),
+            message_key: "message".to_string(),
+            meta_key: None,
+            replacer: None,
+            sort_keys: false,
+            indent: 0,
+            ascii: false,
+        }
+    }
+
+    /// Defaults to `level`.
+    pub fn with_level_key(mut self, key: &str) -> Self {
+        self.level_key = key.to_string();
+        self
+    }
+
+    /// Defaults to `message`.
+    pub fn with_message_key(mut self, key: &str) -> Self {
+        self.message_key = key.to_string();
+        self
+    }
+
+    /// Nests all meta under `key` instead of writing it next to the core keys.
+    pub fn with_meta_key(mut self, key: &str) -> Self {
+        self.meta_key = Some(key.to_string());
+        self
+    }
+
+    pub fn with_replacer<F>(mut self, replacer: F) -> Self
+    where
+        F: for<'v> Fn(&str, &'v Value) -> Option<Cow<'v, Value>> + Send + Sync + 'static,
+    {
+        self.replacer = Some(Arc::new(replacer));
+        self
+    }
+
+    /// Writes meta keys, at every depth, in lexicographic order. The core keys
+    /// stay first.
+    pub fn with_sorted_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    /// Pretty-prints with `indent` spaces per level; 0 (the default) writes a
+    /// single line.
+    pub fn with_indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Escapes every non-ASCII character as `\uXXXX`.
+    pub fn with_ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    /// Serializes `info` into `writer`, as `transform` would format it.
+    pub fn write_to<W: io::Write>(&self, info: &LogInfo, writer: W) -> io::Result<()> {
+        let line = JsonLine { format: self, info };
+        let indent = vec![b' '; self.indent];
+        match (self.indent > 0, self.ascii) {
+            (false, false) => write_with(&line, writer, CompactFormatter),
+            (false, true) => write_with(&line, writer, AsciiFormatter(CompactFormatter)),
+            (true, false) => write_with(&line, writer, PrettyFormatter::with_indent(&indent)),
+            (true, true) => write_with(
+                &line,
+                writer,
+                AsciiFormatter(PrettyFormatter::with_indent(&indent)),
+            ),
+        }
+    }
+
+    fn walks_values(&self) -> bool {
+        self.replacer.is_some() || self.sort_keys
+    }
+
+    fn serialize_entry<M: SerializeMap>(
+        &self,
+        map: &mut M,
+        key: &str,
+        value: &Value,
+    ) -> Result<(), M::Error> {
+        match &self.replacer {
+            Some(replacer) => match replacer(key, value) {
+                Some(value) => map.serialize_entry(key, &Node(self, &value)),
+                None => Ok(()),
+            },
+            None => map.serialize_entry(key, &Node(self, value)),
+        }
+    }
 
-    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
-        let mut log_object = Map::new();
+    fn serialize_entries<'a, M: SerializeMap>(
+        &self,
+        map: &mut M,
+        entries: impl Iterator<Item = (&'a str, &'a Value)>,
+    ) -> Result<(), M::Error> {
+        if self.sort_keys {
+            let mut entries: Vec<_> = entries.collect();
+            entries.sort_by_key(|(key, _)| *key);
+            for (key, value) in entries {
+                self.serialize_entry(map, key, value)?;
+            }
+        } else {
+            for (key, value) in entries {
+                self.serialize_entry(map, key, value)?;
+            }
+        }
+        Ok(())
+    }
 
-        log_object.insert("level".to_string(), Value::String(info.level.clone()));
-        log_object.insert("message".to_string(), Value::String(info.message.clone()));
+    /// Writes a core field, unless the replacer drops it.
+    fn serialize_core<M: SerializeMap>(
+        &self,
+        map: &mut M,
+        key: &str,
+        value: &str,
+    ) -> Result<(), M::Error> {
+        match self.replacer {
+            Some(_) => self.serialize_entry(map, key, &Value::String(value.to_string())),
+            None => map.serialize_entry(key, value),
+        }
+    }
+}
 
-        for (key, value) in &info.meta {
-            log_object.insert(key.clone(), value.clone());
+fn write_with<W: io::Write, F: Formatter>(
+    line: &JsonLine<'_>,
+    writer: W,
+    formatter: F,
+) -> io::Result<()> {
+    let mut serializer = serde_json::Serializer::with_formatter(writer, formatter);
+    line.serialize(&mut serializer).map_err(io::Error::from)
+}
+
+struct JsonLine<'a> {
+    format: &'a JsonFormat,
+    info: &'a LogInfo,
+}
+
+impl Serialize for JsonLine<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let JsonLine { format, info } = self;
+        let mut map = serializer.serialize_map(None)?;
+
+        match &format.meta_key {
+            Some(meta_key) => {
+                format.serialize_core(&mut map, &format.level_key, &info.level)?;
+                format.serialize_core(&mut map, &format.message_key, &info.message)?;
+                map.serialize_entry(meta_key, &MetaNode(format, info))?;
+            }
+            None => {
+                let (level_key, message_key) = (&format.level_key, &format.message_key);
+                match info.meta.get(level_key) {
+                    Some(level) => format.serialize_entry(&mut map, level_key, level)?,
+                    None => format.serialize_core(&mut map, level_key, &info.level)?,
+                }
+                match info.meta.get(message_key) {
+                    Some(message) => format.serialize_entry(&mut map, message_key, message)?,
+                    None => format.serialize_core(&mut map, message_key, &info.message)?,
+                }
+                format.serialize_entries(
+                    &mut map,
+                    info.meta
+                        .iter()
+                        .map(|(key, value)| (key.as_str(), value))
+                        .filter(|(key, _)| key != level_key && key != message_key),
+                )?;
+            }
         }
+        map.end()
+    }
+}
 
-        let json_message = Value::Object(log_object).to_string();
+/// Meta as a nested object, for `with_meta_key`.
+struct MetaNode<'a>(&'a JsonFormat, &'a LogInfo);
 
-        Some(LogInfo {
-            formatted: Some(json_message),
-            ..info
-        })
+impl Serialize for MetaNode<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let MetaNode(format, info) = self;
+        let mut map = serializer.serialize_map(Some(info.meta.len()))?;
+        format.serialize_entries(
+            &mut map,
+            info.meta.iter().map(|(key, value)| (key.as_str(), value)),
+        )?;
+        map.end()
+    }
+}
+
+/// A value written through the replacer and key sorting, if either is set.
+struct Node<'a>(&'a JsonFormat, &'a Value);
+
+impl Serialize for Node<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Node(format, value) = self;
+        if !format.walks_values() {
+            return value.serialize(serializer);
+        }
+        match value {
+            Value::Object(object) => {
+                let mut map = serializer.serialize_map(Some(object.len()))?;
+                format.serialize_entries(
+                    &mut map,
+                    object.iter().map(|(key, value)| (key.as_str(), value)),
+                )?;
+                map.end()
+            }
+            Value::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for (index, item) in items.iter().enumerate() {
+                    match &format.replacer {
+                        // Dropped items become null to keep the other indices
+                        Some(replacer) => match replacer(&index.to_string(), item) {
+                            Some(item) => seq.serialize_element(&Node(format, &item))?,
+                            None => seq.serialize_element(&Value::Null)?,
+                        },
+                        None => seq.serialize_element(&Node(format, item))?,
+                    }
+                }
+                seq.end()
+            }
+            other => other.serialize(serializer),
+        }
+    }
+}
+
+/// Wraps a formatter to escape non-ASCII characters as `\uXXXX`.
+struct AsciiFormatter<F>(F);
+
+impl<F: Formatter> Formatter for AsciiFormatter<F> {
+    fn write_string_fragment<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        fragment: &str,
+    ) -> io::Result<()> {
+        let mut start = 0;
+        for (index, c) in fragment.char_indices() {
+            if c.is_ascii() {
+                continue;
+            }
+            writer.write_all(&fragment.as_bytes()[start..index])?;
+            let mut units = [0u16; 2];
+            for unit in c.encode_utf16(&mut units) {
+                write!(writer, "\\u{:04x}", unit)?;
+            }
+            start = index + c.len_utf8();
+        }
+        writer.write_all(&fragment.as_bytes()[start..])
+    }
+
+    fn write_char_escape<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        char_escape: CharEscape,
+    ) -> io::Result<()> {
+        self.0.write_char_escape(writer, char_escape)
+    }
+
+    fn begin_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.begin_array(writer)
+    }
+
+    fn end_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_array(writer)
+    }
+
+    fn begin_array_value<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        self.0.begin_array_value(writer, first)
+    }
+
+    fn end_array_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_array_value(writer)
+    }
+
+    fn begin_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.begin_object(writer)
+    }
+
+    fn end_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_object(writer)
+    }
+
+    fn begin_object_key<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        self.0.begin_object_key(writer, first)
+    }
+
+    fn end_object_key<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_object_key(writer)
+    }
+
+    fn begin_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.begin_object_value(writer)
+    }
+
+    fn end_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_object_value(writer)
+    }
+}
+
+impl JsonFormat {
+    pub fn try_transform(&self, info: LogInfo) -> Result<Option<LogInfo>, FormatError> {
+        let mut buffer = Vec::with_capacity(LAST_LEN.get());
+        let output = self.write_to(&info, &mut buffer).and_then(|()| {
+            LAST_LEN.set(buffer.len());
+            into_string(buffer)
+        });
+        formatted("JsonFormat", info, output)
+    }
+}
+
+fallible_format!(JsonFormat);
+
+impl Finalizer for JsonFormat {
+    fn write_to(&self, info: &LogInfo, writer: &mut dyn io::Write) -> io::Result<()> {
+        JsonFormat::write_to(self, info, writer)
     }
 }
 
 pub fn json() -> JsonFormat {
-    JsonFormat
+    JsonFormat::new()
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::Format;
+    use serde_json::{json, Value};
+
     #[test]
     fn test_json_format_empty_metadata() {
-        let json_formatter = JsonFormat;
+        let json_formatter = json();
         let info = LogInfo::new("info", "User logged in");
         let result = json_formatter.transform(info).unwrap();
         let expected_value = json!({
@@ -48,7 +396,7 @@ mod tests {
 
     #[test]
     fn test_json_format_special_characters() {
-        let json_formatter = JsonFormat;
+        let json_formatter = json();
         let info = LogInfo::new("info", "Special chars: \" \n \t ")
             .with_meta("weird\nkey", Value::String("strange\tvalue".to_string()));
         let result = json_formatter.transform(info).unwrap();
@@ -64,7 +412,7 @@ mod tests {
 
     #[test]
     fn test_json_format_large_metadata() {
-        let json_formatter = JsonFormat;
+        let json_formatter = json();
         let mut info = LogInfo::new("info", "Bulk meta test");
         for i in 0..1000 {
             info.meta
@@ -90,7 +438,7 @@ mod tests {
 
     #[test]
     fn test_json_format_empty_level_and_message() {
-        let json_formatter = JsonFormat;
+        let json_formatter = json();
         let info = LogInfo::new("", "");
         let result = json_formatter.transform(info).unwrap();
         let expected_value = json!({
@@ -101,13 +449,24 @@ mod tests {
             serde_json::from_str(result.formatted.as_deref().unwrap()).unwrap();
         assert_eq!(actual_value, expected_value);
     }
-    use serde_json::json;
 
-    use super::*;
+    #[test]
+    fn test_json_format_keeps_meta_order() {
+        let info = LogInfo::new("info", "Ordered")
+            .with_meta("zeta", 1)
+            .with_meta("alpha", 2)
+            .with_meta("level", "notice");
+
+        let result = json().transform(info).unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(r#"{"level":"notice","message":"Ordered","zeta":1,"alpha":2}"#)
+        );
+    }
 
     #[test]
     fn test_json_format() {
-        let json_formatter = JsonFormat;
+        let json_formatter = json();
 
         let info = LogInfo::new("info", "User logged in")
             .with_meta("user_id", Value::Number(12345.into()))
@@ -125,4 +484,85 @@ mod tests {
             serde_json::from_str(result.formatted.as_deref().unwrap()).unwrap();
         assert_eq!(actual_value, expected_value);
     }
+
+    #[test]
+    fn test_json_format_renamed_keys_and_nested_meta() {
+        let info = LogInfo::new("warn", "Disk almost full")
+            .with_meta("mount", "/var")
+            .with_meta("level", "ignored when nested");
+
+        let result = json()
+            .with_level_key("severity")
+            .with_message_key("msg")
+            .with_meta_key("meta")
+            .transform(info)
+            .unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(
+                r#"{"severity":"warn","msg":"Disk almost full","meta":{"mount":"/var","level":"ignored when nested"}}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_json_format_replacer() {
+        let info = LogInfo::new("info", "Transfer")
+            .with_meta("amount", json!(18446744073709551615u64))
+            .with_meta("password", "hunter2")
+            .with_meta("items", json!([{"id": 1, "password": "x"}, 2]));
+
+        let format = json().with_replacer(|key, value| match (key, value) {
+            ("password", _) => None,
+            (_, Value::Number(n)) if n.as_i64().is_none() => {
+                Some(Cow::Owned(Value::String(n.to_string())))
+            }
+            _ => Some(Cow::Borrowed(value)),
+        });
+        let result = format.transform(info).unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(
+                r#"{"level":"info","message":"Transfer","amount":"18446744073709551615","items":[{"id":1},2]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_json_format_sorted_keys() {
+        let info = LogInfo::new("info", "Sorted")
+            .with_meta("zeta", json!({"b": 1, "a": 2}))
+            .with_meta("alpha", 1);
+
+        let result = json().with_sorted_keys(true).transform(info).unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(r#"{"level":"info","message":"Sorted","alpha":1,"zeta":{"a":2,"b":1}}"#)
+        );
+    }
+
+    #[test]
+    fn test_json_format_pretty_and_ascii() {
+        let info = LogInfo::new("info", "Grüße 🦀").with_meta("tags", json!(["é"]));
+
+        let result = json()
+            .with_indent(2)
+            .with_ascii(true)
+            .transform(info)
+            .unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(
+                "{\n  \"level\": \"info\",\n  \"message\": \"Gr\\u00fc\\u00dfe \\ud83e\\udd80\",\n  \"tags\": [\n    \"\\u00e9\"\n  ]\n}"
+            )
+        );
+    }
+
+    #[test]
+    fn test_json_format_write_to() {
+        let info = LogInfo::new("info", "Streamed").with_meta("id", 7);
+        let mut out = Vec::new();
+        json().write_to(&info, &mut out).unwrap();
+        assert_eq!(out, br#"{"level":"info","message":"Streamed","id":7}"#);
+    }
 }
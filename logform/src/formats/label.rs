@@ -39,7 +39,7 @@ impl Format for LabelFormat {
         if self.message {
             info.message = format!("[{}] {}", self.label, info.message);
         } else {
-            info.meta.insert("label".to_string(), json!(self.label));
+            info.meta.insert("label", json!(self.label));
         }
         Some(info)
     }
@@ -0,0 +1,328 @@
+use super::Format;
+use crate::LogInfo;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Meta key set to `true` when `limit()` shortened or dropped anything.
+const TRUNCATED: &str = "_truncated";
+
+/// Keeps entries within size limits, e.g. a collector's maximum request size.
+///
+/// Lengths are counted in characters, so strings are never cut inside a UTF-8
+/// sequence; a cut string ends with the ellipsis marker (`…` by default).
+/// Arrays longer than the array length keep their first items, and objects or
+/// arrays nested deeper than the depth limit become `"[Object]"`/`"[Array]"`
+/// (top-level meta values are at depth 0).
+///
+/// As a last resort, if the entry serialized as by `to_flat_value()` is still
+/// over the byte budget, meta keys are dropped largest first and then the
+/// message is cut. Every limit is off unless set, and any truncation sets
+/// `_truncated: true` in meta.
+#[derive(Clone)]
+pub struct LimitFormat {
+    message_length: Option<usize>,
+    string_length: Option<usize>,
+    array_length: Option<usize>,
+    depth: Option<usize>,
+    max_bytes: Option<usize>,
+    ellipsis: String,
+}
+
+impl Default for LimitFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LimitFormat {
+    pub fn new() -> Self {
+        Self {
+            message_length: None,
+            string_length: None,
+            array_length: None,
+            depth: None,
+            max_bytes: None,
+            ellipsis: "…".to_string(),
+        }
+    }
+
+    /// Maximum characters of `message`.
+    pub fn with_message_length(mut self, length: usize) -> Self {
+        self.message_length = Some(length);
+        self
+    }
+
+    /// Maximum characters of each string in meta, at any depth.
+    pub fn with_string_length(mut self, length: usize) -> Self {
+        self.string_length = Some(length);
+        self
+    }
+
+    /// Maximum items of each array in meta, at any depth.
+    pub fn with_array_length(mut self, length: usize) -> Self {
+        self.array_length = Some(length);
+        self
+    }
+
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Maximum size in bytes of the serialized entry.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    pub fn with_ellipsis(mut self, ellipsis: &str) -> Self {
+        self.ellipsis = ellipsis.to_string();
+        self
+    }
+
+    fn truncate(&self, s: &mut String, length: usize) -> bool {
+        match s.char_indices().nth(length) {
+            Some((end, _)) => {
+                s.truncate(end);
+                s.push_str(&self.ellipsis);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn limit_value(&self, value: &mut Value, depth: usize) -> bool {
+        let too_deep = self.depth.is_some_and(|max| depth >= max);
+        match value {
+            Value::String(s) => self
+                .string_length
+                .is_some_and(|length| self.truncate(s, length)),
+            Value::Array(_) if too_deep => {
+                *value = Value::String("[Array]".to_string());
+                true
+            }
+            Value::Object(_) if too_deep => {
+                *value = Value::String("[Object]".to_string());
+                true
+            }
+            Value::Array(items) => {
+                let mut truncated = false;
+                if let Some(length) = self.array_length.filter(|length| items.len() > *length) {
+                    items.truncate(length);
+                    truncated = true;
+                }
+                for item in items.iter_mut() {
+                    truncated |= self.limit_value(item, depth + 1);
+                }
+                truncated
+            }
+            Value::Object(map) => map.values_mut().fold(false, |truncated, v| {
+                self.limit_value(v, depth + 1) | truncated
+            }),
+            _ => false,
+        }
+    }
+
+    fn serialized_len(info: &LogInfo) -> usize {
+        Self::json_len(&info.to_flat_value())
+    }
+
+    fn json_len<T: Serialize + ?Sized>(value: &T) -> usize {
+        serde_json::to_vec(value).map_or(0, |bytes| bytes.len())
+    }
+
+    /// Bytes a meta field adds to the serialized entry: `"key":value` plus the
+    /// comma separating it from the `level` and `message` fields.
+    fn field_len(key: &str, value: &Value) -> usize {
+        Self::json_len(key) + 1 + Self::json_len(value) + 1
+    }
+
+    /// Meta keys that replace (or are replaced by) a field of the entry itself,
+    /// so removing them doesn't shrink it by their own size.
+    fn shadows_entry(key: &str) -> bool {
+        matches!(key, "level" | "message" | "timestamp")
+    }
+
+    /// Drops meta keys, largest first, then cuts the message until the entry
+    /// fits in `max_bytes`. The entry is serialized once; each field is then
+    /// measured once and subtracted as it is dropped or cut.
+    fn fit(&self, info: &mut LogInfo, max_bytes: usize) {
+        let mut len = Self::serialized_len(info);
+        if len <= max_bytes {
+            return;
+        }
+        match info.meta.insert(TRUNCATED, Value::Bool(true)) {
+            None => len += Self::field_len(TRUNCATED, &Value::Bool(true)),
+            Some(previous) => {
+                len = len - Self::json_len(&previous) + Self::json_len(&Value::Bool(true))
+            }
+        }
+
+        let mut sizes: Vec<(String, usize)> = info
+            .meta
+            .iter()
+            .filter(|(key, _)| *key != TRUNCATED)
+            .map(|(key, value)| (key.to_string(), Self::field_len(key, value)))
+            .collect();
+        sizes.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+        for (key, size) in sizes {
+            if len <= max_bytes {
+                break;
+            }
+            info.meta.remove(&key);
+            if Self::shadows_entry(&key) {
+                len = Self::serialized_len(info);
+            } else {
+                len -= size;
+            }
+        }
+
+        let mut message_len = Self::json_len(&info.message);
+        while len > max_bytes && !info.message.is_empty() {
+            let excess = len - max_bytes + self.ellipsis.len();
+            let mut end = info.message.len().saturating_sub(excess);
+            while !info.message.is_char_boundary(end) {
+                end -= 1;
+            }
+            info.message.truncate(end);
+            if !info.message.is_empty() {
+                info.message.push_str(&self.ellipsis);
+            }
+            let cut_len = Self::json_len(&info.message);
+            len = len - message_len + cut_len;
+            message_len = cut_len;
+        }
+    }
+
+    pub fn transform(&self, mut info: LogInfo) -> Option<LogInfo> {
+        let mut truncated = self
+            .message_length
+            .is_some_and(|length| self.truncate(&mut info.message, length));
+        if self.string_length.is_some() || self.array_length.is_some() || self.depth.is_some() {
+            for value in info.meta.values_mut() {
+                truncated |= self.limit_value(value, 0);
+            }
+        }
+        if truncated {
+            info.meta.insert(TRUNCATED, Value::Bool(true));
+        }
+        if let Some(max_bytes) = self.max_bytes {
+            self.fit(&mut info, max_bytes);
+        }
+        Some(info)
+    }
+}
+
+impl Format for LimitFormat {
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        self.transform(info)
+    }
+}
+
+pub fn limit() -> LimitFormat {
+    LimitFormat::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_limit_strings_are_utf8_safe() {
+        let info = LogInfo::new("info", "héllo wörld")
+            .with_meta("city", "Zürich")
+            .with_meta("nested", json!({"emoji": "🦀🦀🦀"}));
+
+        let result = limit()
+            .with_message_length(5)
+            .with_string_length(2)
+            .transform(info)
+            .unwrap();
+
+        assert_eq!(result.message, "héllo…");
+        assert_eq!(result.meta["city"], json!("Zü…"));
+        assert_eq!(result.meta["nested"], json!({"emoji": "🦀🦀…"}));
+        assert_eq!(result.meta["_truncated"], json!(true));
+    }
+
+    #[test]
+    fn test_limit_arrays_and_depth() {
+        let info = LogInfo::new("info", "x")
+            .with_meta("ids", json!([1, 2, 3, 4]))
+            .with_meta("deep", json!({"a": {"b": {"c": 1}}, "list": [[1]]}));
+
+        let result = limit()
+            .with_array_length(2)
+            .with_depth(2)
+            .transform(info)
+            .unwrap();
+
+        assert_eq!(result.meta["ids"], json!([1, 2]));
+        assert_eq!(
+            result.meta["deep"],
+            json!({"a": {"b": "[Object]"}, "list": ["[Array]"]})
+        );
+    }
+
+    #[test]
+    fn test_limit_untouched_entry_has_no_flag() {
+        let info = LogInfo::new("info", "short").with_meta("k", "v");
+        let result = limit()
+            .with_message_length(100)
+            .with_string_length(100)
+            .with_max_bytes(1000)
+            .transform(info)
+            .unwrap();
+        assert!(!result.meta.contains_key("_truncated"));
+    }
+
+    #[test]
+    fn test_limit_byte_budget_drops_largest_keys_first() {
+        let info = LogInfo::new("info", "request done")
+            .with_meta("body", "x".repeat(500))
+            .with_meta("status", 200)
+            .with_meta("headers", "y".repeat(100));
+
+        let result = limit().with_max_bytes(120).transform(info).unwrap();
+
+        assert!(!result.meta.contains_key("body"));
+        assert!(!result.meta.contains_key("headers"));
+        assert_eq!(result.meta["status"], json!(200));
+        assert_eq!(result.meta["_truncated"], json!(true));
+        assert!(LimitFormat::serialized_len(&result) <= 120);
+    }
+
+    #[test]
+    fn test_limit_byte_budget_cuts_message_last() {
+        let info = LogInfo::new("info", "ü".repeat(200)).with_meta("k", "v");
+        let result = limit().with_max_bytes(100).transform(info).unwrap();
+
+        assert!(!result.meta.contains_key("k"));
+        assert!(result.message.ends_with('…'));
+        assert!(LimitFormat::serialized_len(&result) <= 100);
+    }
+
+    #[test]
+    fn test_limit_byte_budget_tracks_escaped_and_shadowing_fields() {
+        let info = LogInfo::new("info", "line\n\"quoted\"\t".repeat(20))
+            .with_meta("level", "custom level name that is rather long")
+            .with_meta("path", "C:\\logs\\".repeat(10))
+            .with_meta("status", 200);
+
+        for max_bytes in [60, 90, 150, 250] {
+            let result = limit()
+                .with_max_bytes(max_bytes)
+                .transform(info.clone())
+                .unwrap();
+            assert!(
+                LimitFormat::serialized_len(&result) <= max_bytes,
+                "max_bytes {}",
+                max_bytes
+            );
+            assert_eq!(result.meta["_truncated"], json!(true));
+        }
+    }
+}
@@ -0,0 +1,370 @@
+use super::Format;
+use crate::LogInfo;
+use serde_json::Value;
+use std::{collections::HashMap, fmt::Write};
+
+/// Renders entries as logfmt: `level=info msg="User logged in" user_id=42`.
+///
+/// Nested meta objects are flattened into dotted keys (`http.status=200`).
+/// Values containing spaces, `=`, quotes or control characters are quoted and
+/// escaped, as are strings that would otherwise read back as a number, bool or
+/// null, so `parse::logfmt()` recovers the original types.
+///
+/// The entry's own time is written as `ts` when meta has no `timestamp` (as
+/// `timestamp()` sets). A meta key that clashes with a key already written
+/// (`level`, the message key, that `ts`, or a dotted key that flattening also
+/// produces) is written with a `fields.` prefix rather than replacing it.
+#[derive(Clone)]
+pub struct LogfmtFormat {
+    order: Vec<String>,
+    message_key: String,
+    flatten: bool,
+}
+
+impl Default for LogfmtFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogfmtFormat {
+    pub fn new() -> Self {
+        Self {
+            order: vec!["level".to_string(), "message".to_string()],
+            message_key: "msg".to_string(),
+            flatten: true,
+        }
+    }
+
+    /// Keys written first, in this order; everything else follows in meta
+    /// order. `message` refers to the message whatever its key, and meta keys
+    /// use their dotted names, e.g. `["timestamp", "level", "message"]`.
+    pub fn with_order<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.order = keys.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Key the message is written under (default: `msg`).
+    pub fn with_message_key(mut self, key: &str) -> Self {
+        self.message_key = key.to_string();
+        self
+    }
+
+    /// Whether nested objects become dotted keys (default) or JSON strings.
+    pub fn with_flatten(mut self, flatten: bool) -> Self {
+        self.flatten = flatten;
+        self
+    }
+
+    /// Adds a field, or its flattened children, under a key not yet written.
+    /// `written` maps each key to its index in `fields`.
+    fn push_field(
+        &self,
+        fields: &mut Vec<(String, Value)>,
+        written: &mut HashMap<String, usize>,
+        key: String,
+        value: Value,
+    ) {
+        match value {
+            Value::Object(object) if self.flatten && !object.is_empty() => {
+                for (child, value) in object {
+                    self.push_field(fields, written, format!("{}.{}", key, child), value);
+                }
+            }
+            value => {
+                let mut key = key;
+                while written.contains_key(&key) {
+                    key.insert_str(0, "fields.");
+                }
+                written.insert(key.clone(), fields.len());
+                fields.push((key, value));
+            }
+        }
+    }
+
+    fn write_key(out: &mut String, key: &str) {
+        for c in key.chars() {
+            if c.is_whitespace() || c == '=' || c == '"' || c.is_control() {
+                out.push('_');
+            } else {
+                out.push(c);
+            }
+        }
+    }
+
+    fn needs_quotes(value: &str) -> bool {
+        value.is_empty()
+            || value
+                .chars()
+                .any(|c| c.is_whitespace() || c == '=' || c == '"' || c == '\\' || c.is_control())
+            || matches!(value, "true" | "false" | "null")
+            || value.parse::<serde_json::Number>().is_ok()
+    }
+
+    fn write_string(out: &mut String, value: &str) {
+        if !Self::needs_quotes(value) {
+            out.push_str(value);
+            return;
+        }
+        out.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if c.is_control() => {
+                    let _ = write!(out, "\\u{{{:04x}}}", c as u32);
+                }
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+
+    fn write_value(out: &mut String, value: &Value) {
+        match value {
+            Value::String(s) => Self::write_string(out, s),
+            Value::Array(_) | Value::Object(_) => Self::write_string(out, &value.to_string()),
+            other => {
+                let _ = write!(out, "{}", other);
+            }
+        }
+    }
+
+    pub fn transform(&self, info: LogInfo) -> Option<LogInfo> {
+        let mut fields = Vec::with_capacity(info.meta.len() + 3);
+        let mut written = HashMap::with_capacity(info.meta.len() + 3);
+        self.push_field(
+            &mut fields,
+            &mut written,
+            "level".to_string(),
+            Value::String(info.level.to_string()),
+        );
+        self.push_field(
+            &mut fields,
+            &mut written,
+            self.message_key.clone(),
+            Value::String(info.message.clone()),
+        );
+        let ts = match info.timestamp {
+            Some(timestamp) if !info.meta.contains_key("timestamp") => {
+                self.push_field(
+                    &mut fields,
+                    &mut written,
+                    "ts".to_string(),
+                    Value::String(LogInfo::format_timestamp(&timestamp)),
+                );
+                true
+            }
+            _ => false,
+        };
+        for (key, value) in &info.meta {
+            self.push_field(&mut fields, &mut written, key.to_string(), value.clone());
+        }
+
+        let mut taken = vec![false; fields.len()];
+        let mut ordered = Vec::with_capacity(fields.len());
+        for name in &self.order {
+            let key = match name.as_str() {
+                "message" => &self.message_key,
+                "timestamp" if ts => "ts",
+                _ => name,
+            };
+            if let Some(&index) = written.get(key) {
+                if !std::mem::replace(&mut taken[index], true) {
+                    ordered.push(index);
+                }
+            }
+        }
+        ordered.extend((0..fields.len()).filter(|&index| !taken[index]));
+
+        let mut line = String::new();
+        for (key, value) in ordered.into_iter().map(|index| &fields[index]) {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            Self::write_key(&mut line, key);
+            line.push('=');
+            Self::write_value(&mut line, value);
+        }
+
+        Some(LogInfo {
+            formatted: Some(line),
+            ..info
+        })
+    }
+}
+
+impl Format for LogfmtFormat {
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        self.transform(info)
+    }
+}
+
+pub fn logfmt() -> LogfmtFormat {
+    LogfmtFormat::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{self, Parser};
+    use serde_json::json;
+
+    #[test]
+    fn test_logfmt_format() {
+        let info = LogInfo::new("info", "User logged in")
+            .with_meta("user_id", 42)
+            .with_meta("admin", false);
+
+        let result = logfmt().transform(info).unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(r#"level=info msg="User logged in" user_id=42 admin=false"#)
+        );
+    }
+
+    #[test]
+    fn test_logfmt_quotes_and_escapes() {
+        let info = LogInfo::new("warn", "say \"hi\"\nbye")
+            .with_meta("path", "C:\\temp")
+            .with_meta("query", "a=b")
+            .with_meta("empty", "")
+            .with_meta("zip", "01234")
+            .with_meta("count", "42")
+            .with_meta("bad key", "x");
+
+        let result = logfmt().transform(info).unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(
+                r#"level=warn msg="say \"hi\"\nbye" path="C:\\temp" query="a=b" empty="" zip=01234 count="42" bad_key=x"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_logfmt_key_order_and_message_key() {
+        let info = LogInfo::new("info", "Started")
+            .with_meta("port", 8080)
+            .with_meta("timestamp", "2024-04-01T12:30:00Z");
+
+        let result = logfmt()
+            .with_order(["timestamp", "level", "message"])
+            .with_message_key("message")
+            .transform(info)
+            .unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some("timestamp=2024-04-01T12:30:00Z level=info message=Started port=8080")
+        );
+    }
+
+    #[test]
+    fn test_logfmt_flattens_nested_meta() {
+        let info = LogInfo::new("info", "Handled").with_meta(
+            "http",
+            json!({"method": "GET", "status": 200, "tags": ["a", "b"]}),
+        );
+
+        let flat = logfmt().transform(info.clone()).unwrap();
+        assert_eq!(
+            flat.formatted.as_deref(),
+            Some(
+                r#"level=info msg=Handled http.method=GET http.status=200 http.tags="[\"a\",\"b\"]""#
+            )
+        );
+
+        let nested = logfmt().with_flatten(false).transform(info).unwrap();
+        assert_eq!(
+            nested.formatted.as_deref(),
+            Some(
+                r#"level=info msg=Handled http="{\"method\":\"GET\",\"status\":200,\"tags\":[\"a\",\"b\"]}""#
+            )
+        );
+    }
+
+    #[test]
+    fn test_logfmt_roundtrips_through_parser() {
+        let info = LogInfo::new("error", "Request \"failed\"")
+            .with_meta("attempt", 3)
+            .with_meta("code", "500")
+            .with_meta("http", json!({"method": "POST", "ok": false}));
+
+        let line = logfmt().transform(info.clone()).unwrap().formatted.unwrap();
+        let parsed = parse::logfmt().with_unflatten(true).parse(&line).unwrap();
+
+        assert_eq!(parsed.level, "error");
+        assert_eq!(parsed.message, info.message);
+        assert_eq!(parsed.meta, info.meta);
+    }
+
+    #[test]
+    fn test_logfmt_keeps_level_and_message_over_meta() {
+        let info = LogInfo::new("warn", "Disk low")
+            .with_meta("level", "debug")
+            .with_meta("msg", "shadow")
+            .with_meta("mount", "/var");
+
+        let result = logfmt().transform(info).unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(r#"level=warn msg="Disk low" fields.level=debug fields.msg=shadow mount=/var"#)
+        );
+    }
+
+    #[test]
+    fn test_logfmt_prefixes_keys_that_clash_after_flattening() {
+        let info = LogInfo::new("info", "x")
+            .with_meta("http.status", 200)
+            .with_meta("http", json!({"status": 503}))
+            .with_meta("fields.http.status", "taken");
+
+        let result = logfmt().transform(info).unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(
+                "level=info msg=x http.status=200 fields.http.status=503 fields.fields.http.status=taken"
+            )
+        );
+    }
+
+    #[test]
+    fn test_logfmt_writes_entry_time_as_ts() {
+        use chrono::{TimeZone, Utc};
+
+        let mut info = LogInfo::new("info", "Started").with_meta("ts", 1);
+        info.timestamp = Some(Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap());
+
+        let line = logfmt()
+            .with_order(["timestamp", "level", "message"])
+            .transform(info.clone())
+            .unwrap()
+            .formatted
+            .unwrap();
+        assert_eq!(
+            line,
+            "ts=2024-05-01T12:00:00Z level=info msg=Started fields.ts=1"
+        );
+
+        let parsed = parse::logfmt().parse(&line).unwrap();
+        assert_eq!(parsed.timestamp, info.timestamp);
+        assert!(!parsed.meta.contains_key("ts"));
+
+        // A `timestamp` meta value takes its place
+        let stamped = info.with_meta("timestamp", "2024-05-01T12:00:01Z");
+        let line = logfmt().transform(stamped).unwrap().formatted.unwrap();
+        assert_eq!(
+            line,
+            "level=info msg=Started ts=1 timestamp=2024-05-01T12:00:01Z"
+        );
+    }
+}
@@ -1,68 +1,95 @@
 use chrono::DateTime;
 use chrono::Utc;
-use serde_json::json;
+use serde::ser::{Serialize, SerializeMap, Serializer};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::io;
 
 use crate::LogInfo;
 
-use super::Format;
+use super::{formatted, into_string, Finalizer, FormatError};
 
 pub struct LogstashFormat;
 
-impl Format for LogstashFormat {
-    type Input = LogInfo;
-
-    fn transform(&self, mut info: LogInfo) -> Option<Self::Input> {
-        let mut logstash_object = json!({"@message": info.message});
-
-        // The timestamp is expected to be a String in the meta map.
-        let ts = match info.meta.remove("timestamp") {
-            Some(Value::String(s)) => s,
-            Some(Value::Number(num)) => {
-                if let Some(epoch_secs) = num.as_i64() {
-                    DateTime::<Utc>::from_timestamp(epoch_secs, 0)
-                        .map(|dt| dt.to_rfc3339())
-                        .unwrap_or_else(|| {
-                            eprintln!("Invalid epoch_secs for timestamp: {}", epoch_secs);
-                            Utc::now().to_rfc3339()
-                        })
-                } else {
-                    eprintln!("Non-i64 number for timestamp: {}", num);
-                    Utc::now().to_rfc3339()
-                }
-            }
-            Some(other) => {
-                eprintln!("Unexpected type for timestamp: {:?}", other);
-                Utc::now().to_rfc3339()
-            }
-            None => Utc::now().to_rfc3339(),
-        };
-        logstash_object["@timestamp"] = json!(ts);
+impl LogstashFormat {
+    /// The `@timestamp` value: the `timestamp` meta value (a string, or epoch
+    /// seconds), else the entry's time.
+    fn timestamp(info: &LogInfo) -> Result<String, String> {
+        match info.meta.get("timestamp") {
+            Some(Value::String(s)) => Ok(s.clone()),
+            Some(Value::Number(num)) => num
+                .as_i64()
+                .and_then(|epoch_secs| DateTime::<Utc>::from_timestamp(epoch_secs, 0))
+                .map(|dt| dt.to_rfc3339())
+                .ok_or_else(|| format!("invalid epoch seconds for timestamp: {}", num)),
+            Some(other) => Err(format!("unexpected type for timestamp: {}", other)),
+            None => Ok(LogInfo::format_timestamp(
+                &info.timestamp.unwrap_or_else(Utc::now),
+            )),
+        }
+    }
+}
 
-        let mut fields = HashMap::new();
-        fields.insert("level".to_string(), json!(info.level.clone()));
+/// `{"@message": .., "@timestamp": .., "@fields": {"level": .., ..meta}}`,
+/// serialized straight from the `LogInfo`.
+struct LogstashLine<'a> {
+    info: &'a LogInfo,
+    timestamp: String,
+}
 
-        for (key, value) in info.meta.iter() {
-            fields.insert(key.clone(), value.clone());
-        }
+impl Serialize for LogstashLine<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("@message", &self.info.message)?;
+        map.serialize_entry("@timestamp", &self.timestamp)?;
+        map.serialize_entry("@fields", &Fields(self.info))?;
+        map.end()
+    }
+}
+
+struct Fields<'a>(&'a LogInfo);
 
-        logstash_object["@fields"] = json!(fields);
-
-        // Handle serialization errors gracefully
-        match serde_json::to_string(&logstash_object) {
-            Ok(serialized) => Some(LogInfo {
-                formatted: Some(serialized),
-                ..info
-            }),
-            Err(e) => {
-                eprintln!("LogstashFormat: failed to serialize logstash object: {}", e);
-                None
+impl Serialize for Fields<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let info = self.0;
+        let mut map = serializer.serialize_map(None)?;
+        // A `level` meta value takes the level's place
+        match info.meta.get("level") {
+            Some(level) => map.serialize_entry("level", level)?,
+            None => map.serialize_entry("level", &info.level)?,
+        }
+        for (key, value) in info.meta.iter() {
+            if key.as_str() != "level" && key.as_str() != "timestamp" {
+                map.serialize_entry(key.as_str(), value)?;
             }
         }
+        map.end()
+    }
+}
+
+impl Finalizer for LogstashFormat {
+    fn write_to(&self, info: &LogInfo, writer: &mut dyn io::Write) -> io::Result<()> {
+        let line = LogstashLine {
+            info,
+            timestamp: Self::timestamp(info)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+        };
+        serde_json::to_writer(writer, &line).map_err(io::Error::from)
+    }
+}
+
+impl LogstashFormat {
+    pub fn try_transform(&self, mut info: LogInfo) -> Result<Option<LogInfo>, FormatError> {
+        let mut buffer = Vec::new();
+        let output = self.write_to(&info, &mut buffer).and_then(|()| {
+            info.meta.remove("timestamp");
+            into_string(buffer)
+        });
+        formatted("LogstashFormat", info, output)
     }
 }
 
+fallible_format!(LogstashFormat);
+
 pub fn logstash() -> LogstashFormat {
     LogstashFormat
 }
@@ -85,7 +112,8 @@ mod tests {
         assert_eq!(parsed["@fields"]["level"], "info");
     }
     use super::*;
-    use serde_json::Value;
+    use crate::Format;
+    use serde_json::{json, Value};
 
     #[test]
     fn test_logstash_format() {
@@ -117,6 +145,45 @@ mod tests {
         assert_eq!(parsed["@fields"]["level"], "info");
     }
 
+    #[test]
+    fn test_logstash_format_is_deterministic() {
+        let info = LogInfo::new("info", "Test message")
+            .with_meta("timestamp", "2025-09-05T12:34:56Z")
+            .with_meta("user_id", "1234")
+            .with_meta("attempt", 2);
+
+        let result = LogstashFormat.transform(info).unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(
+                r#"{"@message":"Test message","@timestamp":"2025-09-05T12:34:56Z","@fields":{"level":"info","user_id":"1234","attempt":2}}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_logstash_format_timestamp_values() {
+        let epoch = LogInfo::new("info", "x")
+            .with_meta("timestamp", 1_700_000_000)
+            .with_meta("user_id", "1234");
+        let result = LogstashFormat.transform(epoch).unwrap();
+        let parsed: Value = serde_json::from_str(result.formatted.as_deref().unwrap()).unwrap();
+        assert_eq!(parsed["@timestamp"], "2023-11-14T22:13:20+00:00");
+        // As before: the timestamp is only written as `@timestamp`
+        assert_eq!(
+            parsed["@fields"],
+            json!({"level": "info", "user_id": "1234"})
+        );
+        assert!(!result.meta.contains_key("timestamp"));
+
+        for bad in [json!(1.5), json!(true), json!(i64::MAX)] {
+            let info = LogInfo::new("info", "x").with_meta("timestamp", bad);
+            let error = LogstashFormat.try_transform(info.clone()).unwrap_err();
+            assert_eq!(error.format(), "LogstashFormat");
+            assert!(LogstashFormat.write_to(&info, &mut Vec::new()).is_err());
+        }
+    }
+
     #[test]
     fn test_logstash_format_with_no_timestamp_in_meta() {
         let logstash_format = LogstashFormat;
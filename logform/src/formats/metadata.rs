@@ -1,6 +1,5 @@
-use crate::LogInfo;
-use serde_json::json;
-use std::collections::HashMap;
+use crate::{LogInfo, MetaKey};
+use serde_json::{Map, Value};
 use std::collections::HashSet;
 
 use super::Format;
@@ -46,33 +45,25 @@ impl Format for MetadataFormat {
     type Input = LogInfo;
 
     fn transform(&self, mut info: LogInfo) -> Option<Self::Input> {
-        let mut metadata = HashMap::new();
-
-        if !self.fill_with.is_empty() {
-            for key in &self.fill_with {
-                if self.fill_except.contains(key) {
-                    continue;
-                }
-                if let Some(value) = info.meta.remove(key) {
-                    metadata.insert(key.clone(), value);
-                }
-            }
-        } else {
-            // Collect keys to move to avoid cloning the whole map
-            let keys_to_move: Vec<String> = info
-                .meta
-                .keys()
-                .filter(|key| !self.fill_except.contains(*key))
-                .cloned()
-                .collect();
-            for key in keys_to_move {
-                if let Some(value) = info.meta.remove(&key) {
-                    metadata.insert(key, value);
-                }
+        let mut metadata = Map::new();
+
+        // Walk meta in order so the nested object keeps the logged field order
+        let keys_to_move: Vec<MetaKey> = info
+            .meta
+            .keys()
+            .filter(|key| {
+                (self.fill_with.is_empty() || self.fill_with.contains(key.as_str()))
+                    && !self.fill_except.contains(key.as_str())
+            })
+            .cloned()
+            .collect();
+        for key in keys_to_move {
+            if let Some(value) = info.meta.remove(&key) {
+                metadata.insert(key.into(), value);
             }
         }
 
-        info.meta.insert(self.key.clone(), json!(metadata));
+        info.meta.insert(self.key.clone(), Value::Object(metadata));
         Some(info)
     }
 }
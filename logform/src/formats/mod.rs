@@ -1,20 +1,61 @@
+/// Implements [`Format`] for a `LogInfo` format from its inherent
+/// `try_transform`; `transform` reports failures on stderr and drops the entry.
+macro_rules! fallible_format {
+    ($format:ty) => {
+        impl $crate::formats::Format for $format {
+            type Input = $crate::LogInfo;
+
+            fn transform(&self, info: $crate::LogInfo) -> Option<$crate::LogInfo> {
+                $crate::formats::report(self.try_transform(info))
+            }
+
+            fn try_transform(
+                &self,
+                info: $crate::LogInfo,
+            ) -> Result<Option<$crate::LogInfo>, $crate::formats::FormatError> {
+                self.try_transform(info)
+            }
+        }
+    };
+}
+
 pub mod align;
+pub mod branch;
 pub mod cli;
 pub mod colorize;
+pub mod csv;
+pub mod dev;
+pub mod ecs;
+pub mod enrich;
+pub mod errors;
+pub mod fields;
+pub mod filter;
+mod finalizer;
 mod format;
+pub mod gelf;
 pub mod json;
 pub mod label;
+pub mod limit;
+pub mod logfmt;
 pub mod logstash;
 mod macros;
 pub mod metadata;
 pub mod ms;
+pub mod otel;
 pub mod pad_levels;
 pub mod pretty_print;
 pub mod printf;
+pub mod redact;
 pub mod simple;
+pub mod splat;
+pub mod syslog;
+pub mod template;
 pub mod timestamp;
 pub mod uncolorize;
-pub use format::Format;
+pub(crate) use finalizer::into_string;
+pub use finalizer::Finalizer;
+pub(crate) use format::{formatted, report};
+pub use format::{ChainedFormat, FanoutFormat, Format, FormatError, MapFormat};
 pub mod passthrough;
 /* chaining of formats can be achieved by the `.chain` method on the `Format`
 instance hence the `combine` format is not needed  */
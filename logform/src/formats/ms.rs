@@ -1,11 +1,11 @@
 use crate::LogInfo;
+use chrono::{DateTime, Utc};
 use std::sync::Mutex;
-use std::time::Instant;
 
 use super::Format;
 
 pub struct MsFormat {
-    prev_time: Mutex<Option<Instant>>,
+    prev_time: Mutex<Option<DateTime<Utc>>>,
 }
 
 impl Default for MsFormat {
@@ -26,11 +26,12 @@ impl Format for MsFormat {
     type Input = LogInfo;
 
     fn transform(&self, mut input: LogInfo) -> Option<Self::Input> {
-        let curr = Instant::now();
+        // Measure between the entries' own timestamps so queued entries keep their spacing.
+        let curr = input.timestamp.unwrap_or_else(Utc::now);
         let mut prev_time = self.prev_time.lock().ok()?;
         let diff = match *prev_time {
-            Some(prev) => curr.duration_since(prev),
-            None => std::time::Duration::from_millis(0), // first call → +0ms
+            Some(prev) => (curr - prev).max(chrono::Duration::zero()),
+            None => chrono::Duration::zero(), // first call → +0ms
         };
 
         // update stored time
@@ -39,7 +40,7 @@ impl Format for MsFormat {
         // Add the time difference in milliseconds to the `info` meta
         input
             .meta
-            .insert("ms".to_string(), format!("+{}ms", diff.as_millis()).into());
+            .insert("ms", format!("+{}ms", diff.num_milliseconds()).into());
 
         Some(input)
     }
@@ -84,4 +85,18 @@ mod tests {
             ms2_value
         );
     }
+
+    #[test]
+    fn test_ms_uses_captured_timestamps() {
+        let formatter = ms();
+        let start = Utc::now();
+
+        let first = LogInfo::new("info", "first").with_timestamp(start);
+        let second = LogInfo::new("info", "second")
+            .with_timestamp(start + chrono::Duration::milliseconds(1500));
+
+        formatter.transform(first).unwrap();
+        let result = formatter.transform(second).unwrap();
+        assert_eq!(result.meta["ms"], "+1500ms");
+    }
 }
@@ -0,0 +1,266 @@
+use super::{formatted, FormatError};
+use crate::LogInfo;
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Renders entries as OTLP/JSON `LogRecord` objects.
+///
+/// `timeUnixNano` comes from the entry's timestamp, `severityText` is the level
+/// name and `severityNumber` its OpenTelemetry severity (`trace` → 1, `debug` →
+/// 5, `info` → 9, `warn` → 13, `error` → 17, `crit`/`fatal` → 21, with the
+/// other npm and syslog names in between). The message becomes the string
+/// `body` and meta becomes `attributes` typed as OTLP `AnyValue`s. Hex trace
+/// and span ids under `trace_id`/`traceId` and `span_id`/`spanId` are lifted
+/// into `traceId`/`spanId`.
+///
+/// Each record is one object; exporters wrap batches in `resourceLogs`/`scopeLogs`.
+#[derive(Clone, Default)]
+pub struct OtelFormat {
+    levels: HashMap<String, u8>,
+}
+
+impl OtelFormat {
+    pub fn new() -> Self {
+        Self {
+            levels: HashMap::new(),
+        }
+    }
+
+    /// Maps a custom level name onto an OpenTelemetry severity number (1-24).
+    pub fn with_level(mut self, level: &str, severity: u8) -> Self {
+        self.levels.insert(level.to_string(), severity.min(24));
+        self
+    }
+
+    /// OpenTelemetry severity number for `level`, or 0 (unspecified) if unknown.
+    pub fn severity(&self, level: &str) -> u8 {
+        if let Some(severity) = self.levels.get(level) {
+            return *severity;
+        }
+        match level.to_ascii_lowercase().as_str() {
+            "trace" | "silly" => 1,
+            "debug" => 5,
+            "verbose" => 6,
+            "http" => 7,
+            "info" | "help" | "data" | "prompt" | "input" => 9,
+            "notice" => 10,
+            "warn" | "warning" => 13,
+            "error" | "err" => 17,
+            "crit" | "critical" | "fatal" => 21,
+            "alert" => 22,
+            "emerg" | "emergency" | "panic" => 23,
+            _ => 0,
+        }
+    }
+
+    /// Converts a JSON value into an OTLP `AnyValue`.
+    pub fn any_value(value: &Value) -> Value {
+        match value {
+            Value::Null => json!({}),
+            Value::Bool(b) => json!({ "boolValue": b }),
+            Value::Number(n) => match n.as_i64() {
+                // OTLP/JSON encodes 64-bit integers as strings
+                Some(i) => json!({ "intValue": i.to_string() }),
+                None => match n.as_f64() {
+                    Some(f) if n.is_f64() => json!({ "doubleValue": f }),
+                    _ => json!({ "stringValue": n.to_string() }),
+                },
+            },
+            Value::String(s) => json!({ "stringValue": s }),
+            Value::Array(values) => json!({
+                "arrayValue": { "values": values.iter().map(Self::any_value).collect::<Vec<_>>() }
+            }),
+            Value::Object(object) => json!({
+                "kvlistValue": { "values": Self::key_values(object.iter()) }
+            }),
+        }
+    }
+
+    fn key_values<'a, K: AsRef<str> + 'a>(
+        entries: impl Iterator<Item = (K, &'a Value)>,
+    ) -> Vec<Value> {
+        entries
+            .map(|(key, value)| json!({ "key": key.as_ref(), "value": Self::any_value(value) }))
+            .collect()
+    }
+
+    /// A valid hex id of `len` digits, lowercased. Anything else is kept as
+    /// an attribute instead.
+    fn hex_id(value: &Value, len: usize) -> Option<String> {
+        match value {
+            Value::String(id) if id.len() == len && id.chars().all(|c| c.is_ascii_hexdigit()) => {
+                Some(id.to_ascii_lowercase())
+            }
+            _ => None,
+        }
+    }
+
+    fn unix_nanos(time: DateTime<Utc>) -> String {
+        time.timestamp_nanos_opt()
+            .map(|nanos| nanos.max(0).to_string())
+            .unwrap_or_else(|| "0".to_string())
+    }
+
+    pub fn try_transform(&self, info: LogInfo) -> Result<Option<LogInfo>, FormatError> {
+        let time = info.time();
+
+        let mut ids = Vec::new();
+        let mut attributes = Vec::new();
+        for (key, value) in info.meta.iter() {
+            let id = match key.as_ref() {
+                "timestamp" => continue,
+                "trace_id" | "traceId" => Self::hex_id(value, 32).map(|id| ("traceId", id)),
+                "span_id" | "spanId" => Self::hex_id(value, 16).map(|id| ("spanId", id)),
+                _ => None,
+            };
+            match id {
+                Some(id) => ids.push(id),
+                None => attributes.push((key, value)),
+            }
+        }
+
+        let mut record = IndexMap::new();
+        record.insert("timeUnixNano".to_string(), json!(Self::unix_nanos(time)));
+        record.insert(
+            "observedTimeUnixNano".to_string(),
+            json!(Self::unix_nanos(Utc::now())),
+        );
+        record.insert(
+            "severityNumber".to_string(),
+            json!(self.severity(&info.level)),
+        );
+        record.insert("severityText".to_string(), json!(info.level));
+        record.insert("body".to_string(), json!({ "stringValue": info.message }));
+        record.insert(
+            "attributes".to_string(),
+            Value::Array(Self::key_values(attributes.into_iter())),
+        );
+        for (field, id) in ids {
+            record.entry(field.to_string()).or_insert(Value::String(id));
+        }
+
+        let output = serde_json::to_string(&record);
+        formatted("OtelFormat", info, output)
+    }
+}
+
+fallible_format!(OtelFormat);
+
+pub fn otel() -> OtelFormat {
+    OtelFormat::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Format;
+    use chrono::TimeZone;
+
+    fn render(format: &OtelFormat, info: LogInfo) -> Value {
+        let result = format.transform(info).unwrap();
+        serde_json::from_str(result.formatted.as_deref().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_otel_format() {
+        let time = Utc.timestamp_nanos(1_712_000_000_123_456_789);
+        let info = LogInfo::new("warn", "Disk almost full")
+            .with_timestamp(time)
+            .with_meta("trace_id", "5B8EFFF798038103D269B633813FC60C")
+            .with_meta("span_id", "eee19b7ec3c1b174")
+            .with_meta("mount", "/var");
+
+        let parsed = render(&otel(), info);
+        assert_eq!(parsed["timeUnixNano"], "1712000000123456789");
+        assert!(parsed["observedTimeUnixNano"].is_string());
+        assert_eq!(parsed["severityNumber"], 13);
+        assert_eq!(parsed["severityText"], "warn");
+        assert_eq!(parsed["body"], json!({"stringValue": "Disk almost full"}));
+        assert_eq!(parsed["traceId"], "5b8efff798038103d269b633813fc60c");
+        assert_eq!(parsed["spanId"], "eee19b7ec3c1b174");
+        assert_eq!(
+            parsed["attributes"],
+            json!([{"key": "mount", "value": {"stringValue": "/var"}}])
+        );
+    }
+
+    #[test]
+    fn test_otel_attributes_are_any_values() {
+        let info = LogInfo::new("info", "Handled")
+            .with_meta("count", 3)
+            .with_meta("ratio", 0.5)
+            .with_meta("ok", true)
+            .with_meta("missing", Value::Null)
+            .with_meta("tags", json!(["a", 1]))
+            .with_meta("http", json!({"status": 200}))
+            .with_meta("traceId", "not-a-trace-id");
+
+        let parsed = render(&otel(), info);
+        assert!(parsed.get("traceId").is_none());
+        assert_eq!(
+            parsed["attributes"],
+            json!([
+                {"key": "count", "value": {"intValue": "3"}},
+                {"key": "ratio", "value": {"doubleValue": 0.5}},
+                {"key": "ok", "value": {"boolValue": true}},
+                {"key": "missing", "value": {}},
+                {"key": "tags", "value": {"arrayValue": {"values": [
+                    {"stringValue": "a"}, {"intValue": "1"}
+                ]}}},
+                {"key": "http", "value": {"kvlistValue": {"values": [
+                    {"key": "status", "value": {"intValue": "200"}}
+                ]}}},
+                {"key": "traceId", "value": {"stringValue": "not-a-trace-id"}}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_otel_severity_mapping() {
+        let format = otel().with_level("audit", 10);
+
+        let cases = [
+            ("trace", 1),
+            ("debug", 5),
+            ("info", 9),
+            ("notice", 10),
+            ("warning", 13),
+            ("error", 17),
+            ("crit", 21),
+            ("emerg", 23),
+            ("audit", 10),
+            ("custom", 0),
+        ];
+        for (level, severity) in cases {
+            assert_eq!(format.severity(level), severity, "level {}", level);
+        }
+    }
+
+    #[test]
+    fn test_otel_keeps_meta_on_the_entry() {
+        let info = LogInfo::new("info", "Handled")
+            .with_meta("trace_id", "5b8efff798038103d269b633813fc60c")
+            .with_meta("user", "bob")
+            .with_meta("timestamp", "2024-04-01T12:30:00Z");
+
+        let result = otel().transform(info).unwrap();
+        assert_eq!(
+            result.meta.get("trace_id"),
+            Some(&json!("5b8efff798038103d269b633813fc60c"))
+        );
+        assert_eq!(result.meta.get("user"), Some(&json!("bob")));
+        assert_eq!(
+            result.meta.get("timestamp"),
+            Some(&json!("2024-04-01T12:30:00Z"))
+        );
+
+        let parsed: Value = serde_json::from_str(result.formatted.as_deref().unwrap()).unwrap();
+        assert_eq!(parsed["timeUnixNano"], "1711974600000000000");
+        assert_eq!(
+            parsed["attributes"],
+            json!([{"key": "user", "value": {"stringValue": "bob"}}])
+        );
+    }
+}
@@ -62,7 +62,7 @@ impl Padder {
     }
 
     pub fn transform(&self, mut info: LogInfo) -> Option<LogInfo> {
-        if let Some(padding) = self.paddings.get(&info.level) {
+        if let Some(padding) = self.paddings.get(info.level.as_ref()) {
             info.message = format!("{}{}", padding, info.message);
         }
         Some(info)
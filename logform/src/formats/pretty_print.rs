@@ -26,11 +26,11 @@ impl PrettyPrinter {
 
     fn format_log(&self, info: LogInfo) -> LogInfo {
         let mut json_output = Map::new();
-        json_output.insert("level".to_string(), Value::String(info.level.clone()));
+        json_output.insert("level".to_string(), Value::String(info.level.to_string()));
         json_output.insert("message".to_string(), Value::String(info.message.clone()));
 
         for (key, value) in &info.meta {
-            json_output.insert(key.clone(), value.clone());
+            json_output.insert(key.to_string(), value.clone());
         }
 
         let json_value = Value::Object(json_output);
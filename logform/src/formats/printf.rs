@@ -1,7 +1,7 @@
 use crate::LogInfo;
-use std::sync::Arc;
+use std::{io, sync::Arc};
 
-use super::Format;
+use super::{Finalizer, Format};
 
 #[derive(Clone)]
 pub struct Printf {
@@ -31,6 +31,12 @@ impl Format for Printf {
     }
 }
 
+impl Finalizer for Printf {
+    fn write_to(&self, info: &LogInfo, writer: &mut dyn io::Write) -> io::Result<()> {
+        writer.write_all((self.template)(info).as_bytes())
+    }
+}
+
 pub fn printf<T>(template_fn: T) -> Printf
 where
     T: Fn(&LogInfo) -> String + Send + Sync + 'static,
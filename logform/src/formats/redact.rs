@@ -0,0 +1,335 @@
+use super::Format;
+use crate::{path::FieldPath, LogInfo};
+use regex::{Captures, Regex};
+use serde_json::Value;
+#[cfg(feature = "hash")]
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+
+/// How a redacted value (or pattern match) is replaced.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Redaction {
+    /// Drops the meta key, or deletes the matched text.
+    Remove,
+    /// Replaces the value with a fixed mask.
+    Mask(String),
+    /// Masks all but the last `n` characters with `*`, e.g. `************1111`.
+    Partial(usize),
+    /// Replaces the value with the first 16 hex digits of `sha256(salt + value)`,
+    /// so equal values can still be correlated across entries. Requires the
+    /// `hash` feature.
+    #[cfg(feature = "hash")]
+    Hash(String),
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Redaction::Mask("[REDACTED]".to_string())
+    }
+}
+
+impl Redaction {
+    fn apply(&self, text: &str) -> String {
+        match self {
+            Redaction::Remove => String::new(),
+            Redaction::Mask(mask) => mask.clone(),
+            Redaction::Partial(keep) => {
+                let len = text.chars().count();
+                let keep = if *keep < len { *keep } else { 0 };
+                text.chars()
+                    .enumerate()
+                    .map(|(i, c)| if i < len - keep { '*' } else { c })
+                    .collect()
+            }
+            #[cfg(feature = "hash")]
+            Redaction::Hash(salt) => {
+                let digest = Sha256::new()
+                    .chain_update(salt.as_bytes())
+                    .chain_update(text.as_bytes())
+                    .finalize();
+                digest[..8].iter().map(|b| format!("{:02x}", b)).collect()
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Pattern {
+    regex: Regex,
+    redaction: Redaction,
+    /// Extra check on a match, e.g. the Luhn checksum for card numbers.
+    validate: Option<fn(&str) -> bool>,
+}
+
+/// Masks secrets and PII in `message` and `meta` before any transport sees them.
+///
+/// Meta values are selected by path in [`FieldPath`](crate::FieldPath) syntax
+/// (`password`, `user.email`, `headers.*.authorization`, `cards[*].number`), and
+/// text in the message and every meta string is matched against regex patterns.
+/// If a pattern has a `secret` named group, only that part of the match is
+/// redacted. Invalid paths and patterns are reported and ignored.
+#[derive(Clone, Default)]
+pub struct RedactFormat {
+    paths: Vec<(FieldPath, Redaction)>,
+    patterns: Vec<Pattern>,
+}
+
+impl RedactFormat {
+    pub fn new() -> Self {
+        Self {
+            paths: Vec::new(),
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Redacts the meta values at `path` (a leading `meta.` is optional).
+    pub fn with_path(mut self, path: &str, redaction: Redaction) -> Self {
+        match FieldPath::parse(path.strip_prefix("meta.").unwrap_or(path)) {
+            Ok(path) => self.paths.push((path, redaction)),
+            Err(e) => eprintln!("[logform::redact] Ignoring invalid path: {}", e),
+        }
+        self
+    }
+
+    /// Redacts matches of `pattern` in the message and meta strings.
+    pub fn with_pattern(self, pattern: &str, redaction: Redaction) -> Self {
+        self.with_validated_pattern(pattern, redaction, None)
+    }
+
+    /// Payment card numbers (13-19 digits, optionally grouped by spaces or
+    /// dashes) that pass the Luhn check.
+    pub fn with_credit_cards(self, redaction: Redaction) -> Self {
+        self.with_validated_pattern(r"\b(?:\d[ -]?){12,18}\d\b", redaction, Some(luhn_valid))
+    }
+
+    pub fn with_emails(self, redaction: Redaction) -> Self {
+        self.with_pattern(
+            r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}",
+            redaction,
+        )
+    }
+
+    /// The token in `Bearer <token>`; the scheme name is kept.
+    pub fn with_bearer_tokens(self, redaction: Redaction) -> Self {
+        self.with_pattern(
+            r"(?i)\bbearer\s+(?P<secret>[A-Za-z0-9\-._~+/]+=*)",
+            redaction,
+        )
+    }
+
+    fn with_validated_pattern(
+        mut self,
+        pattern: &str,
+        redaction: Redaction,
+        validate: Option<fn(&str) -> bool>,
+    ) -> Self {
+        match Regex::new(pattern) {
+            Ok(regex) => self.patterns.push(Pattern {
+                regex,
+                redaction,
+                validate,
+            }),
+            Err(e) => eprintln!("[logform::redact] Ignoring invalid pattern: {}", e),
+        }
+        self
+    }
+
+    fn redact_text<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for pattern in &self.patterns {
+            let replaced = pattern.regex.replace_all(&text, |caps: &Captures| {
+                let whole = caps.get(0).unwrap();
+                if pattern.validate.is_some_and(|valid| !valid(whole.as_str())) {
+                    return whole.as_str().to_string();
+                }
+                match caps.name("secret") {
+                    Some(secret) => {
+                        let start = secret.start() - whole.start();
+                        let end = secret.end() - whole.start();
+                        format!(
+                            "{}{}{}",
+                            &whole.as_str()[..start],
+                            pattern.redaction.apply(secret.as_str()),
+                            &whole.as_str()[end..]
+                        )
+                    }
+                    None => pattern.redaction.apply(whole.as_str()),
+                }
+            });
+            if let Cow::Owned(replaced) = replaced {
+                text = Cow::Owned(replaced);
+            }
+        }
+        text
+    }
+
+    fn redact_strings(&self, value: &mut Value) {
+        match value {
+            Value::String(s) => {
+                if let Cow::Owned(redacted) = self.redact_text(s) {
+                    *s = redacted;
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|v| self.redact_strings(v)),
+            Value::Object(map) => map.values_mut().for_each(|v| self.redact_strings(v)),
+            _ => {}
+        }
+    }
+
+    pub fn transform(&self, mut info: LogInfo) -> Option<LogInfo> {
+        for (path, redaction) in &self.paths {
+            path.visit_mut(&mut info.meta, &mut |value| {
+                if *redaction == Redaction::Remove {
+                    return false;
+                }
+                let redacted = match &*value {
+                    Value::String(s) => redaction.apply(s),
+                    other => redaction.apply(&other.to_string()),
+                };
+                *value = Value::String(redacted);
+                true
+            });
+        }
+
+        if !self.patterns.is_empty() {
+            if let Cow::Owned(message) = self.redact_text(&info.message) {
+                info.message = message;
+            }
+            for value in info.meta.values_mut() {
+                self.redact_strings(value);
+            }
+        }
+
+        Some(info)
+    }
+}
+
+impl Format for RedactFormat {
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        self.transform(info)
+    }
+}
+
+fn luhn_valid(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2 == 1, d * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => d,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+pub fn redact() -> RedactFormat {
+    RedactFormat::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_paths() {
+        let info = LogInfo::new("info", "Login")
+            .with_meta("password", "hunter2")
+            .with_meta("token", "abc")
+            .with_meta(
+                "headers",
+                json!({
+                    "upstream": {"authorization": "Basic dXNlcjpwdw==", "accept": "*/*"},
+                    "client": {"authorization": "Basic Zm9vOmJhcg=="}
+                }),
+            )
+            .with_meta("card", 4111111111111111u64);
+
+        let result = redact()
+            .with_path("password", Redaction::default())
+            .with_path("token", Redaction::Remove)
+            .with_path(
+                "meta.headers.*.authorization",
+                Redaction::Mask("***".into()),
+            )
+            .with_path("card", Redaction::Partial(4))
+            .transform(info)
+            .unwrap();
+
+        assert_eq!(result.meta["password"], json!("[REDACTED]"));
+        assert!(!result.meta.contains_key("token"));
+        assert_eq!(
+            result.meta["headers"],
+            json!({
+                "upstream": {"authorization": "***", "accept": "*/*"},
+                "client": {"authorization": "***"}
+            })
+        );
+        assert_eq!(result.meta["card"], json!("************1111"));
+    }
+
+    #[test]
+    fn test_redact_patterns() {
+        let info = LogInfo::new(
+            "info",
+            "Charged 4111 1111 1111 1111 for bob@example.com (order 1234567890123)",
+        )
+        .with_meta("auth", "Bearer eyJhbGciOi.J9.abc")
+        .with_meta("contacts", json!([{"email": "alice@example.org"}]));
+
+        let result = redact()
+            .with_credit_cards(Redaction::Partial(4))
+            .with_emails(Redaction::Mask("<email>".into()))
+            .with_bearer_tokens(Redaction::default())
+            .transform(info)
+            .unwrap();
+
+        assert_eq!(
+            result.message,
+            "Charged ***************1111 for <email> (order 1234567890123)"
+        );
+        assert_eq!(result.meta["auth"], json!("Bearer [REDACTED]"));
+        assert_eq!(result.meta["contacts"], json!([{"email": "<email>"}]));
+    }
+
+    #[cfg(feature = "hash")]
+    #[test]
+    fn test_redact_hash_is_salted_and_stable() {
+        let format = redact().with_path("user", Redaction::Hash("s1".into()));
+        let hash = |format: &RedactFormat, user: &str| {
+            format
+                .transform(LogInfo::new("info", "x").with_meta("user", user))
+                .unwrap()
+                .meta["user"]
+                .clone()
+        };
+
+        let first = hash(&format, "alice");
+        assert_eq!(first, hash(&format, "alice"));
+        assert_ne!(first, hash(&format, "bob"));
+        assert_eq!(first.as_str().unwrap().len(), 16);
+
+        let other_salt = redact().with_path("user", Redaction::Hash("s2".into()));
+        assert_ne!(first, hash(&other_salt, "alice"));
+    }
+
+    #[test]
+    fn test_redact_remove_pattern_and_invalid_rules() {
+        let result = redact()
+            .with_pattern(r"secret=\S+", Redaction::Remove)
+            .with_pattern("(", Redaction::Remove)
+            .with_path("a..b", Redaction::Remove)
+            .transform(LogInfo::new("info", "call secret=xyz done"))
+            .unwrap();
+
+        assert_eq!(result.message, "call  done");
+    }
+}
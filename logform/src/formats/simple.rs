@@ -1,39 +1,63 @@
-use super::Format;
-use crate::LogInfo;
+use super::{formatted, into_string, Finalizer, FormatError};
+use crate::{LogInfo, Meta};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::io;
+
+/// Meta keys `simple()` and `cli()` leave out of the trailing JSON.
+const SKIPPED: [&str; 4] = ["level", "message", "splat", "padding"];
+
+/// Meta without the keys in `SKIPPED`, serialized without copying it.
+struct Rest<'a>(&'a Meta);
+
+impl Serialize for Rest<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for (key, value) in self.0.iter() {
+            if !SKIPPED.contains(&key.as_str()) {
+                map.serialize_entry(key.as_str(), value)?;
+            }
+        }
+        map.end()
+    }
+}
 
-pub struct SimpleFormat;
+/// Writes ` {meta}` when any meta is left to show.
+pub(crate) fn write_rest(meta: &Meta, writer: &mut dyn io::Write) -> io::Result<()> {
+    if meta.keys().all(|key| SKIPPED.contains(&key.as_str())) {
+        return Ok(());
+    }
+    writer.write_all(b" ")?;
+    serde_json::to_writer(writer, &Rest(meta)).map_err(io::Error::from)
+}
 
-impl Format for SimpleFormat {
-    type Input = LogInfo;
+pub struct SimpleFormat;
 
-    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+impl Finalizer for SimpleFormat {
+    fn write_to(&self, info: &LogInfo, writer: &mut dyn io::Write) -> io::Result<()> {
         let padding = info
             .meta
             .get("padding")
-            .and_then(|v| v.get(&info.level))
+            .and_then(|v| v.get(info.level.as_ref()))
             .and_then(|v| v.as_str())
             .unwrap_or("");
 
-        let mut message = format!("{}:{} {}", info.level, padding, info.message);
-
-        let mut rest = info.meta.clone();
-        rest.remove("level");
-        rest.remove("message");
-        rest.remove("splat");
-        rest.remove("padding");
-
-        if !rest.is_empty() {
-            let rest_string = serde_json::to_string(&rest).unwrap_or_default();
-            message.push_str(&format!(" {}", rest_string));
-        }
+        write!(writer, "{}:{} {}", info.level, padding, info.message)?;
+        write_rest(&info.meta, writer)
+    }
+}
 
-        Some(LogInfo {
-            formatted: Some(message),
-            ..info
-        })
+impl SimpleFormat {
+    pub fn try_transform(&self, info: LogInfo) -> Result<Option<LogInfo>, FormatError> {
+        let mut buffer = Vec::new();
+        let output = self
+            .write_to(&info, &mut buffer)
+            .and_then(|()| into_string(buffer));
+        formatted("SimpleFormat", info, output)
     }
 }
 
+fallible_format!(SimpleFormat);
+
 pub fn simple() -> SimpleFormat {
     SimpleFormat
 }
@@ -74,6 +98,20 @@ mod tests {
         assert_eq!(actual_json, expected_json);
     }
 
+    #[test]
+    fn test_simple_format_keeps_meta_order() {
+        let info = LogInfo::new("warn", "Disk almost full")
+            .with_meta("mount", "/var")
+            .with_meta("free_pct", 4)
+            .with_meta("alert", true);
+
+        let result = SimpleFormat.transform(info).unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(r#"warn: Disk almost full {"mount":"/var","free_pct":4,"alert":true}"#)
+        );
+    }
+
     #[test]
     fn test_simple_format_no_metadata_after_filtering() {
         let simple_formatter = SimpleFormat;
@@ -87,6 +125,7 @@ mod tests {
         assert_eq!(result.formatted.as_deref().unwrap(), expected_message);
     }
     use super::*;
+    use crate::Format;
     use serde_json::{json, Value};
 
     #[test]
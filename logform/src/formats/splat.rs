@@ -0,0 +1,196 @@
+use super::Format;
+use crate::LogInfo;
+use serde_json::Value;
+
+/// Meta key holding the positional arguments (see [`LogInfo::with_splat`]).
+pub(crate) const SPLAT: &str = "splat";
+
+/// Fills `%`-placeholders in the message from the positional arguments in the
+/// `splat` meta array, as `log!(logger, info, "%s has %d items"; user, count)`
+/// records them.
+///
+/// - `%s`: strings as-is, other values as JSON
+/// - `%d`: a number; strings are parsed, anything else is `NaN`
+/// - `%i`: like `%d`, truncated to an integer
+/// - `%f`: like `%d`, as a float
+/// - `%j` / `%o`: JSON
+/// - `%%`: a literal `%`
+///
+/// Placeholders without an argument are left as they are. Arguments beyond the
+/// last placeholder are merged into meta if they are objects, and appended to
+/// the message (space-separated, like `%s`) otherwise. Entries without a
+/// `splat` array pass through unchanged.
+#[derive(Clone, Default)]
+pub struct SplatFormat;
+
+impl SplatFormat {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn number(value: &Value) -> Option<f64> {
+        match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse().ok(),
+            Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+            _ => None,
+        }
+    }
+
+    fn string(value: &Value) -> String {
+        match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    fn substitute(placeholder: char, value: &Value) -> String {
+        match placeholder {
+            's' => Self::string(value),
+            'd' => match value {
+                Value::Number(n) => n.to_string(),
+                other => Self::number(other).map_or("NaN".to_string(), |n| n.to_string()),
+            },
+            'i' => match value {
+                Value::Number(n) if n.is_i64() || n.is_u64() => n.to_string(),
+                other => Self::number(other)
+                    .filter(|n| n.is_finite())
+                    .map_or("NaN".to_string(), |n| (n.trunc() as i64).to_string()),
+            },
+            'f' => Self::number(value).map_or("NaN".to_string(), |n| n.to_string()),
+            _ => value.to_string(),
+        }
+    }
+
+    pub fn transform(&self, mut info: LogInfo) -> Option<LogInfo> {
+        let args = match info.meta.remove(SPLAT) {
+            Some(Value::Array(args)) => args,
+            Some(other) => {
+                info.meta.insert(SPLAT, other);
+                return Some(info);
+            }
+            None => return Some(info),
+        };
+
+        let mut args = args.into_iter();
+        let mut message = String::with_capacity(info.message.len());
+        let mut chars = info.message.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                message.push(c);
+                continue;
+            }
+            match chars.peek().copied() {
+                Some('%') => {
+                    chars.next();
+                    message.push('%');
+                }
+                Some(placeholder @ ('s' | 'd' | 'i' | 'f' | 'j' | 'o')) => {
+                    chars.next();
+                    match args.next() {
+                        Some(arg) => message.push_str(&Self::substitute(placeholder, &arg)),
+                        None => {
+                            message.push('%');
+                            message.push(placeholder);
+                        }
+                    }
+                }
+                _ => message.push('%'),
+            }
+        }
+
+        for arg in args {
+            match arg {
+                Value::Object(object) => {
+                    for (key, value) in object {
+                        info.meta.insert(key, value);
+                    }
+                }
+                other => {
+                    message.push(' ');
+                    message.push_str(&Self::string(&other));
+                }
+            }
+        }
+
+        info.message = message;
+        Some(info)
+    }
+}
+
+impl Format for SplatFormat {
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        self.transform(info)
+    }
+}
+
+pub fn splat() -> SplatFormat {
+    SplatFormat::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn format(message: &str, args: Vec<Value>) -> LogInfo {
+        let info = args
+            .into_iter()
+            .fold(LogInfo::new("info", message), LogInfo::with_splat);
+        splat().transform(info).unwrap()
+    }
+
+    #[test]
+    fn test_splat_placeholders() {
+        let result = format(
+            "%s has %d items (%i%%, %f) %j %o",
+            vec![
+                json!("Alice"),
+                json!(5),
+                json!(42.9),
+                json!("1.5"),
+                json!({"a": 1}),
+                json!([1, "x"]),
+            ],
+        );
+        assert_eq!(
+            result.message,
+            r#"Alice has 5 items (42%, 1.5) {"a":1} [1,"x"]"#
+        );
+        assert!(!result.meta.contains_key("splat"));
+    }
+
+    #[test]
+    fn test_splat_non_numeric_and_missing_args() {
+        let result = format(
+            "%d %i %s %s %x",
+            vec![json!("abc"), json!(null), json!(true)],
+        );
+        assert_eq!(result.message, "NaN NaN true %s %x");
+    }
+
+    #[test]
+    fn test_splat_leftover_args() {
+        let result = format(
+            "Request %s",
+            vec![
+                json!("GET"),
+                json!({"status": 200}),
+                json!("extra"),
+                json!(3),
+            ],
+        );
+        assert_eq!(result.message, "Request GET extra 3");
+        assert_eq!(result.meta["status"], json!(200));
+    }
+
+    #[test]
+    fn test_splat_without_args_is_unchanged() {
+        let info = LogInfo::new("info", "100% done %s").with_meta("splat", "not an array");
+        let result = splat().transform(info).unwrap();
+        assert_eq!(result.message, "100% done %s");
+        assert_eq!(result.meta["splat"], json!("not an array"));
+    }
+}
@@ -0,0 +1,424 @@
+use super::Format;
+use crate::{
+    config,
+    utils::host::{exe_name, hostname},
+    LogInfo,
+};
+use chrono::{Local, SecondsFormat};
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::collections::HashSet;
+
+const NIL: &str = "-";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Protocol {
+    Rfc5424,
+    Rfc3164,
+}
+
+/// Renders entries as syslog messages, per RFC 5424 (`syslog_5424()`) or the
+/// older BSD format of RFC 3164 (`syslog_3164()`).
+///
+/// PRI is `facility * 8 + severity`, with the severity taken from the level
+/// name (see [`config::syslog::severity`]; unknown names count as `info`).
+/// RFC 5424 messages carry meta as one structured-data element,
+/// `[meta@32473 key="value" ...]`, with nested objects flattened into dotted
+/// parameter names (keys with an empty name are left out, names that collide
+/// once cut to 32 characters get a `~2` suffix) and `"`, `\` and `]` escaped. RFC 3164 has no structured
+/// data, so meta is appended to the message as JSON like `simple()` does.
+#[derive(Clone)]
+pub struct SyslogFormat {
+    protocol: Protocol,
+    facility: u8,
+    app_name: String,
+    hostname: String,
+    procid: String,
+    msgid: String,
+    sd_id: String,
+}
+
+impl SyslogFormat {
+    fn new(protocol: Protocol) -> Self {
+        Self {
+            protocol,
+            facility: 1,
+            app_name: exe_name().to_string(),
+            hostname: hostname().to_string(),
+            procid: std::process::id().to_string(),
+            msgid: NIL.to_string(),
+            sd_id: "meta@32473".to_string(),
+        }
+    }
+
+    /// Facility by name (`user`, `daemon`, `local0`, ...) or code (`"16"`).
+    /// Defaults to `user`; an unknown facility is ignored with a warning (see
+    /// [`try_with_facility`](Self::try_with_facility) to reject it instead).
+    pub fn with_facility(self, facility: &str) -> Self {
+        match self.clone().try_with_facility(facility) {
+            Ok(format) => format,
+            Err(e) => {
+                eprintln!("[logform::syslog] Ignoring {}", e);
+                self
+            }
+        }
+    }
+
+    /// Like [`with_facility`](Self::with_facility), but fails on an unknown facility.
+    pub fn try_with_facility(mut self, facility: &str) -> Result<Self, String> {
+        match config::syslog::facility(facility)
+            .or_else(|| facility.parse().ok().filter(|code| *code <= 23))
+        {
+            Some(code) => {
+                self.facility = code;
+                Ok(self)
+            }
+            None => Err(format!("unknown facility '{}'", facility)),
+        }
+    }
+
+    /// Defaults to the executable's name.
+    pub fn with_app_name(mut self, app_name: &str) -> Self {
+        self.app_name = app_name.to_string();
+        self
+    }
+
+    /// Defaults to this machine's hostname.
+    pub fn with_hostname(mut self, hostname: &str) -> Self {
+        self.hostname = hostname.to_string();
+        self
+    }
+
+    /// Defaults to the current process id.
+    pub fn with_procid(mut self, procid: &str) -> Self {
+        self.procid = procid.to_string();
+        self
+    }
+
+    /// RFC 5424 only; defaults to `-`.
+    pub fn with_msgid(mut self, msgid: &str) -> Self {
+        self.msgid = msgid.to_string();
+        self
+    }
+
+    /// RFC 5424 only: the SD-ID of the element holding meta (default
+    /// `meta@32473`, under the enterprise number reserved for examples).
+    pub fn with_sd_id(mut self, sd_id: &str) -> Self {
+        self.sd_id = sd_id.to_string();
+        self
+    }
+
+    fn pri(&self, level: &str) -> u8 {
+        self.facility * 8 + config::syslog::severity(level).unwrap_or(6)
+    }
+
+    /// Header fields are printable ASCII without spaces, `-` when empty.
+    fn header_field(out: &mut String, value: &str, max_len: usize) {
+        let start = out.len();
+        out.extend(
+            value
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| if c.is_ascii_graphic() { c } else { '_' })
+                .take(max_len),
+        );
+        if out.len() == start {
+            out.push_str(NIL);
+        }
+    }
+
+    /// An SD-NAME: printable ASCII except `=`, space, `]` and `"`, at most 32
+    /// characters.
+    fn sd_name(name: &str) -> String {
+        name.chars()
+            .map(|c| match c {
+                '=' | ']' | '"' => '_',
+                c if c.is_ascii_graphic() => c,
+                _ => '_',
+            })
+            .take(32)
+            .collect()
+    }
+
+    /// `name` as an SD-NAME not in `used`: names cut to 32 characters (or
+    /// flattened onto an existing key) can collide, so later ones get a `~2`,
+    /// `~3`, ... suffix in place of their last characters.
+    fn param_name(used: &mut HashSet<String>, name: &str) -> String {
+        let name = Self::sd_name(name);
+        let mut unique = name.clone();
+        let mut n = 2;
+        while used.contains(&unique) {
+            let suffix = format!("~{}", n);
+            let keep = name.len().min(32 - suffix.len());
+            unique = format!("{}{}", &name[..keep], suffix);
+            n += 1;
+        }
+        used.insert(unique.clone());
+        unique
+    }
+
+    fn push_param(params: &mut Vec<(String, String)>, name: String, value: &Value) {
+        match value {
+            Value::Null => {}
+            Value::Object(object) => {
+                for (key, value) in object {
+                    Self::push_param(params, format!("{}.{}", name, key), value);
+                }
+            }
+            // An empty PARAM-NAME is invalid; leave such keys out
+            _ if name.is_empty() => {}
+            Value::String(s) => params.push((name, s.clone())),
+            other => params.push((name, other.to_string())),
+        }
+    }
+
+    fn write_structured_data(&self, out: &mut String, info: &LogInfo) {
+        let mut params = Vec::new();
+        for (key, value) in info.meta.iter() {
+            if key != "timestamp" {
+                Self::push_param(&mut params, key.to_string(), value);
+            }
+        }
+        if params.is_empty() {
+            out.push_str(NIL);
+            return;
+        }
+
+        out.push('[');
+        match Self::sd_name(&self.sd_id) {
+            sd_id if sd_id.is_empty() => out.push_str(NIL),
+            sd_id => out.push_str(&sd_id),
+        }
+        let mut used = HashSet::new();
+        for (name, value) in params {
+            out.push(' ');
+            out.push_str(&Self::param_name(&mut used, &name));
+            out.push_str("=\"");
+            for c in value.chars() {
+                if matches!(c, '"' | '\\' | ']') {
+                    out.push('\\');
+                }
+                out.push(c);
+            }
+            out.push('"');
+        }
+        out.push(']');
+    }
+
+    pub fn transform(&self, info: LogInfo) -> Option<LogInfo> {
+        let time = info.time();
+        let mut line = format!("<{}>", self.pri(&info.level));
+
+        match self.protocol {
+            Protocol::Rfc5424 => {
+                line.push_str("1 ");
+                line.push_str(&time.to_rfc3339_opts(SecondsFormat::Micros, true));
+                line.push(' ');
+                Self::header_field(&mut line, &self.hostname, 255);
+                line.push(' ');
+                Self::header_field(&mut line, &self.app_name, 48);
+                line.push(' ');
+                Self::header_field(&mut line, &self.procid, 128);
+                line.push(' ');
+                Self::header_field(&mut line, &self.msgid, 32);
+                line.push(' ');
+                self.write_structured_data(&mut line, &info);
+                if !info.message.is_empty() {
+                    line.push(' ');
+                    line.push_str(&info.message);
+                }
+            }
+            Protocol::Rfc3164 => {
+                line.push_str(
+                    &time
+                        .with_timezone(&Local)
+                        .format("%b %e %H:%M:%S")
+                        .to_string(),
+                );
+                line.push(' ');
+                Self::header_field(&mut line, &self.hostname, 255);
+                line.push(' ');
+                let tag: String = self
+                    .app_name
+                    .chars()
+                    .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+                    .take(32)
+                    .collect();
+                line.push_str(if tag.is_empty() { NIL } else { &tag });
+                if !self.procid.is_empty() {
+                    line.push('[');
+                    line.push_str(&self.procid);
+                    line.push(']');
+                }
+                line.push_str(": ");
+                line.push_str(&info.message);
+                let meta: IndexMap<&str, &Value> = info
+                    .meta
+                    .iter()
+                    .filter(|(key, _)| *key != "timestamp")
+                    .map(|(key, value)| (key.as_ref(), value))
+                    .collect();
+                if !meta.is_empty() {
+                    match serde_json::to_string(&meta) {
+                        Ok(meta) => {
+                            line.push(' ');
+                            line.push_str(&meta);
+                        }
+                        Err(e) => eprintln!("[logform::syslog] Failed to serialize meta: {}", e),
+                    }
+                }
+            }
+        }
+
+        Some(LogInfo {
+            formatted: Some(line),
+            ..info
+        })
+    }
+}
+
+impl Format for SyslogFormat {
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        self.transform(info)
+    }
+}
+
+pub fn syslog_5424() -> SyslogFormat {
+    SyslogFormat::new(Protocol::Rfc5424)
+}
+
+pub fn syslog_3164() -> SyslogFormat {
+    SyslogFormat::new(Protocol::Rfc3164)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    fn fixed(format: SyslogFormat) -> SyslogFormat {
+        format
+            .with_facility("local0")
+            .with_app_name("api")
+            .with_hostname("web-1")
+            .with_procid("42")
+    }
+
+    #[test]
+    fn test_syslog_5424_format() {
+        let time = Utc.with_ymd_and_hms(2024, 4, 1, 12, 30, 0).unwrap();
+        let info = LogInfo::new("warn", "Disk almost full")
+            .with_timestamp(time)
+            .with_meta("mount", "/var")
+            .with_meta("usage", json!({"pct": 96}));
+
+        let result = fixed(syslog_5424())
+            .with_msgid("DISK")
+            .transform(info)
+            .unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(
+                r#"<132>1 2024-04-01T12:30:00.000000Z web-1 api 42 DISK [meta@32473 mount="/var" usage.pct="96"] Disk almost full"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_syslog_5424_escapes_structured_data() {
+        let info = LogInfo::new("error", "")
+            .with_meta("query", r#"a="b" \ [c]"#)
+            .with_meta("bad name", "x")
+            .with_meta("", "no name")
+            .with_meta("timestamp", "2024-04-01T12:30:00Z");
+
+        let result = fixed(syslog_5424()).transform(info).unwrap();
+        assert_eq!(
+            result.formatted.as_deref(),
+            Some(
+                r#"<131>1 2024-04-01T12:30:00.000000Z web-1 api 42 - [meta@32473 query="a=\"b\" \\ [c\]" bad_name="x"]"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_syslog_5424_without_meta_uses_nil_values() {
+        let info = LogInfo::new("custom", "hello").with_timestamp(Utc::now());
+        let result = syslog_5424()
+            .with_app_name("")
+            .with_procid("")
+            .with_facility("nonsense")
+            .transform(info)
+            .unwrap();
+        let line = result.formatted.unwrap();
+
+        // user facility, unknown level counted as info
+        assert!(line.starts_with("<14>1 "));
+        assert!(line.ends_with(" - - - - hello"));
+    }
+
+    #[test]
+    fn test_syslog_3164_format() {
+        let time = Utc.with_ymd_and_hms(2024, 4, 1, 12, 30, 0).unwrap();
+        let info = LogInfo::new("info", "Server started")
+            .with_timestamp(time)
+            .with_meta("port", 8080);
+
+        let result = fixed(syslog_3164()).transform(info).unwrap();
+        let stamp = time.with_timezone(&Local).format("%b %e %H:%M:%S");
+        assert_eq!(
+            result.formatted,
+            Some(format!(
+                r#"<134>{} web-1 api[42]: Server started {{"port":8080}}"#,
+                stamp
+            ))
+        );
+    }
+
+    #[test]
+    fn test_syslog_5424_sd_names() {
+        let long = "a".repeat(40);
+        let info = LogInfo::new("info", "x")
+            .with_meta(format!("{}1", long), 1)
+            .with_meta(format!("{}2", long), 2)
+            .with_meta("a.b", 3)
+            .with_meta("a", json!({"b": 4}));
+
+        let result = fixed(syslog_5424())
+            .with_sd_id(r#"my"id]=x"#)
+            .transform(info)
+            .unwrap();
+        let line = result.formatted.unwrap();
+        let a32 = "a".repeat(32);
+        let a30 = "a".repeat(30);
+        assert!(line.contains(&format!(
+            r#"[my_id__x {}="1" {}~2="2" a.b="3" a.b~2="4"]"#,
+            a32, a30
+        )));
+    }
+
+    #[test]
+    fn test_syslog_keeps_meta_on_the_entry() {
+        let info = LogInfo::new("info", "x")
+            .with_meta("port", 8080)
+            .with_meta("timestamp", "2024-04-01T12:30:00Z");
+
+        let result = fixed(syslog_3164()).transform(info).unwrap();
+        assert_eq!(result.meta.get("port"), Some(&json!(8080)));
+        assert!(result.meta.get("timestamp").is_some());
+        assert!(result.formatted.unwrap().ends_with(r#": x {"port":8080}"#));
+    }
+
+    #[test]
+    fn test_syslog_try_with_facility() {
+        assert!(syslog_5424().try_with_facility("local7").is_ok());
+        assert!(syslog_5424().try_with_facility("23").is_ok());
+        assert_eq!(
+            syslog_5424().try_with_facility("nonsense").err().as_deref(),
+            Some("unknown facility 'nonsense'")
+        );
+    }
+}
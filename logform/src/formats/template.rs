@@ -0,0 +1,427 @@
+use super::{
+    colorize::{apply_color, colors_enabled, Colorizer},
+    uncolorize::strip_colors,
+    Format,
+};
+use crate::{path::FieldPath, LogInfo};
+use colored::Colorize;
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Field {
+    Level,
+    Message,
+    Timestamp,
+    /// Every meta key not used by another field, as JSON.
+    Meta,
+    Path(FieldPath),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Placeholder {
+    field: Field,
+    fill: char,
+    align: Option<Align>,
+    width: usize,
+    precision: Option<usize>,
+    default: Option<String>,
+    /// Color names applied in order; `level` means the level's own color.
+    colors: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Placeholder(Placeholder),
+}
+
+/// Renders entries through a template string parsed once up front, so formats
+/// can come from configuration instead of a `printf` closure.
+///
+/// ```ignore
+/// let format = template("{timestamp} [{level:>5|color=level}] {label|default=main}: {message} {meta}")?;
+/// ```
+///
+/// A placeholder is `{field[:spec][|option]...}`:
+/// - `field` is `level`, `message`, `timestamp`, `meta` (the remaining meta as
+///   JSON), or a meta path such as `label` or `meta.user.id` (`meta.` is optional
+///   unless the key clashes with a built-in name).
+/// - `spec` follows Rust's `[[fill]align][width][.precision]`, e.g. `>5`, `-<10`
+///   or `.8`; widths count visible characters, ignoring ANSI colors.
+/// - `default=text` is used when the field is missing, and `color=red bold`
+///   styles the segment (`color=level` uses the level's color).
+///
+/// `{{` and `}}` are literal braces. Trailing whitespace is trimmed, so a final
+/// `{meta}` leaves no dangling space when meta is empty.
+#[derive(Clone)]
+pub struct TemplateFormat {
+    segments: Vec<Segment>,
+    /// Top-level meta keys consumed by placeholders, left out of `{meta}`.
+    used_keys: Vec<String>,
+    colorizer: Option<Colorizer>,
+}
+
+impl TemplateFormat {
+    pub fn new(template: &str) -> Result<Self, String> {
+        let segments = Self::parse(template)?;
+
+        let mut used_keys = Vec::new();
+        let mut level_colors = false;
+        for segment in &segments {
+            if let Segment::Placeholder(placeholder) = segment {
+                match &placeholder.field {
+                    Field::Timestamp => used_keys.push("timestamp".to_string()),
+                    Field::Path(path) => used_keys.extend(path.root().map(str::to_string)),
+                    _ => {}
+                }
+                level_colors |= placeholder.colors.iter().any(|color| color == "level");
+            }
+        }
+
+        Ok(Self {
+            segments,
+            used_keys,
+            colorizer: level_colors.then(Colorizer::new),
+        })
+    }
+
+    /// Colors used by `color=level` (default: the `colorize()` defaults).
+    pub fn with_colorizer(mut self, colorizer: Colorizer) -> Self {
+        self.colorizer = Some(colorizer);
+        self
+    }
+
+    fn parse(template: &str) -> Result<Vec<Segment>, String> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.next_if_eq(&'{').is_some() => literal.push('{'),
+                '}' if chars.next_if_eq(&'}').is_some() => literal.push('}'),
+                '}' => return Err("Unmatched '}' in template; use '}}' for a literal brace".into()),
+                '{' => {
+                    let mut inner = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => inner.push(c),
+                            None => return Err(format!("Unclosed placeholder '{{{}'", inner)),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Placeholder(Self::parse_placeholder(&inner)?));
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(segments)
+    }
+
+    fn parse_placeholder(inner: &str) -> Result<Placeholder, String> {
+        let mut options = inner.split('|');
+        let head = options.next().unwrap_or_default();
+        let (name, spec) = head.split_once(':').unwrap_or((head, ""));
+
+        let field = match name.trim() {
+            "level" => Field::Level,
+            "message" => Field::Message,
+            "timestamp" => Field::Timestamp,
+            "meta" => Field::Meta,
+            "" => return Err(format!("Missing field name in '{{{}}}'", inner)),
+            path => Field::Path(FieldPath::parse(
+                path.strip_prefix("meta.").unwrap_or(path),
+            )?),
+        };
+
+        let mut placeholder = Placeholder {
+            field,
+            fill: ' ',
+            align: None,
+            width: 0,
+            precision: None,
+            default: None,
+            colors: Vec::new(),
+        };
+        Self::parse_spec(spec, &mut placeholder)
+            .map_err(|e| format!("{} in '{{{}}}'", e, inner))?;
+
+        for option in options {
+            match option.split_once('=') {
+                Some(("default", value)) => placeholder.default = Some(value.to_string()),
+                Some(("color", value)) => {
+                    placeholder.colors = value
+                        .split(|c: char| c.is_whitespace() || c == ',')
+                        .filter(|color| !color.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                }
+                _ => return Err(format!("Unknown option '{}' in '{{{}}}'", option, inner)),
+            }
+        }
+        Ok(placeholder)
+    }
+
+    fn parse_spec(spec: &str, placeholder: &mut Placeholder) -> Result<(), String> {
+        let align = |c: char| match c {
+            '<' => Some(Align::Left),
+            '>' => Some(Align::Right),
+            '^' => Some(Align::Center),
+            _ => None,
+        };
+
+        let mut rest = spec;
+        let mut chars = spec.chars();
+        if let (Some(fill), Some(second)) = (chars.next(), chars.next()) {
+            if let Some(alignment) = align(second) {
+                placeholder.fill = fill;
+                placeholder.align = Some(alignment);
+                rest = &spec[fill.len_utf8() + 1..];
+            }
+        }
+        if placeholder.align.is_none() {
+            if let Some(alignment) = rest.chars().next().and_then(align) {
+                placeholder.align = Some(alignment);
+                rest = &rest[1..];
+            }
+        }
+
+        let (width, precision) = match rest.split_once('.') {
+            Some((width, precision)) => (width, Some(precision)),
+            None => (rest, None),
+        };
+        if !width.is_empty() {
+            placeholder.width = width
+                .parse()
+                .map_err(|_| format!("Invalid width '{}'", width))?;
+        }
+        if let Some(precision) = precision {
+            placeholder.precision = Some(
+                precision
+                    .parse()
+                    .map_err(|_| format!("Invalid precision '{}'", precision))?,
+            );
+        }
+        Ok(())
+    }
+
+    fn value(&self, field: &Field, info: &LogInfo) -> Option<String> {
+        let text = |value: &Value| match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+
+        match field {
+            Field::Level => Some(info.level.to_string()),
+            Field::Message => Some(info.message.clone()),
+            Field::Timestamp => match info.meta.get("timestamp") {
+                Some(value) => Some(text(value)),
+                None => info.timestamp.as_ref().map(LogInfo::format_timestamp),
+            },
+            Field::Meta => {
+                let rest: Map<String, Value> = info
+                    .meta
+                    .iter()
+                    .filter(|(key, _)| !self.used_keys.iter().any(|used| used == key.as_ref()))
+                    .map(|(key, value)| (key.to_string(), value.clone()))
+                    .collect();
+                (!rest.is_empty()).then(|| Value::Object(rest).to_string())
+            }
+            Field::Path(path) => {
+                let values = path.resolve(&info.meta);
+                match (path.has_wildcards(), values.as_slice()) {
+                    (_, []) => None,
+                    (false, [value, ..]) => Some(text(value)),
+                    (true, values) => {
+                        Some(Value::from_iter(values.iter().copied().cloned()).to_string())
+                    }
+                }
+            }
+        }
+    }
+
+    fn render(&self, placeholder: &Placeholder, info: &LogInfo, out: &mut String) {
+        let mut text = self
+            .value(&placeholder.field, info)
+            .or_else(|| placeholder.default.clone())
+            .unwrap_or_default();
+
+        if let Some(precision) = placeholder.precision {
+            if let Some((index, _)) = text.char_indices().nth(precision) {
+                text.truncate(index);
+            }
+        }
+
+        let visible = strip_colors(&text).chars().count();
+        let padding = placeholder.width.saturating_sub(visible);
+        let (before, after) = match placeholder.align {
+            _ if padding == 0 => (0, 0),
+            Some(Align::Right) => (padding, 0),
+            Some(Align::Center) => (padding / 2, padding - padding / 2),
+            // Like `format!`, numbers default to right alignment and text to left
+            None if matches!(placeholder.field, Field::Path(_)) && text.parse::<f64>().is_ok() => {
+                (padding, 0)
+            }
+            Some(Align::Left) | None => (0, padding),
+        };
+        let fill = |count: usize| std::iter::repeat_n(placeholder.fill, count).collect::<String>();
+        let mut text = format!("{}{}{}", fill(before), text, fill(after));
+
+        let colors_enabled = match &self.colorizer {
+            Some(colorizer) => colorizer.enabled(),
+            None => colors_enabled(),
+        };
+        if !placeholder.colors.is_empty() && colors_enabled {
+            let mut styled = text.normal();
+            for color in &placeholder.colors {
+                if color == "level" {
+                    if let Some(colorizer) = &self.colorizer {
+                        styled = colorizer
+                            .colorize(&info.level, &styled.to_string())
+                            .normal();
+                    }
+                } else {
+                    styled = apply_color(styled, color);
+                }
+            }
+            text = styled.to_string();
+        }
+        out.push_str(&text);
+    }
+
+    pub fn transform(&self, info: LogInfo) -> Option<LogInfo> {
+        let mut line = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => line.push_str(literal),
+                Segment::Placeholder(placeholder) => self.render(placeholder, &info, &mut line),
+            }
+        }
+        line.truncate(line.trim_end().len());
+
+        Some(LogInfo {
+            formatted: Some(line),
+            ..info
+        })
+    }
+}
+
+impl Format for TemplateFormat {
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        self.transform(info)
+    }
+}
+
+impl FromStr for TemplateFormat {
+    type Err = String;
+
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        Self::new(template)
+    }
+}
+
+/// Deserializes from the template string, so a format can live in a config file.
+impl<'de> Deserialize<'de> for TemplateFormat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let template = String::deserialize(deserializer)?;
+        Self::new(&template).map_err(serde::de::Error::custom)
+    }
+}
+
+pub fn template(template: &str) -> Result<TemplateFormat, String> {
+    TemplateFormat::new(template)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    fn render(template_str: &str, info: LogInfo) -> String {
+        template(template_str)
+            .unwrap()
+            .transform(info)
+            .unwrap()
+            .formatted
+            .unwrap()
+    }
+
+    #[test]
+    fn test_template_format() {
+        let info = LogInfo::new("info", "Server started")
+            .with_timestamp(Utc.with_ymd_and_hms(2024, 4, 1, 12, 30, 0).unwrap())
+            .with_meta("label", "api")
+            .with_meta("port", 8080);
+
+        assert_eq!(
+            render("{timestamp} [{level:>5}] {label}: {message} {meta}", info),
+            r#"2024-04-01T12:30:00Z [ info] api: Server started {"port":8080}"#
+        );
+    }
+
+    #[test]
+    fn test_template_paths_defaults_and_specs() {
+        let info = LogInfo::new("warn", "Slow request")
+            .with_meta("user", json!({"id": 7, "name": "alice"}))
+            .with_meta("tags", json!(["a", "b"]));
+
+        assert_eq!(
+            render(
+                "{{{level:-^8}}} {meta.user.id:>4}|{user.name:.3}|{label|default=main}|{tags[*]} {meta}",
+                info
+            ),
+            r#"{--warn--}    7|ali|main|["a","b"]"#
+        );
+    }
+
+    #[test]
+    fn test_template_colors() {
+        colored::control::set_override(true);
+        let info = LogInfo::new("error", "Boom");
+
+        let line = render("{level:<6|color=level}{message|color=bold}", info);
+        assert_eq!(
+            strip_colors(&line),
+            "error Boom",
+            "padding is applied before coloring"
+        );
+        assert!(
+            line.starts_with("\x1b[31m"),
+            "error is red by default: {:?}",
+            line
+        );
+        assert!(line.contains("\x1b[1mBoom"));
+    }
+
+    #[test]
+    fn test_template_parse_errors() {
+        assert!(template("{level").is_err());
+        assert!(template("level}").is_err());
+        assert!(template("{}").is_err());
+        assert!(template("{level:>x}").is_err());
+        assert!(template("{level|colour=red}").is_err());
+        assert!(template("{items[x]}").is_err());
+
+        let format: TemplateFormat = serde_json::from_value(json!("{level}: {message}")).unwrap();
+        let result = format.transform(LogInfo::new("info", "hi")).unwrap();
+        assert_eq!(result.formatted.as_deref(), Some("info: hi"));
+    }
+}
@@ -1,12 +1,68 @@
 use super::Format;
 use crate::LogInfo;
-use chrono::Utc;
-use serde_json::json;
+use chrono::{DateTime, FixedOffset, Local, Offset, SecondsFormat, TimeZone, Timelike, Utc};
+use serde_json::{json, Number, Value};
+use std::fmt::Display;
 
-#[derive(Clone, Default)]
+/// Unit of a numeric epoch timestamp.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EpochUnit {
+    Seconds,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl EpochUnit {
+    fn nanos(self) -> i64 {
+        match self {
+            EpochUnit::Seconds => 1_000_000_000,
+            EpochUnit::Millis => 1_000_000,
+            EpochUnit::Micros => 1_000,
+            EpochUnit::Nanos => 1,
+        }
+    }
+
+    /// Most decimals an `f64` epoch number keeps exactly: microseconds, which
+    /// need 16 significant digits and so fit in an `f64` until the year 2255.
+    fn max_decimals(self) -> usize {
+        match self {
+            EpochUnit::Seconds => 6,
+            EpochUnit::Millis => 3,
+            EpochUnit::Micros | EpochUnit::Nanos => 0,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Zone {
+    Utc,
+    Local,
+    Fixed(FixedOffset),
+    #[cfg(feature = "tz")]
+    Iana(chrono_tz::Tz),
+}
+
+/// Adds the entry's time to meta as `timestamp` (and the alias, if set).
+///
+/// The time is the one captured at the call site, else now. By default it is
+/// written as RFC 3339 in UTC; it can instead be rendered with a chrono format
+/// string, in another timezone, with a fixed number of fractional digits, or
+/// as a number since the Unix epoch.
+#[derive(Clone)]
 pub struct Timestamp {
     format: Option<String>,
     alias: Option<String>,
+    zone: Zone,
+    precision: Option<usize>,
+    epoch: Option<EpochUnit>,
+    use_existing: bool,
+}
+
+impl Default for Timestamp {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Timestamp {
@@ -14,6 +70,10 @@ impl Timestamp {
         Self {
             format: None,
             alias: None,
+            zone: Zone::Utc,
+            precision: None,
+            epoch: None,
+            use_existing: false,
         }
     }
 
@@ -27,19 +87,160 @@ impl Timestamp {
         self
     }
 
-    pub fn transform(&self, mut info: LogInfo) -> Option<LogInfo> {
-        let timestamp = if let Some(fmt) = &self.format {
-            Utc::now().format(fmt).to_string()
-        } else {
-            Utc::now().to_rfc3339()
+    /// `UTC` (the default), `local`, a fixed offset such as `+05:30` or
+    /// `-0800`, or, with the `tz` feature, an IANA name such as
+    /// `Europe/Berlin`.
+    pub fn with_timezone(mut self, timezone: &str) -> Self {
+        match Self::parse_zone(timezone) {
+            Some(zone) => self.zone = zone,
+            None => eprintln!("Timestamp: unknown timezone '{}'", timezone),
+        }
+        self
+    }
+
+    /// Digits of fractional seconds (0-9) in the default RFC 3339 output, or
+    /// decimals of `Seconds`/`Millis` epoch numbers. RFC 3339 output otherwise
+    /// uses as many digits as needed. Epoch numbers are JSON floats, which can't
+    /// hold nanoseconds, so their decimals stop at microseconds (6 for
+    /// `Seconds`, 3 for `Millis`).
+    pub fn with_precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits.min(9));
+        self
+    }
+
+    /// Writes the time as a number of `unit`s since the Unix epoch.
+    pub fn with_epoch(mut self, unit: EpochUnit) -> Self {
+        self.epoch = Some(unit);
+        self
+    }
+
+    /// Keeps a `timestamp` already in meta, e.g. on entries read back from a
+    /// transport and re-ingested through a proxy, instead of restamping them.
+    pub fn with_use_existing(mut self, use_existing: bool) -> Self {
+        self.use_existing = use_existing;
+        self
+    }
+
+    fn parse_zone(timezone: &str) -> Option<Zone> {
+        match timezone {
+            "UTC" | "utc" | "Z" => return Some(Zone::Utc),
+            "local" | "Local" => return Some(Zone::Local),
+            _ => {}
+        }
+        if let Some(offset) = Self::parse_offset(timezone) {
+            return Some(Zone::Fixed(offset));
+        }
+        #[cfg(feature = "tz")]
+        if let Ok(tz) = timezone.parse::<chrono_tz::Tz>() {
+            return Some(Zone::Iana(tz));
+        }
+        None
+    }
+
+    /// `+HH:MM`, `+HHMM` or `+HH`.
+    fn parse_offset(offset: &str) -> Option<FixedOffset> {
+        let sign = match offset.chars().next()? {
+            '+' => 1,
+            '-' => -1,
+            _ => return None,
         };
+        let digits: String = offset[1..].chars().filter(|c| *c != ':').collect();
+        if !matches!(digits.len(), 2 | 4) || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let hours: i32 = digits[..2].parse().ok()?;
+        let minutes: i32 = digits.get(2..).map_or(Ok(0), str::parse).ok()?;
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+    }
+
+    fn epoch_value(&self, time: DateTime<Utc>, unit: EpochUnit) -> Value {
+        let nanos = time.timestamp_nanos_opt().unwrap_or_else(|| {
+            // Outside the range of i64 nanoseconds (years before 1677 or after 2262)
+            time.timestamp().saturating_mul(1_000_000_000)
+        });
+        let per_unit = unit.nanos();
+        let whole = nanos.div_euclid(per_unit);
+        match self.precision.map(|digits| digits.min(unit.max_decimals())) {
+            Some(digits) if digits > 0 => {
+                let fraction = format!(
+                    "{:09}",
+                    nanos.rem_euclid(per_unit) * (1_000_000_000 / per_unit)
+                );
+                let number = format!("{}.{}", whole, &fraction[..digits]);
+                number
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(Number::from_f64)
+                    .map_or(json!(whole), Value::Number)
+            }
+            _ => json!(whole),
+        }
+    }
+
+    fn render<Tz: TimeZone>(&self, time: DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        if let Some(format) = &self.format {
+            return time.format(format).to_string();
+        }
+        let Some(digits) = self.precision else {
+            return time.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+        };
+        let mut rendered = time.format("%Y-%m-%dT%H:%M:%S").to_string();
+        if digits > 0 {
+            let nanos = format!("{:09}", time.nanosecond() % 1_000_000_000);
+            rendered.push('.');
+            rendered.push_str(&nanos[..digits]);
+        }
+        if time.offset().fix().local_minus_utc() == 0 {
+            rendered.push('Z');
+        } else {
+            rendered.push_str(&time.format("%:z").to_string());
+        }
+        rendered
+    }
 
-        // Always set the timestamp field
-        info.meta.insert("timestamp".to_string(), json!(&timestamp));
+    fn value(&self, time: DateTime<Utc>) -> Value {
+        if let Some(unit) = self.epoch {
+            return self.epoch_value(time, unit);
+        }
+        Value::String(match &self.zone {
+            Zone::Utc => self.render(time),
+            Zone::Local => self.render(time.with_timezone(&Local)),
+            Zone::Fixed(offset) => self.render(time.with_timezone(offset)),
+            #[cfg(feature = "tz")]
+            Zone::Iana(tz) => self.render(time.with_timezone(tz)),
+        })
+    }
+
+    pub fn transform(&self, mut info: LogInfo) -> Option<LogInfo> {
+        let existing = match info.meta.get("timestamp") {
+            Some(existing) if self.use_existing => Some(existing.clone()),
+            _ => None,
+        };
+        let timestamp = match existing {
+            Some(existing) => {
+                if info.timestamp.is_none() {
+                    info.timestamp = existing
+                        .as_str()
+                        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                        .map(|time| time.with_timezone(&Utc));
+                }
+                existing
+            }
+            None => {
+                // Prefer the time captured at the call site over the time the format runs.
+                let time = *info.timestamp.get_or_insert_with(Utc::now);
+                let timestamp = self.value(time);
+                info.meta.insert("timestamp", timestamp.clone());
+                timestamp
+            }
+        };
 
         // Set alias if provided
         if let Some(alias) = &self.alias {
-            info.meta.insert(alias.clone(), json!(&timestamp));
+            info.meta.insert(alias.clone(), timestamp);
         }
 
         Some(info)
@@ -61,6 +262,7 @@ pub fn timestamp() -> Timestamp {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::DateTime;
     use regex::Regex;
 
     #[test]
@@ -84,6 +286,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_uses_captured_timestamp() {
+        let captured = DateTime::parse_from_rfc3339("2024-04-01T12:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let info = LogInfo::new("info", "Test message").with_timestamp(captured);
+
+        let result = timestamp().transform(info).unwrap();
+        assert_eq!(result.meta["timestamp"], json!("2024-04-01T12:30:00Z"));
+        assert_eq!(result.timestamp, Some(captured));
+
+        let result = timestamp()
+            .with_format("%H:%M")
+            .transform(LogInfo::new("info", "Test message").with_timestamp(captured))
+            .unwrap();
+        assert_eq!(result.meta["timestamp"], json!("12:30"));
+    }
+
     #[test]
     fn test_custom_format() {
         let formatter = timestamp().with_format("%d/%m/%Y %H:%M:%S");
@@ -124,4 +344,124 @@ mod tests {
         let custom_format_regex = Regex::new(r"^\d{2}/\d{2}/\d{4} \d{2}:\d{2}:\d{2}$").unwrap();
         assert!(custom_format_regex.is_match(timestamp));
     }
+
+    fn at(rfc3339: &str) -> LogInfo {
+        let time = DateTime::parse_from_rfc3339(rfc3339)
+            .unwrap()
+            .with_timezone(&Utc);
+        LogInfo::new("info", "Test message").with_timestamp(time)
+    }
+
+    #[test]
+    fn test_timezones() {
+        let render = |format: Timestamp| {
+            format.transform(at("2024-04-01T12:30:00.5Z")).unwrap().meta["timestamp"].clone()
+        };
+
+        assert_eq!(
+            render(timestamp().with_timezone("+05:30")),
+            json!("2024-04-01T18:00:00.500+05:30")
+        );
+        assert_eq!(
+            render(timestamp().with_timezone("-0800").with_format("%H:%M %z")),
+            json!("04:30 -0800")
+        );
+        assert_eq!(
+            render(timestamp().with_timezone("nowhere")),
+            json!("2024-04-01T12:30:00.500Z")
+        );
+
+        let local = render(timestamp().with_timezone("local"));
+        let expected = DateTime::parse_from_rfc3339("2024-04-01T12:30:00.5Z")
+            .unwrap()
+            .with_timezone(&Local)
+            .to_rfc3339_opts(SecondsFormat::AutoSi, true);
+        assert_eq!(local, json!(expected));
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn test_iana_timezone() {
+        let result = timestamp()
+            .with_timezone("Europe/Berlin")
+            .transform(at("2024-07-01T12:00:00Z"))
+            .unwrap();
+        assert_eq!(result.meta["timestamp"], json!("2024-07-01T14:00:00+02:00"));
+    }
+
+    #[test]
+    fn test_precision() {
+        let render = |digits| {
+            timestamp()
+                .with_precision(digits)
+                .transform(at("2024-04-01T12:30:00.123456789Z"))
+                .unwrap()
+                .meta["timestamp"]
+                .clone()
+        };
+        assert_eq!(render(0), json!("2024-04-01T12:30:00Z"));
+        assert_eq!(render(3), json!("2024-04-01T12:30:00.123Z"));
+        assert_eq!(render(6), json!("2024-04-01T12:30:00.123456Z"));
+    }
+
+    #[test]
+    fn test_epoch() {
+        let render = |format: Timestamp| {
+            format
+                .with_alias("ts")
+                .transform(at("2024-04-01T12:30:00.123456789Z"))
+                .unwrap()
+                .meta
+        };
+
+        let meta = render(timestamp().with_epoch(EpochUnit::Seconds));
+        assert_eq!(meta["timestamp"], json!(1711974600));
+        assert_eq!(meta["ts"], json!(1711974600));
+        assert_eq!(
+            render(timestamp().with_epoch(EpochUnit::Seconds).with_precision(3))["timestamp"],
+            json!(1711974600.123)
+        );
+        assert_eq!(
+            render(timestamp().with_epoch(EpochUnit::Seconds).with_precision(9))["timestamp"],
+            json!(1711974600.123456)
+        );
+        assert_eq!(
+            render(timestamp().with_epoch(EpochUnit::Millis))["timestamp"],
+            json!(1711974600123i64)
+        );
+        assert_eq!(
+            render(timestamp().with_epoch(EpochUnit::Millis).with_precision(6))["timestamp"],
+            json!(1711974600123.456)
+        );
+        assert_eq!(
+            render(timestamp().with_epoch(EpochUnit::Micros).with_precision(3))["timestamp"],
+            json!(1711974600123456i64)
+        );
+        assert_eq!(
+            render(timestamp().with_epoch(EpochUnit::Nanos))["timestamp"],
+            json!(1711974600123456789i64)
+        );
+    }
+
+    #[test]
+    fn test_use_existing() {
+        let info =
+            LogInfo::new("info", "Re-ingested").with_meta("timestamp", "2024-04-01T12:30:00Z");
+
+        let result = timestamp()
+            .with_use_existing(true)
+            .with_alias("time")
+            .with_epoch(EpochUnit::Millis)
+            .transform(info.clone())
+            .unwrap();
+        assert_eq!(result.meta["timestamp"], json!("2024-04-01T12:30:00Z"));
+        assert_eq!(result.meta["time"], json!("2024-04-01T12:30:00Z"));
+        assert_eq!(
+            result.timestamp,
+            Some(Utc.with_ymd_and_hms(2024, 4, 1, 12, 30, 0).unwrap())
+        );
+
+        let result = timestamp().transform(info).unwrap();
+        assert_ne!(result.meta["timestamp"], json!("2024-04-01T12:30:00Z"));
+    }
 }
@@ -34,7 +34,7 @@ impl Uncolorize {
 
     pub fn transform(&self, mut info: LogInfo) -> Option<LogInfo> {
         if self.level {
-            info.level = strip_colors(&info.level);
+            info.level = strip_colors(&info.level).into();
         }
         if self.message {
             info.message = strip_colors(&info.message);
@@ -47,7 +47,7 @@ use std::sync::OnceLock;
 
 static STRIP_COLORS_REGEX: OnceLock<Regex> = OnceLock::new();
 
-fn strip_colors(input: &str) -> String {
+pub(crate) fn strip_colors(input: &str) -> String {
     let re = STRIP_COLORS_REGEX.get_or_init(|| Regex::new(r"\x1b\[[0-9;]*m").unwrap());
     re.replace_all(input, "").to_string()
 }
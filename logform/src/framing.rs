@@ -0,0 +1,114 @@
+//! Length-prefixed framing for streams of encoded entries.
+//!
+//! Each frame is a 4-byte big-endian payload length followed by the payload,
+//! e.g. the output of `LogInfo::to_msgpack` or `LogInfo::to_cbor`. This lets a
+//! proxy socket or spool file carry many entries back to back:
+//!
+//! ```ignore
+//! framing::write_frame(&mut spool, &info.to_msgpack()?)?;
+//!
+//! while let Some(frame) = framing::read_frame(&mut spool)? {
+//!     let info = LogInfo::from_msgpack(&frame)?;
+//! }
+//! ```
+
+use std::io::{self, Read, Write};
+
+/// Frames longer than this are rejected when reading, so a corrupt length
+/// prefix can't trigger a huge allocation.
+pub const MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+/// Writes `payload` as a single frame.
+pub fn write_frame<W: Write + ?Sized>(writer: &mut W, payload: &[u8]) -> io::Result<()> {
+    if payload.len() > MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "frame of {} bytes exceeds the {} byte limit",
+                payload.len(),
+                MAX_FRAME_LEN
+            ),
+        ));
+    }
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(payload)
+}
+
+/// Reads the next frame.
+///
+/// Returns `Ok(None)` at a clean end of stream. A stream that ends partway
+/// through a frame (e.g. a spool file cut off by a crash) yields an
+/// `UnexpectedEof` error.
+pub fn read_frame<R: Read + ?Sized>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut prefix = [0u8; 4];
+    let mut filled = 0;
+    while filled < prefix.len() {
+        match reader.read(&mut prefix[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "stream ended inside a frame length prefix",
+                ))
+            }
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let len = u32::from_be_bytes(prefix) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "frame of {} bytes exceeds the {} byte limit",
+                len, MAX_FRAME_LEN
+            ),
+        ));
+    }
+
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+    Ok(Some(payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_frames_roundtrip() {
+        let mut stream = Vec::new();
+        write_frame(&mut stream, b"first").unwrap();
+        write_frame(&mut stream, b"").unwrap();
+        write_frame(&mut stream, b"third").unwrap();
+
+        let mut reader = Cursor::new(stream);
+        assert_eq!(read_frame(&mut reader).unwrap(), Some(b"first".to_vec()));
+        assert_eq!(read_frame(&mut reader).unwrap(), Some(Vec::new()));
+        assert_eq!(read_frame(&mut reader).unwrap(), Some(b"third".to_vec()));
+        assert_eq!(read_frame(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn test_truncated_frame_is_an_error() {
+        let mut stream = Vec::new();
+        write_frame(&mut stream, b"complete").unwrap();
+        write_frame(&mut stream, b"cut off").unwrap();
+        stream.truncate(stream.len() - 3);
+
+        let mut reader = Cursor::new(stream);
+        assert!(read_frame(&mut reader).unwrap().is_some());
+        let err = read_frame(&mut reader).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_oversized_length_is_rejected() {
+        let mut reader = Cursor::new(u32::MAX.to_be_bytes().to_vec());
+        let err = read_frame(&mut reader).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
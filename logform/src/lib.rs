@@ -1,12 +1,48 @@
 pub mod config;
 mod formats;
+pub mod framing;
 mod log_info;
+mod meta;
+pub mod parse;
+mod path;
+pub mod spec;
 mod utils;
 
 pub use formats::{
-    align::align, cli::cli, colorize::colorize, json::json, label::label, logstash::logstash,
-    metadata::metadata, ms::ms, pad_levels::pad_levels, passthrough::passthrough,
-    pretty_print::pretty_print, printf::printf, simple::simple, timestamp::timestamp,
-    uncolorize::uncolorize, Format,
+    align::align,
+    branch::branch,
+    cli::cli,
+    colorize::{colorize, with_terminal},
+    csv::csv,
+    dev::dev,
+    ecs::ecs,
+    enrich::{capture_thread, enrich},
+    errors::errors,
+    fields::fields,
+    filter::{filter, when},
+    gelf::gelf,
+    json::json,
+    label::label,
+    limit::limit,
+    logfmt::logfmt,
+    logstash::logstash,
+    metadata::metadata,
+    ms::ms,
+    otel::otel,
+    pad_levels::pad_levels,
+    passthrough::passthrough,
+    pretty_print::pretty_print,
+    printf::printf,
+    redact::{redact, Redaction},
+    simple::simple,
+    splat::splat,
+    syslog::{syslog_3164, syslog_5424},
+    template::template,
+    timestamp::{timestamp, EpochUnit},
+    uncolorize::uncolorize,
+    ChainedFormat, FanoutFormat, Finalizer, Format, FormatError, MapFormat,
 };
 pub use log_info::LogInfo;
+pub use meta::{Meta, MetaKey};
+pub use path::{FieldPath, PathSegment};
+pub use spec::{from_env, from_spec};
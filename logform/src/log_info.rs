@@ -1,77 +1,218 @@
-#[cfg(feature = "serde")]
+use crate::{formats::splat::SPLAT, Meta, MetaKey};
+use chrono::{DateTime, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    backtrace::{Backtrace, BacktraceStatus},
+    borrow::Cow,
+    error::Error,
+    fmt,
+    str::FromStr,
+};
 
-use serde_json::Value;
-use std::{collections::HashMap, fmt, str::FromStr};
-
-#[cfg(feature = "serde")]
 use std::io::Result as IoResult;
 
 #[non_exhaustive]
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogInfo {
-    pub level: String,
+    /// Level names from string literals (as the logging macros produce) are
+    /// borrowed rather than allocated.
+    pub level: Cow<'static, str>,
     pub message: String,
-    pub meta: HashMap<String, Value>,
+    pub meta: Meta,
+    /// When the entry was created. `Logger::log` fills it in before the entry is
+    /// queued, so formats and transports see the call-site time rather than the
+    /// time the worker got to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
     /// The terminal output string produced by a finalizer format.
     /// Transports read this field (via Display) instead of `message`.
     /// Set only by finalizers; transforms never touch it.
-    #[cfg_attr(feature = "serde", serde(skip))]
+    #[serde(skip)]
     pub formatted: Option<String>,
 }
 
 impl LogInfo {
-    pub fn new<L: Into<String>, M: Into<String>>(level: L, message: M) -> Self {
+    pub fn new<L: Into<Cow<'static, str>>, M: Into<String>>(level: L, message: M) -> Self {
         Self {
             level: level.into(),
             message: message.into(),
-            meta: HashMap::new(),
+            meta: Meta::new(),
+            timestamp: None,
             formatted: None,
         }
     }
 
-    pub fn from_parts<L, M>(level: L, message: M, meta: HashMap<String, Value>) -> Self
+    pub fn from_parts<L, M, T>(level: L, message: M, meta: T) -> Self
     where
-        L: Into<String>,
+        L: Into<Cow<'static, str>>,
         M: Into<String>,
+        T: Into<Meta>,
     {
         Self {
             level: level.into(),
             message: message.into(),
-            meta,
+            meta: meta.into(),
+            timestamp: None,
             formatted: None,
         }
     }
 
+    pub fn with_timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Formats a timestamp the way `to_value`, `to_flat_value` and the default
+    /// `timestamp()` format do: RFC 3339 in UTC with a `Z` suffix.
+    pub fn format_timestamp(timestamp: &DateTime<Utc>) -> String {
+        timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    }
+
+    /// The entry's time: the `timestamp` field, else the `timestamp` meta value
+    /// written by `timestamp()` if it is RFC 3339, else now. Used by formats that
+    /// carry the time in a field of their own; they skip that meta key themselves.
+    pub(crate) fn time(&self) -> DateTime<Utc> {
+        let from_meta = match self.meta.get("timestamp") {
+            Some(Value::String(s)) => DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|dt| dt.with_timezone(&Utc)),
+            _ => None,
+        };
+        self.timestamp.or(from_meta).unwrap_or_else(Utc::now)
+    }
+
     pub fn with_meta<K, V>(mut self, key: K, value: V) -> Self
     where
-        K: Into<String>,
+        K: Into<MetaKey>,
         V: Into<Value>,
     {
-        self.meta.insert(key.into(), value.into());
+        self.meta.insert(key, value.into());
+        self
+    }
+
+    /// Appends a positional argument to the `splat` array in meta, for the
+    /// `splat()` format to substitute into `%s`-style placeholders.
+    pub fn with_splat<V: Into<Value>>(mut self, value: V) -> Self {
+        match self.meta.get_mut(SPLAT) {
+            Some(Value::Array(args)) => args.push(value.into()),
+            _ => {
+                self.meta.insert(SPLAT, Value::Array(vec![value.into()]));
+            }
+        }
+        self
+    }
+
+    pub fn without_meta<K: AsRef<str>>(mut self, key: K) -> Self {
+        self.meta.remove(key.as_ref());
+        self
+    }
+
+    /// Records `error` as a structured `error` object in meta.
+    ///
+    /// The object holds the error's `message`, the messages of its `source()`
+    /// chain under `chain`, and a `backtrace` when one is captured (i.e.
+    /// `RUST_BACKTRACE`/`RUST_LIB_BACKTRACE` enables capture). It also holds
+    /// the `type` name of `E` when that is a concrete type; it is left out for
+    /// trait objects (`&dyn Error`, `Box<dyn Error>`), whose type name says
+    /// nothing about the error. Use the `errors()` format to render it for
+    /// text output.
+    pub fn with_error<E>(mut self, error: &E) -> Self
+    where
+        E: Error + ?Sized,
+    {
+        let mut chain = Vec::new();
+        let mut source = error.source();
+        while let Some(cause) = source {
+            chain.push(Value::String(cause.to_string()));
+            source = cause.source();
+        }
+
+        let mut object = json!({
+            "message": error.to_string(),
+            "chain": chain,
+        });
+        let type_name = std::any::type_name::<E>().trim_start_matches('&');
+        if !type_name.starts_with("dyn ") && !type_name.contains("<dyn ") {
+            object["type"] = Value::String(type_name.to_string());
+        }
+
+        let backtrace = Backtrace::capture();
+        if backtrace.status() == BacktraceStatus::Captured {
+            object["backtrace"] = Value::String(backtrace.to_string());
+        }
+
+        self.meta.insert("error", object);
         self
     }
 
-    pub fn without_meta<K: Into<String>>(mut self, key: K) -> Self {
-        self.meta.remove(&key.into());
+    /// Attaches `backtrace` to the `error` object recorded by [`LogInfo::with_error`],
+    /// for errors that carry their own backtrace (e.g. `anyhow::Error::backtrace`).
+    /// Does nothing if no error has been recorded.
+    pub fn with_backtrace(mut self, backtrace: &Backtrace) -> Self {
+        if backtrace.status() == BacktraceStatus::Captured {
+            if let Some(Value::Object(error)) = self.meta.get_mut("error") {
+                error.insert(
+                    "backtrace".to_string(),
+                    Value::String(backtrace.to_string()),
+                );
+            }
+        }
         self
     }
 
     /// Convert LogInfo to JSON bytes
-    #[cfg(feature = "serde")]
     pub fn to_bytes(&self) -> IoResult<Vec<u8>> {
         serde_json::to_vec(self)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
     }
 
     /// Convert JSON bytes to LogInfo
-    #[cfg(feature = "serde")]
     pub fn from_bytes(bytes: &[u8]) -> IoResult<Self> {
         serde_json::from_slice(bytes)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
     }
 
+    /// Version tag written ahead of the entry by the binary encodings
+    /// (`to_msgpack`, `to_cbor`). Fields added to `LogInfo` later are
+    /// `#[serde(default)]`, so entries written under this version stay readable;
+    /// the tag only changes if the layout changes incompatibly.
+    #[cfg(any(feature = "msgpack", feature = "cbor"))]
+    pub const ENCODING_VERSION: u8 = 1;
+
+    /// Convert LogInfo to versioned MessagePack bytes
+    #[cfg(feature = "msgpack")]
+    pub fn to_msgpack(&self) -> IoResult<Vec<u8>> {
+        // Named (map) encoding so decoding doesn't depend on field positions
+        rmp_serde::to_vec_named(&(Self::ENCODING_VERSION, self))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Convert versioned MessagePack bytes to LogInfo
+    #[cfg(feature = "msgpack")]
+    pub fn from_msgpack(bytes: &[u8]) -> IoResult<Self> {
+        let Versioned(info) = rmp_serde::from_slice(bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(info)
+    }
+
+    /// Convert LogInfo to versioned CBOR bytes
+    #[cfg(feature = "cbor")]
+    pub fn to_cbor(&self) -> IoResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&(Self::ENCODING_VERSION, self), &mut bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        Ok(bytes)
+    }
+
+    /// Convert versioned CBOR bytes to LogInfo
+    #[cfg(feature = "cbor")]
+    pub fn from_cbor(bytes: &[u8]) -> IoResult<Self> {
+        let Versioned(info) = ciborium::from_reader(bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        Ok(info)
+    }
+
     /// Convert serde_json::Value to LogInfo
     pub fn from_value(value: Value) -> Result<Self, String> {
         if let Value::Object(map) = value {
@@ -79,7 +220,8 @@ impl LogInfo {
                 .get("level")
                 .and_then(Value::as_str)
                 .ok_or("Missing or invalid 'level' field")?
-                .to_string();
+                .to_string()
+                .into();
 
             let message = map
                 .get("message")
@@ -87,19 +229,24 @@ impl LogInfo {
                 .ok_or("Missing or invalid 'message' field")?
                 .to_string();
 
-            let mut meta = HashMap::new();
-            if let Some(meta_value) = map.get("meta") {
-                if let Value::Object(meta_map) = meta_value.clone() {
-                    for (key, value) in meta_map {
-                        meta.insert(key, value);
-                    }
+            let mut meta = Meta::new();
+            if let Some(Value::Object(meta_map)) = map.get("meta") {
+                for (key, value) in meta_map {
+                    meta.insert(key, value.clone());
                 }
             }
 
+            let timestamp = map
+                .get("timestamp")
+                .and_then(Value::as_str)
+                .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+                .map(|ts| ts.with_timezone(&Utc));
+
             Ok(Self {
                 level,
                 message,
                 meta,
+                timestamp,
                 formatted: None,
             })
         } else {
@@ -108,24 +255,36 @@ impl LogInfo {
     }
 
     pub fn to_value(&self) -> Value {
-        serde_json::json!({
+        let mut value = serde_json::json!({
             "level": self.level,
             "message": self.message,
-            "meta": self.meta,
-        })
+            "meta": self.meta.to_value(),
+        });
+        if let Some(timestamp) = &self.timestamp {
+            value["timestamp"] = Value::String(Self::format_timestamp(timestamp));
+        }
+        value
     }
 
     /// Returns a flattened JSON representation where metadata fields are at the root level.
     /// This is used by transports for consistent serialization and querying.
     /// Users query fields directly without "meta." prefix.
+    /// The first-class `timestamp`, when set, takes precedence over a `timestamp` meta key.
     pub fn to_flat_value(&self) -> Value {
         let mut flat = serde_json::Map::new();
-        flat.insert("level".to_string(), Value::String(self.level.clone()));
+        flat.insert("level".to_string(), Value::String(self.level.to_string()));
         flat.insert("message".to_string(), Value::String(self.message.clone()));
 
         // Merge all metadata fields at root level
         for (key, value) in &self.meta {
-            flat.insert(key.clone(), value.clone());
+            flat.insert(key.to_string(), value.clone());
+        }
+
+        if let Some(timestamp) = &self.timestamp {
+            flat.insert(
+                "timestamp".to_string(),
+                Value::String(Self::format_timestamp(timestamp)),
+            );
         }
 
         Value::Object(flat)
@@ -149,6 +308,47 @@ macro_rules! log_info {
     }};
 }
 
+/// A `(version, LogInfo)` pair as written by the binary encodings. The version
+/// is read and checked before the entry, so an entry written under a newer,
+/// incompatible layout is reported as such rather than as a decoding error.
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+struct Versioned(LogInfo);
+
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+impl<'de> Deserialize<'de> for Versioned {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{Error, SeqAccess, Visitor};
+
+        struct VersionedVisitor;
+
+        impl<'de> Visitor<'de> for VersionedVisitor {
+            type Value = Versioned;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an encoding version followed by a log entry")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Versioned, A::Error> {
+                let version: u8 = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+                if version == 0 || version > LogInfo::ENCODING_VERSION {
+                    return Err(A::Error::custom(format!(
+                        "unsupported LogInfo encoding version {}",
+                        version
+                    )));
+                }
+                let info = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+                Ok(Versioned(info))
+            }
+        }
+
+        deserializer.deserialize_tuple(2, VersionedVisitor)
+    }
+}
+
 impl fmt::Display for LogInfo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Some(s) = &self.formatted {
@@ -188,7 +388,7 @@ impl FromStr for LogInfo {
 
         let end_bracket = s.find(']').ok_or("Missing closing bracket for level")?;
 
-        let level = s[1..end_bracket].to_string();
+        let level = Cow::Owned(s[1..end_bracket].to_string());
         let rest = s[end_bracket + 1..].trim();
 
         // Split message and metadata if present
@@ -197,7 +397,7 @@ impl FromStr for LogInfo {
             let meta_str = &rest[meta_start..];
 
             // Parse metadata (simple key: value parsing)
-            let mut meta = HashMap::new();
+            let mut meta = Meta::new();
             if let Some(meta_end) = meta_str.rfind('}') {
                 let meta_content = &meta_str[1..meta_end];
                 for pair in meta_content.split(',') {
@@ -219,6 +419,7 @@ impl FromStr for LogInfo {
                 level,
                 message,
                 meta,
+                timestamp: None,
                 formatted: None,
             })
         } else {
@@ -226,7 +427,8 @@ impl FromStr for LogInfo {
             Ok(LogInfo {
                 level,
                 message: rest.to_string(),
-                meta: HashMap::new(),
+                meta: Meta::new(),
+                timestamp: None,
                 formatted: None,
             })
         }
@@ -238,7 +440,6 @@ mod tests {
     use super::*;
     use serde_json::json;
 
-    #[cfg(feature = "serde")]
     #[test]
     fn test_byte_serialization_and_deserialization() {
         let log = LogInfo::new("INFO", "Test message")
@@ -255,6 +456,98 @@ mod tests {
         assert_eq!(deserialized_log.meta["attempts"], json!(3));
     }
 
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack_roundtrip() {
+        let timestamp = DateTime::parse_from_rfc3339("2024-04-01T12:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let log = LogInfo::new("warn", "Spooled")
+            .with_meta("attempts", 3)
+            .with_meta("nested", json!({"ok": false, "ratio": 0.5}))
+            .with_timestamp(timestamp);
+
+        let bytes = log.to_msgpack().unwrap();
+        let decoded = LogInfo::from_msgpack(&bytes).unwrap();
+
+        assert_eq!(decoded.level, "warn");
+        assert_eq!(decoded.message, "Spooled");
+        assert_eq!(decoded.meta, log.meta);
+        assert_eq!(decoded.timestamp, Some(timestamp));
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack_rejects_unknown_version() {
+        let log = LogInfo::new("info", "From the future");
+        let bytes = rmp_serde::to_vec_named(&(LogInfo::ENCODING_VERSION + 1, &log)).unwrap();
+
+        let err = LogInfo::from_msgpack(&bytes).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err
+            .to_string()
+            .contains("unsupported LogInfo encoding version"));
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack_checks_version_before_body() {
+        // A future layout whose body isn't a LogInfo at all
+        let bytes = rmp_serde::to_vec_named(&(2u8, json!(["info", "message", 7]))).unwrap();
+
+        let err = LogInfo::from_msgpack(&bytes).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("unsupported LogInfo encoding version 2"));
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor_checks_version_before_body() {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&(2u8, json!({"lvl": 30, "msg": ["new"]})), &mut bytes).unwrap();
+
+        let err = LogInfo::from_cbor(&bytes).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("unsupported LogInfo encoding version 2"));
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor_roundtrip() {
+        let log = LogInfo::new("error", "Spooled")
+            .with_meta("code", 500)
+            .with_meta("tags", json!(["a", "b"]));
+
+        let bytes = log.to_cbor().unwrap();
+        let decoded = LogInfo::from_cbor(&bytes).unwrap();
+
+        assert_eq!(decoded.level, "error");
+        assert_eq!(decoded.meta, log.meta);
+        assert_eq!(decoded.timestamp, None);
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor_reads_entries_without_newer_fields() {
+        // An entry written before `timestamp` existed
+        let mut bytes = Vec::new();
+        ciborium::into_writer(
+            &(
+                1u8,
+                json!({"level": "info", "message": "old", "meta": {"k": 1}}),
+            ),
+            &mut bytes,
+        )
+        .unwrap();
+
+        let decoded = LogInfo::from_cbor(&bytes).unwrap();
+        assert_eq!(decoded.message, "old");
+        assert_eq!(decoded.meta["k"], json!(1));
+        assert_eq!(decoded.timestamp, None);
+    }
+
     #[test]
     fn test_from_value() {
         let json_value = json!({
@@ -274,6 +567,67 @@ mod tests {
         assert_eq!(log_info.meta["id"], json!(12345));
         assert_eq!(log_info.meta["status"], json!("pending"));
     }
+
+    #[test]
+    fn test_timestamp_serialization() {
+        let timestamp = DateTime::parse_from_rfc3339("2024-04-01T12:30:00.250Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let log = LogInfo::new("info", "Stamped")
+            .with_meta("timestamp", "12:30")
+            .with_timestamp(timestamp);
+
+        assert_eq!(
+            log.to_value()["timestamp"],
+            json!("2024-04-01T12:30:00.250Z")
+        );
+        assert_eq!(
+            log.to_flat_value()["timestamp"],
+            json!("2024-04-01T12:30:00.250Z")
+        );
+
+        let parsed = LogInfo::from_value(log.to_value()).unwrap();
+        assert_eq!(parsed.timestamp, Some(timestamp));
+    }
+
+    #[derive(Debug)]
+    struct WrappedError(std::io::Error);
+
+    impl fmt::Display for WrappedError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "failed to load config")
+        }
+    }
+
+    impl Error for WrappedError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_with_error_records_source_chain() {
+        let error = WrappedError(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "config.toml missing",
+        ));
+        let log = LogInfo::new("error", "startup failed").with_error(&error);
+
+        let recorded = &log.meta["error"];
+        assert_eq!(recorded["message"], json!("failed to load config"));
+        assert!(recorded["type"].as_str().unwrap().ends_with("WrappedError"));
+        assert_eq!(recorded["chain"], json!(["config.toml missing"]));
+    }
+
+    #[test]
+    fn test_with_error_accepts_trait_objects() {
+        let error: Box<dyn Error + Send + Sync> = "boxed failure".into();
+        let log = LogInfo::new("error", "").with_error(&*error);
+
+        assert_eq!(log.meta["error"]["message"], json!("boxed failure"));
+        assert_eq!(log.meta["error"]["chain"], json!([]));
+        assert!(log.meta["error"].get("type").is_none());
+    }
 }
 
 #[cfg(test)]
@@ -294,21 +648,19 @@ mod display_tests {
             .with_meta("retry", 3)
             .with_meta("host", "example.com");
 
-        let display = format!("{}", log);
-        // meta is a HashMap so key order isn't guaranteed — check parts separately
-        assert!(display.starts_with("ERROR Connection failed "));
-        let json_part = &display["ERROR Connection failed ".len()..];
-        let parsed: serde_json::Value = serde_json::from_str(json_part).unwrap();
-        assert_eq!(parsed["retry"], json!(3));
-        assert_eq!(parsed["host"], json!("example.com"));
+        assert_eq!(
+            format!("{}", log),
+            r#"ERROR Connection failed {"retry":3,"host":"example.com"}"#
+        );
     }
 
     #[test]
     fn test_display_with_formatted() {
         let log = LogInfo {
-            level: "info".to_string(),
+            level: "info".into(),
             message: "original".to_string(),
             meta: Default::default(),
+            timestamp: None,
             formatted: Some("custom output".to_string()),
         };
         assert_eq!(format!("{}", log), "custom output");
@@ -341,7 +693,6 @@ mod display_tests {
     }
 
     #[test]
-    #[cfg(feature = "serde")]
     fn test_roundtrip() {
         let original = LogInfo::new("INFO", "Test message").with_meta("key", "value");
 
@@ -352,4 +703,14 @@ mod display_tests {
         assert_eq!(parsed.message, original.message);
         assert_eq!(parsed.meta, original.meta);
     }
+
+    #[test]
+    fn test_bytes_roundtrip_keeps_timestamp() {
+        let original = LogInfo::new("INFO", "Test message").with_timestamp(Utc::now());
+
+        let bytes = original.to_bytes().unwrap();
+        let parsed = LogInfo::from_bytes(&bytes).unwrap();
+
+        assert_eq!(parsed.timestamp, original.timestamp);
+    }
 }
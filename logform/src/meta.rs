@@ -0,0 +1,437 @@
+use indexmap::{map, IndexMap};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::{
+    borrow::{Borrow, Cow},
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Deref, Index, IndexMut},
+    sync::Arc,
+};
+
+/// A metadata key.
+///
+/// Keys built from `&'static str` (string literals, `stringify!`, tracing field
+/// names) are stored without allocating; other keys are stored as a shared
+/// `Arc<str>`, so cloning a `LogInfo` never copies key strings.
+#[derive(Clone)]
+pub struct MetaKey(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    Static(&'static str),
+    Shared(Arc<str>),
+}
+
+impl MetaKey {
+    pub const fn from_static(key: &'static str) -> Self {
+        Self(Repr::Static(key))
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            Repr::Static(key) => key,
+            Repr::Shared(key) => key,
+        }
+    }
+}
+
+impl Deref for MetaKey {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for MetaKey {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for MetaKey {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Hash for MetaKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl PartialEq for MetaKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for MetaKey {}
+
+impl PartialEq<str> for MetaKey {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for MetaKey {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialOrd for MetaKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MetaKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl fmt::Debug for MetaKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for MetaKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&'static str> for MetaKey {
+    fn from(key: &'static str) -> Self {
+        Self::from_static(key)
+    }
+}
+
+impl From<String> for MetaKey {
+    fn from(key: String) -> Self {
+        Self(Repr::Shared(key.into()))
+    }
+}
+
+impl From<&String> for MetaKey {
+    fn from(key: &String) -> Self {
+        Self(Repr::Shared(key.as_str().into()))
+    }
+}
+
+impl From<Arc<str>> for MetaKey {
+    fn from(key: Arc<str>) -> Self {
+        Self(Repr::Shared(key))
+    }
+}
+
+impl From<Cow<'static, str>> for MetaKey {
+    fn from(key: Cow<'static, str>) -> Self {
+        match key {
+            Cow::Borrowed(key) => Self::from_static(key),
+            Cow::Owned(key) => key.into(),
+        }
+    }
+}
+
+impl From<&MetaKey> for MetaKey {
+    fn from(key: &MetaKey) -> Self {
+        key.clone()
+    }
+}
+
+impl From<MetaKey> for String {
+    fn from(key: MetaKey) -> Self {
+        key.as_str().to_string()
+    }
+}
+
+impl Serialize for MetaKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for MetaKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(MetaKey::from)
+    }
+}
+
+/// The metadata attached to a [`LogInfo`](crate::LogInfo).
+///
+/// Entries keep their insertion order, so `json()`, `logstash()` and `simple()`
+/// print meta fields in the order they were logged. Removing a key keeps the
+/// order of the remaining entries. The order is kept by `Meta` itself and does
+/// not depend on serde_json's `preserve_order` feature.
+#[derive(Clone, Default, PartialEq)]
+pub struct Meta(IndexMap<MetaKey, Value>);
+
+impl Meta {
+    pub fn new() -> Self {
+        Self(IndexMap::new())
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(IndexMap::with_capacity(capacity))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.0.get_mut(key)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    /// Inserts a value, returning the previous one. Replacing an existing key
+    /// keeps its position.
+    pub fn insert<K: Into<MetaKey>>(&mut self, key: K, value: Value) -> Option<Value> {
+        self.0.insert(key.into(), value)
+    }
+
+    /// Removes a key, keeping the order of the remaining entries.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        self.0.shift_remove(key)
+    }
+
+    pub fn remove_entry(&mut self, key: &str) -> Option<(MetaKey, Value)> {
+        self.0.shift_remove_entry(key)
+    }
+
+    pub fn retain<F>(&mut self, keep: F)
+    where
+        F: FnMut(&MetaKey, &mut Value) -> bool,
+    {
+        self.0.retain(keep)
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    pub fn iter(&self) -> map::Iter<'_, MetaKey, Value> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> map::IterMut<'_, MetaKey, Value> {
+        self.0.iter_mut()
+    }
+
+    pub fn keys(&self) -> map::Keys<'_, MetaKey, Value> {
+        self.0.keys()
+    }
+
+    pub fn values(&self) -> map::Values<'_, MetaKey, Value> {
+        self.0.values()
+    }
+
+    pub fn values_mut(&mut self) -> map::ValuesMut<'_, MetaKey, Value> {
+        self.0.values_mut()
+    }
+
+    /// Returns the metadata as a JSON object. Its key order is serde_json's
+    /// `Map` order (sorted unless `preserve_order` is enabled); serialize the
+    /// `Meta` itself to write keys in insertion order.
+    pub fn to_value(&self) -> Value {
+        Value::Object(self.to_map())
+    }
+
+    pub fn to_map(&self) -> Map<String, Value> {
+        self.iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect()
+    }
+}
+
+impl fmt::Debug for Meta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl Index<&str> for Meta {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        self.get(key)
+            .unwrap_or_else(|| panic!("no meta entry for key {:?}", key))
+    }
+}
+
+impl IndexMut<&str> for Meta {
+    fn index_mut(&mut self, key: &str) -> &mut Value {
+        self.get_mut(key)
+            .unwrap_or_else(|| panic!("no meta entry for key {:?}", key))
+    }
+}
+
+impl<K: Into<MetaKey>, V: Into<Value>> FromIterator<(K, V)> for Meta {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+}
+
+impl<K: Into<MetaKey>, V: Into<Value>> Extend<(K, V)> for Meta {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.0.extend(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into())),
+        )
+    }
+}
+
+impl IntoIterator for Meta {
+    type Item = (MetaKey, Value);
+    type IntoIter = map::IntoIter<MetaKey, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Meta {
+    type Item = (&'a MetaKey, &'a Value);
+    type IntoIter = map::Iter<'a, MetaKey, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Meta {
+    type Item = (&'a MetaKey, &'a mut Value);
+    type IntoIter = map::IterMut<'a, MetaKey, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut()
+    }
+}
+
+impl From<HashMap<String, Value>> for Meta {
+    fn from(map: HashMap<String, Value>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl From<Map<String, Value>> for Meta {
+    fn from(map: Map<String, Value>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl From<Meta> for Map<String, Value> {
+    fn from(meta: Meta) -> Self {
+        meta.into_iter()
+            .map(|(key, value)| (key.into(), value))
+            .collect()
+    }
+}
+
+impl From<Meta> for Value {
+    fn from(meta: Meta) -> Self {
+        Value::Object(meta.into())
+    }
+}
+
+impl Serialize for Meta {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for Meta {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MetaVisitor;
+
+        impl<'de> de::Visitor<'de> for MetaVisitor {
+            type Value = Meta;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of metadata")
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, mut access: A) -> Result<Meta, A::Error> {
+                let mut meta = Meta::with_capacity(access.size_hint().unwrap_or(0));
+                while let Some((key, value)) = access.next_entry::<String, Value>()? {
+                    meta.insert(key, value);
+                }
+                Ok(meta)
+            }
+        }
+
+        deserializer.deserialize_map(MetaVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_meta_keeps_insertion_order() {
+        let mut meta = Meta::new();
+        meta.insert("zeta", json!(1));
+        meta.insert("alpha", json!(2));
+        meta.insert("mid".to_string(), json!(3));
+        meta.insert("zeta", json!(4));
+
+        let keys: Vec<&str> = meta.keys().map(MetaKey::as_str).collect();
+        assert_eq!(keys, ["zeta", "alpha", "mid"]);
+        assert_eq!(meta["zeta"], json!(4));
+
+        meta.remove("zeta");
+        let keys: Vec<&str> = meta.keys().map(MetaKey::as_str).collect();
+        assert_eq!(keys, ["alpha", "mid"]);
+
+        assert_eq!(
+            serde_json::to_string(&meta).unwrap(),
+            r#"{"alpha":2,"mid":3}"#
+        );
+    }
+
+    #[test]
+    fn test_meta_key_equality_ignores_storage() {
+        let stored = MetaKey::from("user_id");
+        let shared = MetaKey::from("user_id".to_string());
+
+        assert_eq!(stored, shared);
+        assert_eq!(stored, "user_id");
+
+        let mut meta = Meta::new();
+        meta.insert(shared, Value::Bool(true));
+        assert!(meta.contains_key("user_id"));
+    }
+
+    #[test]
+    fn test_meta_deserializes_in_order() {
+        let meta: Meta = serde_json::from_str(r#"{"b":1,"a":{"c":2}}"#).unwrap();
+
+        let keys: Vec<&str> = meta.keys().map(MetaKey::as_str).collect();
+        assert_eq!(keys, ["b", "a"]);
+        assert_eq!(meta.to_value(), json!({"b": 1, "a": {"c": 2}}));
+    }
+}
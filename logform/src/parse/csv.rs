@@ -0,0 +1,189 @@
+use super::{with_meta_timestamp, Parser};
+use crate::{
+    formats::{csv::Column, uncolorize::strip_colors},
+    LogInfo, Meta,
+};
+use serde_json::Value;
+
+/// Reads lines written by `csv()` with the same columns and delimiter.
+///
+/// Empty fields are skipped. Other meta fields are typed back where they look
+/// like JSON (numbers, booleans, objects, arrays), so a string that looks like
+/// a number comes back as one.
+///
+/// A record is complete once its quotes balance, so readers join the lines of
+/// a quoted field that contains a line break. The header from `csv().header()`
+/// is recognised by [`Parser::is_header`]; `parse` reads it like any entry.
+#[derive(Clone)]
+pub struct CsvParser {
+    /// `None` for columns with invalid paths, which are skipped.
+    columns: Vec<(String, Option<Column>)>,
+    delimiter: char,
+}
+
+impl CsvParser {
+    /// Takes the column paths given to `csv().with_columns(..)`.
+    pub fn new(columns: &[&str]) -> Self {
+        let columns = columns
+            .iter()
+            .map(|path| {
+                let column = Column::parse(path)
+                    .map_err(|e| eprintln!("[logform::parse::csv] Ignoring column: {}", e))
+                    .ok();
+                (path.to_string(), column)
+            })
+            .collect();
+        Self {
+            columns,
+            delimiter: ',',
+        }
+    }
+
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Splits a record into its fields, undoing RFC 4180 quoting.
+    fn fields(&self, line: &str) -> Result<Vec<String>, String> {
+        let mut fields = Vec::new();
+        let mut chars = line.chars().peekable();
+        loop {
+            let mut field = String::new();
+            if chars.next_if_eq(&'"').is_some() {
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                        Some('"') => break,
+                        Some(c) => field.push(c),
+                        None => {
+                            return Err(format!("Unterminated quote in field {}", fields.len() + 1))
+                        }
+                    }
+                }
+                if chars.peek().is_some_and(|c| *c != self.delimiter) {
+                    return Err(format!(
+                        "Unexpected text after quoted field {}",
+                        fields.len() + 1
+                    ));
+                }
+            } else {
+                while let Some(c) = chars.next_if(|c| *c != self.delimiter) {
+                    field.push(c);
+                }
+            }
+            fields.push(field);
+            if chars.next().is_none() {
+                return Ok(fields);
+            }
+        }
+    }
+
+    fn typed(field: String) -> Value {
+        match field.as_bytes().first() {
+            Some(b'{' | b'[' | b'-' | b'0'..=b'9' | b't' | b'f') => {
+                serde_json::from_str(&field).unwrap_or(Value::String(field))
+            }
+            _ => Value::String(field),
+        }
+    }
+}
+
+impl Parser for CsvParser {
+    fn parse(&self, line: &str) -> Result<LogInfo, String> {
+        let line = strip_colors(line);
+        let fields = self.fields(line.trim_end_matches(['\r', '\n']))?;
+        if fields.len() != self.columns.len() {
+            return Err(format!(
+                "Expected {} fields, found {}",
+                self.columns.len(),
+                fields.len()
+            ));
+        }
+        let mut level = None;
+        let mut message = String::new();
+        let mut meta = Meta::new();
+        for (field, (_, column)) in fields.into_iter().zip(&self.columns) {
+            if field.is_empty() {
+                continue;
+            }
+            match column {
+                Some(Column::Level) => level = Some(field),
+                Some(Column::Message) => message = field,
+                Some(Column::Timestamp) => {
+                    meta.insert("timestamp", Value::String(field));
+                }
+                Some(Column::Meta(path)) => {
+                    path.insert(&mut meta, Self::typed(field)).ok();
+                }
+                None => {}
+            }
+        }
+
+        let level = level.ok_or("Missing level")?;
+        Ok(with_meta_timestamp(LogInfo::from_parts(
+            level, message, meta,
+        )))
+    }
+
+    fn is_complete(&self, record: &str) -> bool {
+        record.matches('"').count().is_multiple_of(2)
+    }
+
+    fn is_header(&self, record: &str) -> bool {
+        let record = strip_colors(record);
+        self.fields(record.trim_end_matches(['\r', '\n']))
+            .is_ok_and(|fields| {
+                fields.len() == self.columns.len()
+                    && fields
+                        .iter()
+                        .zip(&self.columns)
+                        .all(|(field, (name, _))| *field == *name)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_csv_parser_reads_quoted_fields() {
+        let parser = CsvParser::new(&["level", "message", "order.id", "order.paid", "note"]);
+        let parsed = parser
+            .parse("info,\"Line one\nsaid \"\"ok\"\"\",00042,true,\"a,b\"")
+            .unwrap();
+
+        assert_eq!(parsed.level, "info");
+        assert_eq!(parsed.message, "Line one\nsaid \"ok\"");
+        assert_eq!(parsed.meta["order"], json!({"id": "00042", "paid": true}));
+        assert_eq!(parsed.meta["note"], json!("a,b"));
+
+        assert!(!parser.is_complete("info,\"Line one"));
+        assert!(parser.is_complete("info,\"Line one\nsaid \"\"ok\"\"\",00042,true,\"a,b\""));
+    }
+
+    #[test]
+    fn test_csv_parser_header() {
+        let parser = CsvParser::new(&["level", "message"]);
+        assert!(parser.is_header("level,message"));
+        assert!(!parser.is_header("info,message"));
+
+        // An entry whose fields happen to be the column names still parses
+        let parsed = parser.parse("level,message").unwrap();
+        assert_eq!(
+            (parsed.level.as_ref(), parsed.message.as_str()),
+            ("level", "message")
+        );
+    }
+
+    #[test]
+    fn test_csv_parser_errors() {
+        let parser = CsvParser::new(&["level", "message"]);
+        assert!(parser.parse("info,hi,extra").is_err());
+        assert!(parser.parse(",hi").is_err());
+        assert!(parser.parse("info,\"open").is_err());
+        assert!(parser.parse("info,\"quoted\"tail").is_err());
+    }
+}
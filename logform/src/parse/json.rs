@@ -0,0 +1,63 @@
+use super::{with_meta_timestamp, Parser};
+use crate::{formats::uncolorize::strip_colors, LogInfo, Meta};
+use serde_json::{Map, Value};
+
+/// Reads `{"level":..,"message":..,...}` objects as written by `json()`: every
+/// other top-level key becomes meta.
+pub struct JsonParser;
+
+impl JsonParser {
+    pub(crate) fn from_object(mut object: Map<String, Value>) -> Result<LogInfo, String> {
+        let level = match object.remove("level") {
+            Some(Value::String(level)) => strip_colors(&level),
+            _ => return Err("Missing or invalid 'level' field".to_string()),
+        };
+        let message = match object.remove("message") {
+            Some(Value::String(message)) => message,
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+
+        let meta: Meta = object.into();
+        Ok(with_meta_timestamp(LogInfo::from_parts(
+            level, message, meta,
+        )))
+    }
+}
+
+impl Parser for JsonParser {
+    fn parse(&self, line: &str) -> Result<LogInfo, String> {
+        match serde_json::from_str(line.trim()) {
+            Ok(Value::Object(object)) => Self::from_object(object),
+            Ok(_) => Err("Expected a JSON object".to_string()),
+            Err(e) => Err(format!("Invalid JSON: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{json, Format};
+    use serde_json::json;
+
+    #[test]
+    fn test_json_parser_reads_json_format() {
+        let info = LogInfo::new("info", "User logged in")
+            .with_meta("user_id", 42)
+            .with_meta("timestamp", "2024-04-01T12:30:00Z");
+        let line = json().transform(info).unwrap().formatted.unwrap();
+
+        let parsed = JsonParser.parse(&line).unwrap();
+        assert_eq!(parsed.level, "info");
+        assert_eq!(parsed.message, "User logged in");
+        assert_eq!(parsed.meta["user_id"], json!(42));
+        assert!(parsed.timestamp.is_some());
+    }
+
+    #[test]
+    fn test_json_parser_rejects_missing_level() {
+        assert!(JsonParser.parse(r#"{"message":"no level"}"#).is_err());
+        assert!(JsonParser.parse("not json").is_err());
+    }
+}
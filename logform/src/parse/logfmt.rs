@@ -0,0 +1,235 @@
+use super::{with_meta_timestamp, Parser};
+use crate::{formats::uncolorize::strip_colors, LogInfo, Meta};
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+
+/// Reads `level=info msg="User logged in" user_id=42` lines.
+///
+/// `msg` (or `message`) becomes the message and an RFC 3339 `ts` the entry's
+/// timestamp. Quoted values are always strings;
+/// bare `true`/`false`/`null` and numbers are typed, and a key without `=` is
+/// read as `true`. With `with_unflatten(true)`, dotted keys written by
+/// `logfmt()` are rebuilt into nested objects.
+#[derive(Clone, Default)]
+pub struct LogfmtParser {
+    unflatten: bool,
+}
+
+impl LogfmtParser {
+    pub fn new() -> Self {
+        Self { unflatten: false }
+    }
+
+    pub fn with_unflatten(mut self, unflatten: bool) -> Self {
+        self.unflatten = unflatten;
+        self
+    }
+
+    /// Inserts `a.b.c=value` as `{"a": {"b": {"c": value}}}`, keeping the
+    /// dotted key as-is if a non-object value is in the way.
+    fn insert_nested(meta: &mut Meta, key: String, value: Value) {
+        let segments: Vec<&str> = key.split('.').collect();
+        if segments.len() < 2 || segments.iter().any(|segment| segment.is_empty()) {
+            meta.insert(key, value);
+            return;
+        }
+        let (leaf, parents) = segments.split_last().unwrap();
+
+        let mut current = None;
+        for (depth, segment) in parents.iter().enumerate() {
+            current = match depth {
+                0 => meta.get(segment),
+                _ => current.and_then(|parent: &Value| parent.get(segment)),
+            };
+            if current.is_some_and(|parent| !parent.is_object()) {
+                meta.insert(key, value);
+                return;
+            }
+        }
+
+        if !meta.contains_key(parents[0]) {
+            meta.insert(parents[0].to_string(), Value::Object(Map::new()));
+        }
+        let mut slot = &mut meta[parents[0]];
+        for segment in &parents[1..] {
+            slot = slot
+                .as_object_mut()
+                .unwrap()
+                .entry(segment.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+        }
+        slot.as_object_mut()
+            .unwrap()
+            .insert(leaf.to_string(), value);
+    }
+
+    /// Splits a line into `(key, value)` pairs.
+    pub(crate) fn pairs(line: &str) -> Result<Vec<(String, Value)>, String> {
+        let mut pairs = Vec::new();
+        let mut chars = line.chars().peekable();
+
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.peek().is_none() {
+                break;
+            }
+
+            let mut key = String::new();
+            while let Some(c) = chars.next_if(|c| *c != '=' && !c.is_whitespace()) {
+                key.push(c);
+            }
+            if key.is_empty() {
+                return Err("Expected a key before '='".to_string());
+            }
+
+            if chars.next_if_eq(&'=').is_none() {
+                pairs.push((key, Value::Bool(true)));
+                continue;
+            }
+
+            let value = if chars.next_if_eq(&'"').is_some() {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('r') => value.push('\r'),
+                            Some('t') => value.push('\t'),
+                            Some('u') if chars.next_if_eq(&'{').is_some() => {
+                                let mut hex = String::new();
+                                while let Some(c) = chars.next_if(|c| *c != '}') {
+                                    hex.push(c);
+                                }
+                                chars.next();
+                                let c = u32::from_str_radix(&hex, 16)
+                                    .ok()
+                                    .and_then(char::from_u32)
+                                    .ok_or_else(|| format!("Invalid escape in '{}'", key))?;
+                                value.push(c);
+                            }
+                            Some(c) => value.push(c),
+                            None => return Err(format!("Unterminated value for '{}'", key)),
+                        },
+                        Some(c) => value.push(c),
+                        None => return Err(format!("Unterminated value for '{}'", key)),
+                    }
+                }
+                Value::String(value)
+            } else {
+                let mut raw = String::new();
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    raw.push(c);
+                }
+                Self::typed(raw)
+            };
+            pairs.push((key, value));
+        }
+
+        Ok(pairs)
+    }
+
+    fn typed(raw: String) -> Value {
+        match raw.as_str() {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "null" => Value::Null,
+            _ => match raw.parse::<serde_json::Number>() {
+                Ok(number) => Value::Number(number),
+                Err(_) => Value::String(raw),
+            },
+        }
+    }
+}
+
+impl Parser for LogfmtParser {
+    fn parse(&self, line: &str) -> Result<LogInfo, String> {
+        let mut level = None;
+        let mut message = None;
+        let mut timestamp = None;
+        let mut meta = Meta::new();
+
+        for (key, value) in Self::pairs(&strip_colors(line))? {
+            match (key.as_str(), value) {
+                ("level", Value::String(value)) => level = Some(value),
+                ("msg" | "message", Value::String(value)) if message.is_none() => {
+                    message = Some(value)
+                }
+                ("ts", Value::String(value)) if timestamp.is_none() => {
+                    match DateTime::parse_from_rfc3339(&value) {
+                        Ok(ts) => timestamp = Some(ts.with_timezone(&Utc)),
+                        Err(_) => {
+                            meta.insert(key, Value::String(value));
+                        }
+                    }
+                }
+                (_, value) if self.unflatten => Self::insert_nested(&mut meta, key, value),
+                (_, value) => {
+                    meta.insert(key, value);
+                }
+            }
+        }
+
+        let level = level.ok_or("Missing 'level' key")?;
+        let mut info = LogInfo::from_parts(level, message.unwrap_or_default(), meta);
+        info.timestamp = timestamp;
+        Ok(with_meta_timestamp(info))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_logfmt_parser_reads_pairs() {
+        let line = r#"level=info msg="User \"bob\" logged in\nagain" user_id=42 ratio=0.5 admin=false tag=blue cached"#;
+        let parsed = LogfmtParser::new().parse(line).unwrap();
+
+        assert_eq!(parsed.level, "info");
+        assert_eq!(parsed.message, "User \"bob\" logged in\nagain");
+        assert_eq!(parsed.meta["user_id"], json!(42));
+        assert_eq!(parsed.meta["ratio"], json!(0.5));
+        assert_eq!(parsed.meta["admin"], json!(false));
+        assert_eq!(parsed.meta["tag"], json!("blue"));
+        assert_eq!(parsed.meta["cached"], json!(true));
+    }
+
+    #[test]
+    fn test_logfmt_parser_keeps_quoted_numbers_as_strings() {
+        let parsed = LogfmtParser::new()
+            .parse(r#"level=debug msg=hi zip="01234" empty="""#)
+            .unwrap();
+        assert_eq!(parsed.message, "hi");
+        assert_eq!(parsed.meta["zip"], json!("01234"));
+        assert_eq!(parsed.meta["empty"], json!(""));
+    }
+
+    #[test]
+    fn test_logfmt_parser_unflattens_dotted_keys() {
+        let parsed = LogfmtParser::new()
+            .with_unflatten(true)
+            .parse(
+                r#"level=info msg=ok http.method=GET http.res.status=200 a=1 a.b=2 esc="\u{1b}""#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            parsed.meta["http"],
+            json!({"method": "GET", "res": {"status": 200}})
+        );
+        assert_eq!(parsed.meta["a"], json!(1));
+        assert_eq!(parsed.meta["a.b"], json!(2));
+        assert_eq!(parsed.meta["esc"], json!("\u{1b}"));
+    }
+
+    #[test]
+    fn test_logfmt_parser_errors() {
+        assert!(LogfmtParser::new().parse("msg=hi").is_err());
+        assert!(LogfmtParser::new()
+            .parse(r#"level=info msg="open"#)
+            .is_err());
+        assert!(LogfmtParser::new().parse("level=info =oops").is_err());
+    }
+}
@@ -0,0 +1,71 @@
+use super::{with_meta_timestamp, Parser};
+use crate::{formats::uncolorize::strip_colors, LogInfo, Meta};
+use serde_json::Value;
+
+/// Reads `{"@message":..,"@timestamp":..,"@fields":{..}}` objects as written by
+/// `logstash()`. `@fields.level` becomes the level, `@timestamp` the `timestamp`
+/// meta key, and the remaining `@fields` the rest of meta.
+pub struct LogstashParser;
+
+impl Parser for LogstashParser {
+    fn parse(&self, line: &str) -> Result<LogInfo, String> {
+        let Value::Object(mut object) =
+            serde_json::from_str(line.trim()).map_err(|e| format!("Invalid JSON: {}", e))?
+        else {
+            return Err("Expected a JSON object".to_string());
+        };
+
+        let mut fields = match object.remove("@fields") {
+            Some(Value::Object(fields)) => fields,
+            _ => return Err("Missing or invalid '@fields' object".to_string()),
+        };
+        let level = match fields.remove("level") {
+            Some(Value::String(level)) => strip_colors(&level),
+            _ => return Err("Missing or invalid '@fields.level' field".to_string()),
+        };
+        let message = match object.remove("@message") {
+            Some(Value::String(message)) => message,
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+
+        let mut meta = Meta::with_capacity(fields.len() + 1);
+        if let Some(timestamp) = object.remove("@timestamp") {
+            meta.insert("timestamp", timestamp);
+        }
+        meta.extend(fields);
+
+        Ok(with_meta_timestamp(LogInfo::from_parts(
+            level, message, meta,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{logstash, Format};
+    use serde_json::json;
+
+    #[test]
+    fn test_logstash_parser_reads_logstash_format() {
+        let info = LogInfo::new("warn", "Disk almost full")
+            .with_meta("timestamp", "2024-04-01T12:30:00Z")
+            .with_meta("mount", "/var");
+        let line = logstash().transform(info).unwrap().formatted.unwrap();
+
+        let parsed = LogstashParser.parse(&line).unwrap();
+        assert_eq!(parsed.level, "warn");
+        assert_eq!(parsed.message, "Disk almost full");
+        assert_eq!(parsed.meta["mount"], json!("/var"));
+        assert_eq!(parsed.meta["timestamp"], json!("2024-04-01T12:30:00Z"));
+        assert!(parsed.timestamp.is_some());
+    }
+
+    #[test]
+    fn test_logstash_parser_rejects_plain_json() {
+        assert!(LogstashParser
+            .parse(r#"{"level":"info","message":"hi"}"#)
+            .is_err());
+    }
+}
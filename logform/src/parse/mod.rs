@@ -0,0 +1,106 @@
+//! Parsers that read formatted log lines back into [`LogInfo`].
+//!
+//! Each parser pairs with a built-in format, so a transport that stores text can
+//! be queried back whatever format wrote it:
+//!
+//! ```ignore
+//! use logform::parse::{self, Parser};
+//!
+//! let info = parse::simple().parse(r#"info: User logged in {"user_id":42}"#)?;
+//! assert_eq!(info.meta["user_id"], 42);
+//! ```
+//!
+//! Parsers strip ANSI color codes, keep a `timestamp` field in meta (as the
+//! `timestamp()` format writes it) and also set `LogInfo::timestamp` when the
+//! value is RFC 3339.
+
+mod csv;
+mod json;
+mod logfmt;
+mod logstash;
+mod regex;
+mod simple;
+
+pub use self::csv::CsvParser;
+pub use self::json::JsonParser;
+pub use self::logfmt::LogfmtParser;
+pub use self::logstash::LogstashParser;
+pub use self::regex::RegexParser;
+pub use self::simple::SimpleParser;
+
+use crate::LogInfo;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+pub trait Parser {
+    /// Parses one line, or explains why it couldn't.
+    fn parse(&self, line: &str) -> Result<LogInfo, String>;
+
+    /// Whether `record` is a whole entry. When it isn't, readers append the
+    /// next line (after a `\n`) and ask again; formats that can write an
+    /// entry across lines, like `csv()` with quoted line breaks, override
+    /// this. Defaults to `true`.
+    fn is_complete(&self, record: &str) -> bool {
+        let _ = record;
+        true
+    }
+
+    /// Whether the first record of a file is a header line rather than an
+    /// entry, such as `csv().header()`. Defaults to `false`.
+    fn is_header(&self, record: &str) -> bool {
+        let _ = record;
+        false
+    }
+}
+
+impl<F> Parser for F
+where
+    F: Fn(&str) -> Result<LogInfo, String>,
+{
+    fn parse(&self, line: &str) -> Result<LogInfo, String> {
+        self(line)
+    }
+}
+
+/// Sets the first-class timestamp from an RFC 3339 `timestamp` meta value.
+fn with_meta_timestamp(mut info: LogInfo) -> LogInfo {
+    if info.timestamp.is_none() {
+        info.timestamp = info
+            .meta
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|ts| ts.with_timezone(&Utc));
+    }
+    info
+}
+
+/// Parses a line written by `csv()` with these columns; see [`CsvParser`].
+pub fn csv(columns: &[&str]) -> CsvParser {
+    CsvParser::new(columns)
+}
+
+/// Parses a line written by `json()`.
+pub fn json() -> JsonParser {
+    JsonParser
+}
+
+/// Parses a line written by `logfmt()`.
+pub fn logfmt() -> LogfmtParser {
+    LogfmtParser::new()
+}
+
+/// Parses a line written by `logstash()`.
+pub fn logstash() -> LogstashParser {
+    LogstashParser
+}
+
+/// Parses lines with a regex whose named captures become fields; see [`RegexParser`].
+pub fn regex(pattern: &str) -> Result<RegexParser, ::regex::Error> {
+    RegexParser::new(pattern)
+}
+
+/// Parses a line written by `simple()`.
+pub fn simple() -> SimpleParser {
+    SimpleParser
+}
@@ -0,0 +1,125 @@
+use super::{with_meta_timestamp, Parser};
+use crate::{formats::uncolorize::strip_colors, LogInfo, Meta};
+use regex::Regex;
+use serde_json::{Map, Value};
+
+/// Reads lines with a user-supplied regex, typically to match a `printf` template.
+///
+/// Named captures become fields: `level` (required), `message`, and `meta` (a
+/// JSON object merged into meta). Any other named capture that matched becomes
+/// a string meta value under its name, e.g. `timestamp` or `label`.
+///
+/// ```ignore
+/// let parser = parse::regex(r"^(?P<timestamp>\S+) \[(?P<label>\w+)\] (?P<level>\w+): (?P<message>.*)$")?;
+/// ```
+#[derive(Clone)]
+pub struct RegexParser {
+    regex: Regex,
+}
+
+impl RegexParser {
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            regex: Regex::new(pattern)?,
+        })
+    }
+}
+
+impl From<Regex> for RegexParser {
+    fn from(regex: Regex) -> Self {
+        Self { regex }
+    }
+}
+
+impl Parser for RegexParser {
+    fn parse(&self, line: &str) -> Result<LogInfo, String> {
+        let line = strip_colors(line);
+        let captures = self
+            .regex
+            .captures(&line)
+            .ok_or("Line does not match the pattern")?;
+
+        let level = captures
+            .name("level")
+            .ok_or("Pattern has no 'level' capture")?
+            .as_str()
+            .to_string();
+        let message = captures
+            .name("message")
+            .map(|m| m.as_str())
+            .unwrap_or_default();
+
+        let mut meta = Meta::new();
+        for name in self.regex.capture_names().flatten() {
+            let Some(capture) = captures.name(name) else {
+                continue;
+            };
+            match name {
+                "level" | "message" => {}
+                "meta" => {
+                    if !capture.as_str().trim().is_empty() {
+                        let object: Map<String, Value> = serde_json::from_str(capture.as_str())
+                            .map_err(|e| format!("Invalid 'meta' capture: {}", e))?;
+                        meta.extend(object);
+                    }
+                }
+                _ => {
+                    meta.insert(
+                        name.to_string(),
+                        Value::String(capture.as_str().to_string()),
+                    );
+                }
+            }
+        }
+
+        Ok(with_meta_timestamp(LogInfo::from_parts(
+            level, message, meta,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{printf, Format};
+    use serde_json::json;
+
+    #[test]
+    fn test_regex_parser_reads_printf_output() {
+        let format = printf(|info| {
+            format!(
+                "{} [api] {}: {} {}",
+                info.meta["timestamp"].as_str().unwrap(),
+                info.level,
+                info.message,
+                json!({"user_id": info.meta["user_id"]})
+            )
+        });
+        let info = LogInfo::new("error", "Request failed")
+            .with_meta("timestamp", "2024-04-01T12:30:00Z")
+            .with_meta("user_id", 7);
+        let line = format.transform(info).unwrap().formatted.unwrap();
+
+        let parser = RegexParser::new(
+            r"^(?P<timestamp>\S+) \[(?P<label>\w+)\] (?P<level>\w+): (?P<message>.*?)(?: (?P<meta>\{.*\}))?$",
+        )
+        .unwrap();
+        let parsed = parser.parse(&line).unwrap();
+
+        assert_eq!(parsed.level, "error");
+        assert_eq!(parsed.message, "Request failed");
+        assert_eq!(parsed.meta["label"], json!("api"));
+        assert_eq!(parsed.meta["user_id"], json!(7));
+        assert!(parsed.timestamp.is_some());
+    }
+
+    #[test]
+    fn test_regex_parser_requires_level_and_match() {
+        let parser = RegexParser::new(r"^(?P<message>.*)$").unwrap();
+        assert!(parser.parse("anything").is_err());
+
+        let parser = RegexParser::new(r"^(?P<level>\w+) (?P<message>.*)$").unwrap();
+        assert!(parser.parse("").is_err());
+        assert_eq!(parser.parse("info hello").unwrap().message, "hello");
+    }
+}
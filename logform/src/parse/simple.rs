@@ -0,0 +1,89 @@
+use super::{with_meta_timestamp, Parser};
+use crate::{formats::uncolorize::strip_colors, LogInfo, Meta};
+use serde_json::{Map, Value};
+
+/// Reads `level: message {"key":"value"}` lines as written by `simple()`.
+///
+/// The trailing meta object is the earliest ` {"` from which the rest of the line
+/// parses as a JSON object, so braces inside the message are left alone. Lines
+/// written by `cli()` are read too, with colors stripped.
+pub struct SimpleParser;
+
+impl SimpleParser {
+    fn split_meta(rest: &str) -> (&str, Option<Map<String, Value>>) {
+        for (index, _) in rest.match_indices(" {\"") {
+            if let Ok(meta) = serde_json::from_str::<Map<String, Value>>(&rest[index + 1..]) {
+                return (&rest[..index], Some(meta));
+            }
+        }
+        (rest, None)
+    }
+}
+
+impl Parser for SimpleParser {
+    fn parse(&self, line: &str) -> Result<LogInfo, String> {
+        let line = strip_colors(line.trim_end());
+        let (level, rest) = line.split_once(':').ok_or("Expected a 'level:' prefix")?;
+
+        let level = match level.split_once(' ') {
+            None if !level.is_empty() => level,
+            // cli() output printed via Display repeats the level
+            Some((first, second)) if first == second => first,
+            _ => return Err(format!("Invalid level '{}'", level)),
+        };
+
+        let (message, meta) = Self::split_meta(rest);
+        let meta: Meta = meta.unwrap_or_default().into();
+
+        Ok(with_meta_timestamp(LogInfo::from_parts(
+            level.to_string(),
+            message.trim_start(),
+            meta,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{simple, Format};
+    use serde_json::json;
+
+    #[test]
+    fn test_simple_parser_reads_simple_format() {
+        let info = LogInfo::new("info", "Payload {not json} received")
+            .with_meta("user_id", 42)
+            .with_meta("nested", json!({"a": {"b": [1, 2]}}));
+        let line = simple().transform(info).unwrap().formatted.unwrap();
+
+        let parsed = SimpleParser.parse(&line).unwrap();
+        assert_eq!(parsed.level, "info");
+        assert_eq!(parsed.message, "Payload {not json} received");
+        assert_eq!(parsed.meta["user_id"], json!(42));
+        assert_eq!(parsed.meta["nested"], json!({"a": {"b": [1, 2]}}));
+    }
+
+    #[test]
+    fn test_simple_parser_without_meta() {
+        let parsed = SimpleParser.parse("error: Something broke: badly").unwrap();
+        assert_eq!(parsed.level, "error");
+        assert_eq!(parsed.message, "Something broke: badly");
+        assert!(parsed.meta.is_empty());
+    }
+
+    #[test]
+    fn test_simple_parser_reads_colored_cli_output() {
+        // As written by `cli().with_all(true)` with colors on
+        let line = "\u{1b}[33mwarn\u{1b}[0m:\u{1b}[33m    Low disk\u{1b}[0m {\"free_pct\":4}";
+
+        let parsed = SimpleParser.parse(line).unwrap();
+        assert_eq!(parsed.level, "warn");
+        assert_eq!(parsed.message, "Low disk");
+        assert_eq!(parsed.meta["free_pct"], json!(4));
+    }
+
+    #[test]
+    fn test_simple_parser_rejects_unprefixed_line() {
+        assert!(SimpleParser.parse("no level here").is_err());
+    }
+}
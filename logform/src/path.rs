@@ -0,0 +1,492 @@
+use crate::Meta;
+use serde_json::Value;
+use std::str::FromStr;
+
+/// A path into a JSON value or into `meta`: `user.name`, `items[0].price`,
+/// `user.*`, `items[*].id`. Shared by the formats that address meta fields and
+/// by `winston_transport`'s query DSL.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldPath {
+    segments: Vec<PathSegment>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathSegment {
+    Field(String),
+    Wildcard,
+    ArrayIndex(usize),
+    ArrayWildcard,
+}
+
+impl FieldPath {
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        for part in path.split('.') {
+            let (field, mut rest) = match part.find('[') {
+                Some(index) => part.split_at(index),
+                None => (part, ""),
+            };
+            match field {
+                "" if rest.is_empty() => {
+                    return Err(format!("Empty segment in path '{}'", path));
+                }
+                "" => {}
+                "*" => segments.push(PathSegment::Wildcard),
+                field => segments.push(PathSegment::Field(field.to_string())),
+            }
+            while !rest.is_empty() {
+                let end = rest
+                    .find(']')
+                    .filter(|_| rest.starts_with('['))
+                    .ok_or_else(|| format!("Unclosed '[' in path '{}'", path))?;
+                segments.push(match &rest[1..end] {
+                    "*" => PathSegment::ArrayWildcard,
+                    index => PathSegment::ArrayIndex(
+                        index
+                            .parse()
+                            .map_err(|_| format!("Invalid index '{}' in path '{}'", index, path))?,
+                    ),
+                });
+                rest = &rest[end + 1..];
+            }
+        }
+        if segments.is_empty() {
+            return Err("Empty path".to_string());
+        }
+        Ok(Self { segments })
+    }
+
+    /// The top-level meta key the path starts at, unless it starts with a wildcard.
+    pub(crate) fn root(&self) -> Option<&str> {
+        match self.segments.first() {
+            Some(PathSegment::Field(field)) => Some(field),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn has_wildcards(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|segment| matches!(segment, PathSegment::Wildcard | PathSegment::ArrayWildcard))
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// The value the path matches in `value`: the single match itself, or an
+    /// array of all matches when a wildcard matched several.
+    pub fn extract(&self, value: &Value) -> Option<Value> {
+        match self.extract_refs(value).as_slice() {
+            [] => None,
+            [single] => Some((*single).clone()),
+            values => Some(Value::Array(values.iter().map(|&v| v.clone()).collect())),
+        }
+    }
+
+    /// Every value the path matches in `value`.
+    pub fn extract_refs<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        Self::walk(vec![value], &self.segments)
+    }
+
+    /// Every value the path matches, in meta order.
+    pub(crate) fn resolve<'a>(&self, meta: &'a Meta) -> Vec<&'a Value> {
+        let (first, rest) = self.segments.split_first().expect("paths are non-empty");
+        let current: Vec<&Value> = match first {
+            PathSegment::Field(field) => meta.get(field).into_iter().collect(),
+            PathSegment::Wildcard => meta.values().collect(),
+            PathSegment::ArrayIndex(_) | PathSegment::ArrayWildcard => Vec::new(),
+        };
+        Self::walk(current, rest)
+    }
+
+    fn walk<'a>(mut current: Vec<&'a Value>, segments: &[PathSegment]) -> Vec<&'a Value> {
+        for segment in segments {
+            current = current
+                .into_iter()
+                .flat_map(|value| -> Vec<&Value> {
+                    match (segment, value) {
+                        (PathSegment::Field(field), Value::Object(map)) => {
+                            map.get(field).into_iter().collect()
+                        }
+                        (PathSegment::Wildcard, Value::Object(map)) => map.values().collect(),
+                        (PathSegment::ArrayIndex(index), Value::Array(array)) => {
+                            array.get(*index).into_iter().collect()
+                        }
+                        (PathSegment::ArrayWildcard, Value::Array(array)) => array.iter().collect(),
+                        _ => Vec::new(),
+                    }
+                })
+                .collect();
+        }
+        current
+    }
+
+    /// A path of plain fields from a dotted key, e.g. a flattened `http.status`.
+    pub(crate) fn dotted(key: &str) -> Option<Self> {
+        let segments: Vec<PathSegment> = key
+            .split('.')
+            .map(|field| PathSegment::Field(field.to_string()))
+            .collect();
+        segments
+            .iter()
+            .all(|segment| !matches!(segment, PathSegment::Field(field) if field.is_empty()))
+            .then_some(Self { segments })
+    }
+
+    /// This path extended by one field.
+    pub(crate) fn child(&self, field: &str) -> Self {
+        let mut segments = self.segments.clone();
+        segments.push(PathSegment::Field(field.to_string()));
+        Self { segments }
+    }
+
+    /// The path without its last segment, and that segment's field name; `None`
+    /// if the path doesn't end in a field. The parent is `None` at the top level.
+    pub(crate) fn split_last(&self) -> Option<(Option<Self>, &str)> {
+        match self.segments.split_last() {
+            Some((PathSegment::Field(field), [])) => Some((None, field)),
+            Some((PathSegment::Field(field), parent)) => Some((
+                Some(Self {
+                    segments: parent.to_vec(),
+                }),
+                field,
+            )),
+            _ => None,
+        }
+    }
+
+    /// The value at a path without wildcards.
+    pub(crate) fn get_mut<'a>(&self, meta: &'a mut Meta) -> Option<&'a mut Value> {
+        let (first, rest) = self.segments.split_first().expect("paths are non-empty");
+        let PathSegment::Field(field) = first else {
+            return None;
+        };
+        let mut current = meta.get_mut(field)?;
+        for segment in rest {
+            current = match (segment, current) {
+                (PathSegment::Field(field), Value::Object(map)) => map.get_mut(field)?,
+                (PathSegment::ArrayIndex(index), Value::Array(array)) => array.get_mut(*index)?,
+                _ => return None,
+            };
+        }
+        Some(current)
+    }
+
+    /// Removes and returns the value at the path (the last match, with wildcards).
+    pub(crate) fn take(&self, meta: &mut Meta) -> Option<Value> {
+        let mut taken = None;
+        self.visit_mut(meta, &mut |value| {
+            taken = Some(value.take());
+            false
+        });
+        taken
+    }
+
+    /// Sets the value at a path without wildcards, creating missing objects on
+    /// the way. Hands the value back, leaving meta as it was, if a non-object
+    /// value or a missing array item is in the way.
+    pub(crate) fn insert(&self, meta: &mut Meta, value: Value) -> Result<(), Value> {
+        let (first, rest) = self.segments.split_first().expect("paths are non-empty");
+        let PathSegment::Field(field) = first else {
+            return Err(value);
+        };
+        if rest.is_empty() {
+            meta.insert(field.clone(), value);
+            return Ok(());
+        }
+        if !meta.contains_key(field) {
+            if !Self::can_insert(rest) {
+                return Err(value);
+            }
+            meta.insert(field.clone(), Value::Object(Default::default()));
+        }
+        let target = meta.get_mut(field).expect("inserted above");
+        Self::insert_value(target, rest, value)
+    }
+
+    fn can_insert(segments: &[PathSegment]) -> bool {
+        segments
+            .iter()
+            .all(|segment| matches!(segment, PathSegment::Field(_)))
+    }
+
+    fn insert_value(
+        target: &mut Value,
+        segments: &[PathSegment],
+        value: Value,
+    ) -> Result<(), Value> {
+        let Some((first, rest)) = segments.split_first() else {
+            *target = value;
+            return Ok(());
+        };
+        match (first, target) {
+            (PathSegment::Field(field), Value::Object(map)) => {
+                if !map.contains_key(field) {
+                    if rest.is_empty() {
+                        map.insert(field.clone(), value);
+                        return Ok(());
+                    }
+                    if !Self::can_insert(rest) {
+                        return Err(value);
+                    }
+                    map.insert(field.clone(), Value::Object(Default::default()));
+                }
+                Self::insert_value(map.get_mut(field).expect("inserted above"), rest, value)
+            }
+            (PathSegment::ArrayIndex(index), Value::Array(array)) => match array.get_mut(*index) {
+                Some(item) => Self::insert_value(item, rest, value),
+                None => Err(value),
+            },
+            _ => Err(value),
+        }
+    }
+
+    /// Calls `visit` on every value the path matches; a value is removed from
+    /// its parent object or array when `visit` returns `false`.
+    pub(crate) fn visit_mut(&self, meta: &mut Meta, visit: &mut dyn FnMut(&mut Value) -> bool) {
+        let (first, rest) = self.segments.split_first().expect("paths are non-empty");
+        match first {
+            PathSegment::Field(field) if rest.is_empty() => {
+                if let Some(value) = meta.get_mut(field) {
+                    if !visit(value) {
+                        meta.remove(field);
+                    }
+                }
+            }
+            PathSegment::Field(field) => {
+                if let Some(value) = meta.get_mut(field) {
+                    Self::visit_value(value, rest, visit);
+                }
+            }
+            PathSegment::Wildcard if rest.is_empty() => meta.retain(|_, value| visit(value)),
+            PathSegment::Wildcard => {
+                for value in meta.values_mut() {
+                    Self::visit_value(value, rest, visit);
+                }
+            }
+            PathSegment::ArrayIndex(_) | PathSegment::ArrayWildcard => {}
+        }
+    }
+
+    fn visit_value(
+        value: &mut Value,
+        segments: &[PathSegment],
+        visit: &mut dyn FnMut(&mut Value) -> bool,
+    ) {
+        let Some((first, rest)) = segments.split_first() else {
+            return;
+        };
+        match (first, value) {
+            (PathSegment::Field(field), Value::Object(map)) if rest.is_empty() => {
+                if let Some(value) = map.get_mut(field) {
+                    if !visit(value) {
+                        map.remove(field);
+                    }
+                }
+            }
+            (PathSegment::Field(field), Value::Object(map)) => {
+                if let Some(value) = map.get_mut(field) {
+                    Self::visit_value(value, rest, visit);
+                }
+            }
+            (PathSegment::Wildcard, Value::Object(map)) if rest.is_empty() => {
+                map.retain(|_, value| visit(value))
+            }
+            (PathSegment::Wildcard, Value::Object(map)) => {
+                for value in map.values_mut() {
+                    Self::visit_value(value, rest, visit);
+                }
+            }
+            (PathSegment::ArrayIndex(index), Value::Array(array)) if rest.is_empty() => {
+                if let Some(value) = array.get_mut(*index) {
+                    if !visit(value) {
+                        array.remove(*index);
+                    }
+                }
+            }
+            (PathSegment::ArrayIndex(index), Value::Array(array)) => {
+                if let Some(value) = array.get_mut(*index) {
+                    Self::visit_value(value, rest, visit);
+                }
+            }
+            (PathSegment::ArrayWildcard, Value::Array(array)) if rest.is_empty() => {
+                array.retain_mut(|value| visit(value))
+            }
+            (PathSegment::ArrayWildcard, Value::Array(array)) => {
+                for value in array.iter_mut() {
+                    Self::visit_value(value, rest, visit);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl FromStr for FieldPath {
+    type Err = String;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Self::parse(path)
+    }
+}
+
+impl From<&str> for FieldPath {
+    fn from(s: &str) -> Self {
+        s.parse().expect("Invalid field path")
+    }
+}
+
+impl From<String> for FieldPath {
+    fn from(s: String) -> Self {
+        FieldPath::from(s.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_field_path_parse_and_resolve() {
+        let meta: Meta = json!({
+            "user": {"name": "Alice", "roles": ["admin", "dev"]},
+            "items": [{"price": 10}, {"price": 20}]
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+        .into();
+
+        let cases = [
+            ("user.name", vec![json!("Alice")]),
+            ("user.roles[1]", vec![json!("dev")]),
+            ("items[*].price", vec![json!(10), json!(20)]),
+            ("user.*", vec![json!("Alice"), json!(["admin", "dev"])]),
+            ("user.missing", vec![]),
+        ];
+        for (path, expected) in cases {
+            let path = FieldPath::parse(path).unwrap();
+            let resolved: Vec<Value> = path.resolve(&meta).into_iter().cloned().collect();
+            assert_eq!(resolved, expected);
+        }
+
+        assert_eq!(FieldPath::parse("user.name").unwrap().root(), Some("user"));
+        assert!(FieldPath::parse("items[*].price").unwrap().has_wildcards());
+        assert!(FieldPath::parse("").is_err());
+        assert!(FieldPath::parse("a..b").is_err());
+        assert!(FieldPath::parse("items[x]").is_err());
+        assert!(FieldPath::parse("items[0").is_err());
+    }
+
+    #[test]
+    fn test_field_path_extract() {
+        let value = json!({
+            "user": {"name": "Alice", "address": {"city": "NY"}},
+            "items": [{"price": 10}, {"price": 20}],
+            "users": [{"name": "Alice", "age": 30}, {"name": "Bob", "age": 25}]
+        });
+
+        let cases = [
+            ("user.name", Some(json!("Alice"))),
+            ("user.address.city", Some(json!("NY"))),
+            ("items[1].price", Some(json!(20))),
+            ("items[*].price", Some(json!([10, 20]))),
+            ("user.address.street", None),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(FieldPath::from(path).extract(&value), expected, "{path}");
+        }
+
+        let mut matched: Vec<String> = FieldPath::from("users[*].*")
+            .extract_refs(&value)
+            .into_iter()
+            .map(Value::to_string)
+            .collect();
+        matched.sort();
+        assert_eq!(matched, ["\"Alice\"", "\"Bob\"", "25", "30"]);
+        assert!("items[x]".parse::<FieldPath>().is_err());
+        assert_eq!(
+            FieldPath::from("items[*]").segments(),
+            [
+                PathSegment::Field("items".to_string()),
+                PathSegment::ArrayWildcard
+            ]
+        );
+    }
+
+    #[test]
+    fn test_field_path_visit_mut() {
+        let mut meta: Meta = json!({
+            "headers": {"a": {"authorization": "x", "accept": "*/*"}, "b": {"authorization": "y"}},
+            "items": [1, 2, 3]
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+        .into();
+
+        FieldPath::parse("headers.*.authorization")
+            .unwrap()
+            .visit_mut(&mut meta, &mut |value| {
+                *value = json!("***");
+                true
+            });
+        FieldPath::parse("items[*]")
+            .unwrap()
+            .visit_mut(&mut meta, &mut |value| value != &json!(2));
+
+        assert_eq!(
+            meta["headers"],
+            json!({"a": {"authorization": "***", "accept": "*/*"}, "b": {"authorization": "***"}})
+        );
+        assert_eq!(meta["items"], json!([1, 3]));
+
+        FieldPath::parse("headers")
+            .unwrap()
+            .visit_mut(&mut meta, &mut |_| false);
+        assert!(!meta.contains_key("headers"));
+    }
+
+    #[test]
+    fn test_field_path_take_and_insert() {
+        let mut meta: Meta = json!({"user_id": 7, "http": {"status": 200}, "tags": ["a"]})
+            .as_object()
+            .unwrap()
+            .clone()
+            .into();
+        let path = |path| FieldPath::parse(path).unwrap();
+
+        let value = path("user_id").take(&mut meta).unwrap();
+        assert_eq!(path("user.id").insert(&mut meta, value), Ok(()));
+        assert_eq!(
+            path("http.request.method").insert(&mut meta, json!("GET")),
+            Ok(())
+        );
+        // Scalars and missing array items are never overwritten on the way
+        assert_eq!(
+            path("http.status.code").insert(&mut meta, json!(1)),
+            Err(json!(1))
+        );
+        assert!(path("tags[3]").insert(&mut meta, json!("b")).is_err());
+        assert!(path("missing[0]").insert(&mut meta, json!(1)).is_err());
+
+        assert!(!meta.contains_key("user_id"));
+        assert!(!meta.contains_key("missing"));
+        assert_eq!(meta["user"], json!({"id": 7}));
+        assert_eq!(
+            meta["http"],
+            json!({"status": 200, "request": {"method": "GET"}})
+        );
+
+        *path("http.status").get_mut(&mut meta).unwrap() = json!(204);
+        assert_eq!(meta["http"]["status"], json!(204));
+
+        let request = path("http.request");
+        let (parent, last) = request.split_last().unwrap();
+        assert_eq!((parent, last), (Some(path("http")), "request"));
+        assert_eq!(path("a.b").child("c"), path("a.b.c"));
+        assert!(FieldPath::dotted("a..b").is_none());
+    }
+}
@@ -0,0 +1,429 @@
+use super::{parse_flag, Args, BoxedFormat};
+use crate::{
+    align, cli, colorize, csv, dev, ecs, enrich, errors, fields,
+    formats::{
+        cli::CliFormat, colorize::Colorizer, csv::Column, dev::DevFormat, enrich::EnrichFormat,
+        errors::ErrorsFormat, gelf::GelfFormat, json::JsonFormat, label::LabelFormat,
+        limit::LimitFormat, logfmt::LogfmtFormat, pad_levels::Padder, pretty_print::PrettyPrinter,
+        redact::RedactFormat, syslog::SyslogFormat, timestamp::Timestamp, uncolorize::Uncolorize,
+    },
+    gelf, json, label, limit, logfmt, logstash, metadata, ms, otel, pad_levels, passthrough,
+    pretty_print, redact, simple, splat, syslog_3164, syslog_5424, template, timestamp, uncolorize,
+    EpochUnit, Redaction,
+};
+use serde_json::Value;
+
+type Constructor = fn(&mut Args) -> Result<BoxedFormat, String>;
+
+pub(super) const CONSTRUCTORS: &[(&str, Constructor)] = &[
+    ("align", |_| Ok(Box::new(align()))),
+    ("cli", cli_spec),
+    ("colorize", colorize_spec),
+    ("csv", csv_spec),
+    ("dev", dev_spec),
+    ("ecs", ecs_spec),
+    ("enrich", enrich_spec),
+    ("errors", errors_spec),
+    ("fields", fields_spec),
+    ("gelf", gelf_spec),
+    ("json", json_spec),
+    ("label", label_spec),
+    ("limit", limit_spec),
+    ("logfmt", logfmt_spec),
+    ("logstash", |_| Ok(Box::new(logstash()))),
+    ("metadata", metadata_spec),
+    ("ms", |_| Ok(Box::new(ms()))),
+    ("otel", otel_spec),
+    ("pad_levels", pad_levels_spec),
+    ("passthrough", |_| Ok(Box::new(passthrough()))),
+    ("pretty_print", pretty_print_spec),
+    ("redact", redact_spec),
+    ("simple", |_| Ok(Box::new(simple()))),
+    ("splat", |_| Ok(Box::new(splat()))),
+    ("syslog_3164", |args| syslog_spec(args, syslog_3164())),
+    ("syslog_5424", |args| syslog_spec(args, syslog_5424())),
+    ("template", template_spec),
+    ("timestamp", timestamp_spec),
+    ("uncolorize", uncolorize_spec),
+];
+
+/// Calls the builder `with` if the option was given.
+fn apply<F, T>(format: F, value: Option<T>, with: impl FnOnce(F, T) -> F) -> F {
+    match value {
+        Some(value) => with(format, value),
+        None => format,
+    }
+}
+
+fn strs(items: &[String]) -> Vec<&str> {
+    items.iter().map(String::as_str).collect()
+}
+
+/// A JSON value where the text is one (`3`, `true`, `{"a":1}`), else a string.
+fn json_value(text: &str) -> Value {
+    serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+}
+
+fn cli_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = cli();
+    format = apply(format, args.list("levels")?, CliFormat::with_levels);
+    format = apply(format, args.text("filler")?, CliFormat::with_filler);
+    format = apply(format, args.flag("all")?, CliFormat::with_all);
+    format = apply(format, args.flag("level")?, CliFormat::with_level);
+    format = apply(format, args.flag("message")?, CliFormat::with_message);
+    for (level, color) in args.prefixed("color")? {
+        format = format.with_color(&level, Value::String(color));
+    }
+    Ok(Box::new(format))
+}
+
+fn colorize_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = colorize();
+    format = apply(format, args.flag("enabled")?, Colorizer::with_enabled);
+    format = apply(format, args.flag("all")?, Colorizer::with_all);
+    format = apply(format, args.flag("level")?, Colorizer::with_level);
+    format = apply(format, args.flag("message")?, Colorizer::with_message);
+    for (level, color) in args.prefixed("color")? {
+        format = format.with_color(&level, Value::String(color));
+    }
+    for (key, color) in args.prefixed("meta_color")? {
+        format = format.with_meta_color(&key, Value::String(color));
+    }
+    Ok(Box::new(format))
+}
+
+fn csv_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = csv();
+    if let Some(columns) = args.list("columns")? {
+        for column in &columns {
+            Column::parse(column)?;
+        }
+        format = format.with_columns(&strs(&columns));
+    }
+    if let Some(delimiter) = args.text("delimiter")? {
+        let mut chars = delimiter.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => format = format.with_delimiter(c),
+            _ => {
+                return Err(format!(
+                    "'delimiter' must be one character, got '{}'",
+                    delimiter
+                ))
+            }
+        }
+    }
+    Ok(Box::new(format))
+}
+
+fn dev_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = dev();
+    format = apply(format, args.list("levels")?, DevFormat::with_levels);
+    format = apply(format, args.text("time_format")?, |f, v| {
+        f.with_time_format(&v)
+    });
+    format = apply(format, args.flag("colorize")?, DevFormat::with_colorize);
+    format = apply(format, args.flag("label")?, DevFormat::with_label);
+    format = apply(format, args.flag("target")?, DevFormat::with_target);
+    Ok(Box::new(format))
+}
+
+fn ecs_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = ecs();
+    format = apply(format, args.text("service_name")?, |f, v| {
+        f.with_service_name(&v)
+    });
+    format = apply(format, args.text("hostname")?, |f, v| f.with_hostname(&v));
+    Ok(Box::new(format))
+}
+
+fn enrich_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = enrich();
+    format = apply(format, args.text("service")?, |f, v| f.with_service(&v));
+    format = apply(format, args.text("version")?, |f, v| f.with_version(&v));
+    format = apply(format, args.text("env")?, |f, v| f.with_env(&v));
+    format = apply(format, args.text("hostname")?, |f, v| f.with_hostname(&v));
+    format = apply(format, args.flag("thread")?, EnrichFormat::with_thread);
+    Ok(Box::new(format))
+}
+
+fn errors_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = errors();
+    format = apply(format, args.flag("stack")?, ErrorsFormat::with_stack);
+    format = apply(format, args.flag("text")?, ErrorsFormat::with_text);
+    Ok(Box::new(format))
+}
+
+/// Operations apply in the order written: `rename=[from, to]`,
+/// `move=[path, .., into]`, `flatten=path`, `unflatten=true`, `drop=path` and
+/// `default=[path, value]`, each repeatable; `flatten` and `drop` also take
+/// lists of paths.
+fn fields_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = fields();
+    for (key, values) in args.remaining() {
+        format = match (key.as_str(), values.as_slice()) {
+            ("rename", [from, to]) => format.with_rename(from, to),
+            ("move", [paths @ .., into]) if !paths.is_empty() => {
+                format.with_move(&strs(paths), into)
+            }
+            ("flatten", paths) => paths.iter().fold(format, |f, path| f.with_flatten(path)),
+            ("drop", paths) => paths.iter().fold(format, |f, path| f.with_drop(path)),
+            ("unflatten", [flag]) => match parse_flag(flag) {
+                Some(true) => format.with_unflatten(),
+                Some(false) => format,
+                None => return Err(format!("'unflatten' must be true or false, got '{}'", flag)),
+            },
+            ("default", [path, value]) => format.with_default(path, json_value(value)),
+            ("rename", _) => return Err("'rename' takes [from, to]".to_string()),
+            ("move", _) => return Err("'move' takes [path, .., into]".to_string()),
+            ("unflatten", _) => return Err("'unflatten' takes true or false".to_string()),
+            ("default", _) => return Err("'default' takes [path, value]".to_string()),
+            _ => return Err(format!("unknown option '{}'", key)),
+        };
+    }
+    Ok(Box::new(format))
+}
+
+fn severities(args: &mut Args) -> Result<Vec<(String, u8)>, String> {
+    args.prefixed("level")?
+        .into_iter()
+        .map(|(level, severity)| match severity.parse() {
+            Ok(severity) => Ok((level, severity)),
+            Err(_) => Err(format!(
+                "'level.{}' must be a number from 0 to 255, got '{}'",
+                level, severity
+            )),
+        })
+        .collect()
+}
+
+fn gelf_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = gelf();
+    format = apply(format, args.text("host")?, |f, v| f.with_host(&v));
+    format = apply(
+        format,
+        args.number("default_level")?,
+        GelfFormat::with_default_level,
+    );
+    for (level, severity) in severities(args)? {
+        format = format.with_level(&level, severity);
+    }
+    Ok(Box::new(format))
+}
+
+fn json_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = json();
+    format = apply(format, args.text("level_key")?, |f, v| f.with_level_key(&v));
+    format = apply(format, args.text("message_key")?, |f, v| {
+        f.with_message_key(&v)
+    });
+    format = apply(format, args.text("meta_key")?, |f, v| f.with_meta_key(&v));
+    format = apply(
+        format,
+        args.flag("sorted_keys")?,
+        JsonFormat::with_sorted_keys,
+    );
+    format = apply(format, args.number("indent")?, JsonFormat::with_indent);
+    format = apply(format, args.flag("ascii")?, JsonFormat::with_ascii);
+    Ok(Box::new(format))
+}
+
+fn label_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = label();
+    format = apply(format, args.text_or_positional("label")?, |f, v| {
+        f.with_label(&v)
+    });
+    format = apply(format, args.flag("message")?, LabelFormat::with_message);
+    Ok(Box::new(format))
+}
+
+fn limit_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = limit();
+    format = apply(
+        format,
+        args.number("message_length")?,
+        LimitFormat::with_message_length,
+    );
+    format = apply(
+        format,
+        args.number("string_length")?,
+        LimitFormat::with_string_length,
+    );
+    format = apply(
+        format,
+        args.number("array_length")?,
+        LimitFormat::with_array_length,
+    );
+    format = apply(format, args.number("depth")?, LimitFormat::with_depth);
+    format = apply(
+        format,
+        args.number("max_bytes")?,
+        LimitFormat::with_max_bytes,
+    );
+    format = apply(format, args.text("ellipsis")?, |f, v| f.with_ellipsis(&v));
+    Ok(Box::new(format))
+}
+
+fn logfmt_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = logfmt();
+    format = apply(format, args.list("order")?, LogfmtFormat::with_order);
+    format = apply(format, args.text("message_key")?, |f, v| {
+        f.with_message_key(&v)
+    });
+    format = apply(format, args.flag("flatten")?, LogfmtFormat::with_flatten);
+    Ok(Box::new(format))
+}
+
+fn metadata_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = metadata();
+    format = apply(format, args.text("key")?, |f, v| f.with_key(&v));
+    format = apply(format, args.list("fill_except")?, |f, v| {
+        f.with_fill_except(strs(&v))
+    });
+    format = apply(format, args.list("fill_with")?, |f, v| {
+        f.with_fill_with(strs(&v))
+    });
+    Ok(Box::new(format))
+}
+
+fn otel_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = otel();
+    for (level, severity) in severities(args)? {
+        format = format.with_level(&level, severity);
+    }
+    Ok(Box::new(format))
+}
+
+fn pad_levels_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = pad_levels();
+    format = apply(format, args.list("levels")?, Padder::with_levels);
+    format = apply(format, args.text("filler")?, Padder::with_filler);
+    Ok(Box::new(format))
+}
+
+fn pretty_print_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let format = apply(
+        pretty_print(),
+        args.flag("colorize")?,
+        PrettyPrinter::with_colorize,
+    );
+    Ok(Box::new(format))
+}
+
+/// `remove`, `mask`, `mask:<text>`, `partial:<n>`, `hash` or `hash:<salt>`.
+fn redaction(text: &str) -> Result<Redaction, String> {
+    match text.split_once(':').unwrap_or((text, "")) {
+        ("remove", "") => Ok(Redaction::Remove),
+        ("mask", "") => Ok(Redaction::default()),
+        ("mask", mask) => Ok(Redaction::Mask(mask.to_string())),
+        ("partial", keep) => keep
+            .parse()
+            .map(Redaction::Partial)
+            .map_err(|_| format!("'partial' needs a number of characters, got '{}'", text)),
+        #[cfg(feature = "hash")]
+        ("hash", salt) => Ok(Redaction::Hash(salt.to_string())),
+        #[cfg(not(feature = "hash"))]
+        ("hash", _) => Err("the 'hash' redaction requires the `hash` feature".to_string()),
+        _ => Err(format!(
+            "unknown redaction '{}'; expected remove, mask[:text], partial:n or hash[:salt]",
+            text
+        )),
+    }
+}
+
+/// `path=password` or `path=[password, partial:4]`; likewise `pattern`.
+fn redaction_target(key: &str, values: &[String]) -> Result<(String, Redaction), String> {
+    match values {
+        [target] => Ok((target.clone(), Redaction::default())),
+        [target, with] => Ok((target.clone(), redaction(with)?)),
+        _ => Err(format!("'{}' takes a value or [value, redaction]", key)),
+    }
+}
+
+/// `true` for the default mask, `false` to leave off, or a redaction.
+fn detector(args: &mut Args, key: &str) -> Result<Option<Redaction>, String> {
+    match args.text(key)? {
+        None => Ok(None),
+        Some(text) => match parse_flag(&text) {
+            Some(true) => Ok(Some(Redaction::default())),
+            Some(false) => Ok(None),
+            None => redaction(&text).map(Some),
+        },
+    }
+}
+
+fn redact_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = redact();
+    for values in args.repeated("path") {
+        let (path, with) = redaction_target("path", &values)?;
+        format = format.with_path(&path, with);
+    }
+    for values in args.repeated("pattern") {
+        let (pattern, with) = redaction_target("pattern", &values)?;
+        regex::Regex::new(&pattern).map_err(|e| format!("invalid pattern: {}", e))?;
+        format = format.with_pattern(&pattern, with);
+    }
+    format = apply(
+        format,
+        detector(args, "credit_cards")?,
+        RedactFormat::with_credit_cards,
+    );
+    format = apply(format, detector(args, "emails")?, RedactFormat::with_emails);
+    format = apply(
+        format,
+        detector(args, "bearer_tokens")?,
+        RedactFormat::with_bearer_tokens,
+    );
+    Ok(Box::new(format))
+}
+
+fn syslog_spec(args: &mut Args, mut format: SyslogFormat) -> Result<BoxedFormat, String> {
+    if let Some(facility) = args.text("facility")? {
+        format = format.try_with_facility(&facility)?;
+    }
+    format = apply(format, args.text("app_name")?, |f, v| f.with_app_name(&v));
+    format = apply(format, args.text("hostname")?, |f, v| f.with_hostname(&v));
+    format = apply(format, args.text("procid")?, |f, v| f.with_procid(&v));
+    format = apply(format, args.text("msgid")?, |f, v| f.with_msgid(&v));
+    format = apply(format, args.text("sd_id")?, |f, v| f.with_sd_id(&v));
+    Ok(Box::new(format))
+}
+
+fn template_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let text = args
+        .text_or_positional("template")?
+        .ok_or(r#"needs a template, e.g. template("{level}: {message}")"#)?;
+    Ok(Box::new(template(&text)?))
+}
+
+fn timestamp_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = timestamp();
+    format = apply(format, args.text_or_positional("format")?, |f, v| {
+        f.with_format(&v)
+    });
+    format = apply(format, args.text("alias")?, |f, v| f.with_alias(&v));
+    format = apply(format, args.text("timezone")?, |f, v| f.with_timezone(&v));
+    format = apply(format, args.number("precision")?, Timestamp::with_precision);
+    if let Some(unit) = args.text("epoch")? {
+        let unit = match unit.as_str() {
+            "s" => EpochUnit::Seconds,
+            "ms" => EpochUnit::Millis,
+            "us" => EpochUnit::Micros,
+            "ns" => EpochUnit::Nanos,
+            _ => return Err(format!("'epoch' must be s, ms, us or ns, got '{}'", unit)),
+        };
+        format = format.with_epoch(unit);
+    }
+    format = apply(
+        format,
+        args.flag("use_existing")?,
+        Timestamp::with_use_existing,
+    );
+    Ok(Box::new(format))
+}
+
+fn uncolorize_spec(args: &mut Args) -> Result<BoxedFormat, String> {
+    let mut format = uncolorize();
+    format = apply(format, args.flag("level")?, Uncolorize::with_level);
+    format = apply(format, args.flag("message")?, Uncolorize::with_message);
+    Ok(Box::new(format))
+}
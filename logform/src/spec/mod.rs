@@ -0,0 +1,566 @@
+//! Builds format pipelines from strings, so the format can be picked in a
+//! config file or an environment variable instead of in code:
+//!
+//! ```ignore
+//! let format = logform::from_spec(
+//!     "timestamp(format=%H:%M:%S) | label(api) | colorize(all=true) | simple",
+//! )?;
+//! ```
+//!
+//! A spec is a list of stages separated by `|`, each a format name with
+//! optional arguments in parentheses. Arguments are `key=value` options named
+//! after the format's builder methods without `with_` (`json(indent=2,
+//! sorted_keys=true)`), or a single positional value for the format's main
+//! option (`label(api)`, `template("{level}: {message}")`). Values are bare
+//! text up to the next `,` or unbalanced `)`, double-quoted strings with `\"`,
+//! `\\`, `\n`, `\r` and `\t` escapes, or `[a, b]` lists. Flags take
+//! `true`/`false`, `yes`/`no`, `on`/`off` or `1`/`0`.
+//!
+//! `${VAR}` and `${VAR:-default}` anywhere in the spec are replaced with
+//! environment variables before it is parsed, so ops can adjust one option,
+//! or add a whole stage, without rewriting the spec. [`from_env`] reads the
+//! whole spec from a variable.
+//!
+//! Every built-in format configured with plain values can be named; `printf`,
+//! `branch`, `filter` and `when` take closures or nested formats and are only
+//! available in code. Other crates add their own formats with [`register`].
+
+mod builtins;
+
+use crate::{Format, LogInfo};
+use std::{
+    collections::HashMap,
+    iter::Peekable,
+    str::{Chars, FromStr},
+    sync::{Arc, OnceLock, PoisonError, RwLock},
+};
+
+/// A format built from a spec.
+pub type BoxedFormat = Box<dyn Format<Input = LogInfo> + Send + Sync>;
+
+type Constructor = Arc<dyn Fn(&mut Args) -> Result<BoxedFormat, String> + Send + Sync>;
+
+fn registry() -> &'static RwLock<HashMap<String, Constructor>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, Constructor>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let constructors = builtins::CONSTRUCTORS
+            .iter()
+            .map(|(name, constructor)| (name.to_string(), Arc::new(*constructor) as Constructor))
+            .collect();
+        RwLock::new(constructors)
+    })
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn is_key_char(c: char) -> bool {
+    is_name_char(c) || c == '.' || c == '-'
+}
+
+/// Makes `name` available in specs, replacing any format already registered
+/// under it, built-ins included. Names are ASCII letters, digits and `_`.
+///
+/// ```ignore
+/// logform::spec::register("request_id", |args| {
+///     let header = args.text("header")?.unwrap_or_else(|| "x-request-id".into());
+///     Ok(Box::new(RequestIdFormat::new(&header)))
+/// });
+/// ```
+pub fn register<F>(name: &str, constructor: F)
+where
+    F: Fn(&mut Args) -> Result<BoxedFormat, String> + Send + Sync + 'static,
+{
+    if name.is_empty() || !name.chars().all(is_name_char) {
+        eprintln!("[logform::spec] Ignoring invalid format name '{}'", name);
+        return;
+    }
+    registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(name.to_string(), Arc::new(constructor));
+}
+
+/// Builds the pipeline described by `spec`, or explains what is wrong with it.
+pub fn from_spec(spec: &str) -> Result<BoxedFormat, String> {
+    let spec = expand_env(spec)?;
+    if spec.trim().is_empty() {
+        return Err("Empty format spec".to_string());
+    }
+    let mut stages = parse(&spec)?.into_iter().map(build);
+    let mut format = stages.next().expect("parse returns at least one stage")?;
+    for next in stages {
+        format = Box::new(format.chain(next?));
+    }
+    Ok(format)
+}
+
+/// Builds the pipeline in the environment variable `var`, or `default` when it
+/// is unset or empty: `from_env("LOG_FORMAT", "timestamp | json")`.
+pub fn from_env(var: &str, default: &str) -> Result<BoxedFormat, String> {
+    match std::env::var(var) {
+        Ok(spec) if !spec.trim().is_empty() => {
+            from_spec(&spec).map_err(|e| format!("{}: {}", var, e))
+        }
+        _ => from_spec(default),
+    }
+}
+
+fn build(mut args: Args) -> Result<BoxedFormat, String> {
+    // Cloned out so the lock isn't held while constructors run, which may
+    // themselves call `from_spec` or `register`
+    let constructor = registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&args.format)
+        .cloned();
+    let Some(constructor) = constructor else {
+        let mut known: Vec<String> = registry()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .keys()
+            .cloned()
+            .collect();
+        known.sort();
+        return Err(format!(
+            "Unknown format '{}'; known formats are {}",
+            args.format,
+            known.join(", ")
+        ));
+    };
+    let format = constructor(&mut args).map_err(|e| format!("{}: {}", args.format, e))?;
+    if let Some((key, _)) = args.options.first() {
+        return Err(format!("{}: unknown option '{}'", args.format, key));
+    }
+    if args.positional.is_some() {
+        return Err(format!("{}: takes no positional value", args.format));
+    }
+    Ok(format)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Arg {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl Arg {
+    fn into_text(self, key: &str) -> Result<String, String> {
+        match self {
+            Arg::Text(text) => Ok(text),
+            Arg::List(_) => Err(format!("'{}' takes a single value, not a list", key)),
+        }
+    }
+
+    fn into_list(self) -> Vec<String> {
+        match self {
+            Arg::Text(text) => vec![text],
+            Arg::List(items) => items,
+        }
+    }
+}
+
+pub(crate) fn parse_flag(text: &str) -> Option<bool> {
+    match text {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// The arguments of one stage, handed to its constructor.
+///
+/// Constructors take the options they understand; options left over once the
+/// constructor returns are reported as unknown, so a typo in a spec is an
+/// error rather than silently ignored.
+#[derive(Debug)]
+pub struct Args {
+    format: String,
+    positional: Option<Arg>,
+    options: Vec<(String, Arg)>,
+}
+
+impl Args {
+    /// The name the stage was written with.
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    fn take_all(&mut self, key: &str) -> Vec<Arg> {
+        let (taken, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.options)
+            .into_iter()
+            .partition(|(k, _)| k == key);
+        self.options = rest;
+        taken.into_iter().map(|(_, arg)| arg).collect()
+    }
+
+    fn take(&mut self, key: &str) -> Result<Option<Arg>, String> {
+        let mut taken = self.take_all(key);
+        match taken.len() {
+            0 | 1 => Ok(taken.pop()),
+            _ => Err(format!("'{}' is given more than once", key)),
+        }
+    }
+
+    /// The positional value, as in `label(api)`.
+    pub fn positional(&mut self) -> Result<Option<String>, String> {
+        self.positional
+            .take()
+            .map(|arg| arg.into_text("the positional value"))
+            .transpose()
+    }
+
+    /// The `key` option, or the positional value in its place.
+    pub fn text_or_positional(&mut self, key: &str) -> Result<Option<String>, String> {
+        match (self.text(key)?, self.positional()?) {
+            (Some(_), Some(_)) => Err(format!("'{}' is given both by name and by position", key)),
+            (named, positional) => Ok(named.or(positional)),
+        }
+    }
+
+    pub fn text(&mut self, key: &str) -> Result<Option<String>, String> {
+        self.take(key)?.map(|arg| arg.into_text(key)).transpose()
+    }
+
+    /// A list option; a single value is a list of one.
+    pub fn list(&mut self, key: &str) -> Result<Option<Vec<String>>, String> {
+        Ok(self.take(key)?.map(Arg::into_list))
+    }
+
+    pub fn flag(&mut self, key: &str) -> Result<Option<bool>, String> {
+        self.text(key)?
+            .map(|text| {
+                parse_flag(&text)
+                    .ok_or_else(|| format!("'{}' must be true or false, got '{}'", key, text))
+            })
+            .transpose()
+    }
+
+    pub fn number<T: FromStr>(&mut self, key: &str) -> Result<Option<T>, String> {
+        self.text(key)?
+            .map(|text| {
+                text.parse()
+                    .map_err(|_| format!("'{}' must be a number, got '{}'", key, text))
+            })
+            .transpose()
+    }
+
+    /// Every value of an option that may be repeated, as lists, in the order
+    /// given.
+    pub fn repeated(&mut self, key: &str) -> Vec<Vec<String>> {
+        self.take_all(key).into_iter().map(Arg::into_list).collect()
+    }
+
+    /// Options written `prefix.name=value`, as `(name, value)` pairs: e.g.
+    /// `color.info=green` for `prefixed("color")`.
+    pub fn prefixed(&mut self, prefix: &str) -> Result<Vec<(String, String)>, String> {
+        let prefix = format!("{}.", prefix);
+        let (taken, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.options)
+            .into_iter()
+            .partition(|(k, _)| k.starts_with(&prefix) && k.len() > prefix.len());
+        self.options = rest;
+        taken
+            .into_iter()
+            .map(|(key, arg)| {
+                let text = arg.into_text(&key)?;
+                Ok((key[prefix.len()..].to_string(), text))
+            })
+            .collect()
+    }
+
+    /// All remaining options in the order given, for formats whose options
+    /// apply in sequence.
+    pub fn remaining(&mut self) -> Vec<(String, Vec<String>)> {
+        std::mem::take(&mut self.options)
+            .into_iter()
+            .map(|(key, arg)| (key, arg.into_list()))
+            .collect()
+    }
+}
+
+/// Replaces `${VAR}` and `${VAR:-default}`; the default also applies when the
+/// variable is set but empty, as in a shell.
+fn expand_env(spec: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = spec;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find('}').ok_or("Unclosed '${' in format spec")?;
+        let (name, default) = match after[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&after[..end], None),
+        };
+        match (std::env::var(name), default) {
+            (Ok(value), Some(default)) if value.is_empty() => out.push_str(default),
+            (Ok(value), _) => out.push_str(&value),
+            (Err(_), Some(default)) => out.push_str(default),
+            (Err(_), None) => return Err(format!("Environment variable '{}' is not set", name)),
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn take_while(chars: &mut Peekable<Chars>, keep: impl Fn(char) -> bool) -> String {
+    let mut taken = String::new();
+    while let Some(c) = chars.next_if(|c| keep(*c)) {
+        taken.push(c);
+    }
+    taken
+}
+
+fn parse(spec: &str) -> Result<Vec<Args>, String> {
+    let mut chars = spec.chars().peekable();
+    let mut stages = Vec::new();
+    loop {
+        skip_whitespace(&mut chars);
+        let format = take_while(&mut chars, is_name_char);
+        if format.is_empty() {
+            return Err(match chars.peek() {
+                Some(c) => format!("Expected a format name, found '{}'", c),
+                None => "Expected a format name after the last '|'".to_string(),
+            });
+        }
+        let mut args = Args {
+            format,
+            positional: None,
+            options: Vec::new(),
+        };
+        skip_whitespace(&mut chars);
+        if chars.next_if_eq(&'(').is_some() {
+            parse_args(&mut chars, &mut args)?;
+            skip_whitespace(&mut chars);
+        }
+        let format = args.format.clone();
+        stages.push(args);
+        match chars.next() {
+            None => return Ok(stages),
+            Some('|') => {}
+            Some(c) => return Err(format!("Expected '|' after '{}', found '{}'", format, c)),
+        }
+    }
+}
+
+fn parse_args(chars: &mut Peekable<Chars>, args: &mut Args) -> Result<(), String> {
+    loop {
+        skip_whitespace(chars);
+        if chars.next_if_eq(&')').is_some() {
+            return Ok(());
+        }
+        let start = chars.clone();
+        let key = take_while(chars, is_key_char);
+        skip_whitespace(chars);
+        if !key.is_empty() && chars.next_if_eq(&'=').is_some() {
+            let value = parse_value(chars)?;
+            args.options.push((key, value));
+        } else {
+            *chars = start;
+            let value = parse_value(chars)?;
+            if args.positional.replace(value).is_some() {
+                return Err(format!("{}: more than one positional value", args.format));
+            }
+        }
+        skip_whitespace(chars);
+        match chars.next() {
+            Some(',') => {}
+            Some(')') => return Ok(()),
+            Some(c) => {
+                return Err(format!(
+                    "Expected ',' or ')' in '{}(..)', found '{}'",
+                    args.format, c
+                ))
+            }
+            None => return Err(format!("Unclosed '(' after '{}'", args.format)),
+        }
+    }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<Arg, String> {
+    skip_whitespace(chars);
+    if chars.next_if_eq(&'[').is_none() {
+        return parse_text(chars, ')').map(Arg::Text);
+    }
+    let mut items = Vec::new();
+    loop {
+        skip_whitespace(chars);
+        if chars.next_if_eq(&']').is_some() {
+            return Ok(Arg::List(items));
+        }
+        items.push(parse_text(chars, ']')?);
+        skip_whitespace(chars);
+        match chars.next() {
+            Some(',') => {}
+            Some(']') => return Ok(Arg::List(items)),
+            Some(c) => return Err(format!("Expected ',' or ']' in a list, found '{}'", c)),
+            None => return Err("Unclosed '[' in a list".to_string()),
+        }
+    }
+}
+
+/// A quoted string, or bare text up to a `,` or a `close` that doesn't match
+/// an opening bracket within the text, so `color(208)` needs no quotes.
+fn parse_text(chars: &mut Peekable<Chars>, close: char) -> Result<String, String> {
+    skip_whitespace(chars);
+    let mut text = String::new();
+    if chars.next_if_eq(&'"').is_some() {
+        loop {
+            match chars.next() {
+                Some('"') => return Ok(text),
+                Some('\\') => match chars.next() {
+                    Some('n') => text.push('\n'),
+                    Some('r') => text.push('\r'),
+                    Some('t') => text.push('\t'),
+                    Some(c @ ('"' | '\\')) => text.push(c),
+                    Some(c) => return Err(format!("Unknown escape '\\{}'", c)),
+                    None => return Err("Unterminated quoted value".to_string()),
+                },
+                Some(c) => text.push(c),
+                None => return Err("Unterminated quoted value".to_string()),
+            }
+        }
+    }
+
+    let mut depth = 0usize;
+    while let Some(&c) = chars.peek() {
+        match c {
+            ',' if depth == 0 => break,
+            c if c == close && depth == 0 => break,
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        text.push(c);
+        chars.next();
+    }
+    Ok(text.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::uncolorize::strip_colors;
+    use serde_json::json;
+
+    #[test]
+    fn test_spec_grammar() {
+        let stages = parse(
+            r#"label(api) | json( indent = 2, order=[a, "b,c" ], color.info=color(208), x="q\"\n" ) |simple"#,
+        )
+        .unwrap();
+        assert_eq!(stages.len(), 3);
+        assert_eq!(stages[0].positional, Some(Arg::Text("api".into())));
+        assert_eq!(
+            stages[1].options,
+            vec![
+                ("indent".to_string(), Arg::Text("2".into())),
+                (
+                    "order".to_string(),
+                    Arg::List(vec!["a".into(), "b,c".into()])
+                ),
+                ("color.info".to_string(), Arg::Text("color(208)".into())),
+                ("x".to_string(), Arg::Text("q\"\n".into())),
+            ]
+        );
+        assert_eq!(stages[2].format, "simple");
+
+        for bad in [
+            "",
+            "json |",
+            "json(indent=2",
+            "json(a, b)",
+            "json(x=\"open)",
+            "json(x=[a b])",
+            "json simple",
+        ] {
+            assert!(from_spec(bad).is_err(), "{:?} should not parse", bad);
+        }
+    }
+
+    #[test]
+    fn test_from_spec_pipeline() {
+        let format = from_spec(
+            "timestamp(format=%H:%M:%S) | label(label=api) | colorize(all=true, enabled=true) | simple",
+        )
+        .unwrap();
+        let result = format.transform(LogInfo::new("info", "Started")).unwrap();
+        let formatted = result.formatted.unwrap();
+        assert!(formatted.contains("\x1b["));
+        let plain = strip_colors(&formatted);
+        assert!(plain.starts_with("info: Started {"), "{}", plain);
+        assert!(plain.contains("\"label\":\"api\""));
+
+        let format = from_spec("fields(rename=[user_id, user.id], drop=[token]) | json").unwrap();
+        let info = LogInfo::new("info", "x")
+            .with_meta("user_id", 7)
+            .with_meta("token", "t");
+        let result = format.transform(info).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&result.formatted.unwrap()).unwrap(),
+            json!({"level": "info", "message": "x", "user": {"id": 7}})
+        );
+    }
+
+    #[test]
+    fn test_from_spec_errors() {
+        let error = |spec: &str| from_spec(spec).err().unwrap();
+        assert!(error("jsn").starts_with("Unknown format 'jsn'; known formats are align, "));
+        assert_eq!(error("json(indnt=2)"), "json: unknown option 'indnt'");
+        assert_eq!(error("json(2)"), "json: takes no positional value");
+        assert_eq!(
+            error("json(indent=two)"),
+            "json: 'indent' must be a number, got 'two'"
+        );
+        assert_eq!(
+            error("timestamp(epoch=days)"),
+            "timestamp: 'epoch' must be s, ms, us or ns, got 'days'"
+        );
+        assert!(error("template").starts_with("template: "));
+        assert!(error("template(\"{level\")").starts_with("template: "));
+    }
+
+    #[test]
+    fn test_env_expansion_and_register() {
+        std::env::set_var("LOGFORM_SPEC_TEST_INDENT", "4");
+        std::env::set_var("LOGFORM_SPEC_TEST_EMPTY", "");
+        assert_eq!(
+            expand_env(
+                "json(indent=${LOGFORM_SPEC_TEST_INDENT}) | ${LOGFORM_SPEC_TEST_EMPTY:-simple}"
+            )
+            .unwrap(),
+            "json(indent=4) | simple"
+        );
+        assert_eq!(
+            expand_env("${LOGFORM_SPEC_TEST_UNSET:-json}").unwrap(),
+            "json"
+        );
+        assert!(expand_env("${LOGFORM_SPEC_TEST_UNSET}").is_err());
+        assert!(expand_env("${LOGFORM_SPEC_TEST_UNSET").is_err());
+
+        std::env::set_var("LOGFORM_SPEC_TEST_FORMAT", "shout(suffix=!)");
+        struct Shout(String);
+        impl Format for Shout {
+            type Input = LogInfo;
+            fn transform(&self, info: LogInfo) -> Option<LogInfo> {
+                let message = format!("{}{}", info.message.to_uppercase(), self.0);
+                Some(LogInfo { message, ..info })
+            }
+        }
+        register("shout", |args| {
+            Ok(Box::new(Shout(args.text("suffix")?.unwrap_or_default())))
+        });
+        let format = from_env("LOGFORM_SPEC_TEST_FORMAT", "simple").unwrap();
+        let result = format.transform(LogInfo::new("info", "hi")).unwrap();
+        assert_eq!(result.message, "HI!");
+
+        let format = from_env("LOGFORM_SPEC_TEST_UNSET", "simple").unwrap();
+        let result = format.transform(LogInfo::new("info", "hi")).unwrap();
+        assert_eq!(result.formatted.as_deref(), Some("info: hi"));
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::OnceLock;
+
+/// Best-effort name of this machine, looked up once.
+///
+/// Uses `HOSTNAME`/`COMPUTERNAME` when set, then the kernel hostname on Linux,
+/// and falls back to `localhost`.
+pub fn hostname() -> &'static str {
+    static HOSTNAME: OnceLock<String> = OnceLock::new();
+    HOSTNAME.get_or_init(|| {
+        ["HOSTNAME", "COMPUTERNAME"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .chain(
+                ["/proc/sys/kernel/hostname", "/etc/hostname"]
+                    .iter()
+                    .filter_map(|path| std::fs::read_to_string(path).ok()),
+            )
+            .map(|name| name.trim().to_string())
+            .find(|name| !name.is_empty())
+            .unwrap_or_else(|| "localhost".to_string())
+    })
+}
+
+/// File name of the running executable without its extension, looked up once.
+pub fn exe_name() -> &'static str {
+    static EXE_NAME: OnceLock<String> = OnceLock::new();
+    EXE_NAME.get_or_init(|| {
+        std::env::current_exe()
+            .ok()
+            .and_then(|path| path.file_stem().map(|s| s.to_string_lossy().into_owned()))
+            .unwrap_or_default()
+    })
+}
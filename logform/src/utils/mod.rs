@@ -1 +1,2 @@
 pub mod format_json;
+pub mod host;
@@ -750,6 +750,128 @@ fn bench_winston_concurrent(target: OutputTarget) -> (f64, u64) {
     })
 }
 
+/// Winston with the default `json()` finalizer and structured fields on every
+/// call — the path that exercises `LogInfo` meta keys and ordering. With
+/// `stream`, `json()` is handed to the transport as a `Finalizer` instead, so
+/// entries serialize straight into the transport's buffer.
+fn winston_fields_logger(
+    target: OutputTarget,
+    capacity: usize,
+    file: &str,
+    stream: bool,
+) -> winston::Logger {
+    let builder = winston::Logger::builder()
+        .channel_capacity(capacity)
+        .backpressure_strategy(winston::BackpressureStrategy::Block);
+    let builder = if stream {
+        builder.format(winston::format::timestamp())
+    } else {
+        builder.format(winston::format::timestamp().chain(winston::format::json()))
+    };
+    fn transport<W: Write + Send + Sync + 'static>(
+        writer: W,
+        stream: bool,
+    ) -> winston::transports::WriterTransport<W, winston::format::LogInfo> {
+        let transport = winston::transports::WriterTransport::new(writer);
+        if stream {
+            transport.with_finalizer(winston::format::json())
+        } else {
+            transport
+        }
+    }
+    match target {
+        OutputTarget::Sink => builder
+            .transport(transport(std::io::sink(), stream))
+            .build(),
+        OutputTarget::Stdout => builder
+            .transport(transport(BufWriter::new(std::io::stdout()), stream))
+            .build(),
+        OutputTarget::File => {
+            let log_file = std::fs::File::create(file).unwrap();
+            builder
+                .transport(transport(BufWriter::new(log_file), stream))
+                .build()
+        }
+    }
+}
+
+macro_rules! winston_fields_log {
+    ($logger:expr) => {
+        winston::log!(
+            $logger,
+            info,
+            MESSAGE,
+            target = "logmark",
+            user_id = 42,
+            route = "/api/orders",
+            latency_ms = 12.5
+        )
+    };
+}
+
+fn bench_winston_fields(target: OutputTarget, stream: bool) -> BenchmarkResult {
+    let name = if stream {
+        "winston_stream"
+    } else {
+        "winston_fields"
+    };
+    let init_start = Instant::now();
+    let logger = winston_fields_logger(target, 50_000, &format!("logs/{}.log", name), stream);
+    let startup_secs = init_start.elapsed().as_secs_f64();
+
+    let mut samples = vec![0u64; LATENCY_ITERATIONS];
+    for slot in samples.iter_mut() {
+        let t = Instant::now();
+        winston_fields_log!(logger);
+        *slot = t.elapsed().as_nanos() as u64;
+    }
+    samples.sort_unstable();
+    let (p50, p99, p999, max) = latency_percentiles(&samples);
+
+    let before = jemalloc_allocated();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        winston_fields_log!(logger);
+    }
+    let drain_start = Instant::now();
+    drop(logger);
+    let drain_secs = drain_start.elapsed().as_secs_f64();
+    let elapsed = start.elapsed();
+    let after = jemalloc_allocated();
+
+    BenchmarkResult {
+        name: name.to_string(),
+        target,
+        elapsed: elapsed.as_secs_f64(),
+        ops: ITERATIONS as f64 / elapsed.as_secs_f64(),
+        memory_usage: after.saturating_sub(before) as f64 / (1024.0 * 1024.0),
+        startup_secs,
+        drain_secs,
+        p50_ns: p50,
+        p99_ns: p99,
+        p999_ns: p999,
+        max_ns: max,
+    }
+}
+
+fn bench_winston_fields_concurrent(target: OutputTarget, stream: bool) -> (f64, u64) {
+    let name = if stream {
+        "winston_stream"
+    } else {
+        "winston_fields"
+    };
+    let logger = Arc::new(winston_fields_logger(
+        target,
+        200_000,
+        &format!("logs/{}_conc.log", name),
+        stream,
+    ));
+    run_concurrent(move || {
+        let logger = Arc::clone(&logger);
+        move || winston_fields_log!(*logger)
+    })
+}
+
 fn bench_slog_async_saturate(target: OutputTarget) -> (f64, u64) {
     macro_rules! async_drain {
         ($w:expr) => {
@@ -865,6 +987,8 @@ fn run_concurrent_benchmark(benchmark_name: &str, target_name: &str) {
         "tracing" => bench_tracing_concurrent(target),
         "tracing_async" => bench_tracing_async_concurrent(target),
         "winston" => bench_winston_concurrent(target),
+        "winston_fields" => bench_winston_fields_concurrent(target, false),
+        "winston_stream" => bench_winston_fields_concurrent(target, true),
         _ => panic!("Unknown benchmark: {}", benchmark_name),
     };
     println!(
@@ -908,6 +1032,8 @@ fn run_individual_benchmark(benchmark_name: &str, target_name: &str) -> Benchmar
         "tracing" => bench_tracing(target),
         "tracing_async" => bench_tracing_async(target),
         "winston" => bench_winston(target),
+        "winston_fields" => bench_winston_fields(target, false),
+        "winston_stream" => bench_winston_fields(target, true),
         _ => panic!("Unknown benchmark: {}", benchmark_name),
     };
 
@@ -1594,6 +1720,8 @@ fn main() {
         "slog_async",
         "tracing_async",
         "winston",
+        "winston_fields",
+        "winston_stream",
     ];
 
     let targets = OutputTarget::all();
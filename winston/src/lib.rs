@@ -1,4 +1,6 @@
 mod global;
+#[doc(hidden)]
+pub mod log_field;
 mod log_macros;
 mod logger;
 mod logger_builder;
@@ -15,6 +17,8 @@ pub use global::{
 };
 pub use logform as format;
 pub use logger::Logger;
-pub use logger_options::{BackpressureStrategy, LoggerOptions};
+pub use logger_options::{
+    BackpressureStrategy, CallSiteHook, FormatReport, FormatReportHook, LoggerOptions,
+};
 pub use logger_transport::LoggerTransport;
 pub use winston_transport::LogQuery;
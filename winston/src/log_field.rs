@@ -0,0 +1,63 @@
+//! Support for the `err = <value>` field of `log!`.
+//!
+//! Which method `record` resolves to depends on the value's type, tried in
+//! order through autoref: `std::error::Error` values, values that convert to
+//! `&(dyn Error + Send + Sync)` (`anyhow::Error`, `Box<dyn Error + Send + Sync>`),
+//! values that convert to `&dyn Error` (`Box<dyn Error>`), and finally anything
+//! `Serialize`, which is stored under the key as before. A value that fails to
+//! serialize is stored as the serializer's error text.
+
+use logform::LogInfo;
+use serde::Serialize;
+use serde_json::Value;
+use std::error::Error;
+
+pub struct ErrorField<'a, T: ?Sized>(pub &'a T);
+
+pub trait RecordError {
+    fn record(&self, entry: LogInfo, key: &'static str) -> LogInfo;
+}
+
+pub trait RecordSendError {
+    fn record(&self, entry: LogInfo, key: &'static str) -> LogInfo;
+}
+
+pub trait RecordDynError {
+    fn record(&self, entry: LogInfo, key: &'static str) -> LogInfo;
+}
+
+pub trait RecordValue {
+    fn record(&self, entry: LogInfo, key: &'static str) -> LogInfo;
+}
+
+impl<T: Error + ?Sized> RecordError for &&&ErrorField<'_, T> {
+    fn record(&self, entry: LogInfo, _key: &'static str) -> LogInfo {
+        entry.with_error(self.0)
+    }
+}
+
+impl<T> RecordSendError for &&ErrorField<'_, T>
+where
+    T: AsRef<dyn Error + Send + Sync> + ?Sized,
+{
+    fn record(&self, entry: LogInfo, _key: &'static str) -> LogInfo {
+        entry.with_error(self.0.as_ref())
+    }
+}
+
+impl<T> RecordDynError for &ErrorField<'_, T>
+where
+    T: AsRef<dyn Error> + ?Sized,
+{
+    fn record(&self, entry: LogInfo, _key: &'static str) -> LogInfo {
+        entry.with_error(self.0.as_ref())
+    }
+}
+
+impl<T: Serialize + ?Sized> RecordValue for ErrorField<'_, T> {
+    fn record(&self, entry: LogInfo, key: &'static str) -> LogInfo {
+        let value = serde_json::to_value(self.0)
+            .unwrap_or_else(|e| Value::String(format!("failed to serialize: {}", e)));
+        entry.with_meta(key, value)
+    }
+}
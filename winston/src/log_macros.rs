@@ -3,8 +3,8 @@ macro_rules! log {
     // First case: No logger, simple logging
     ($level:ident, $message:expr $(, $key:ident = $value:expr)* $(,)?) => {{
         if $crate::is_level_enabled_fast(stringify!($level)) {
-            let entry = $crate::format::LogInfo::new(stringify!($level), $message)
-                $(.with_meta(stringify!($key), $value))*;
+            let entry = $crate::format::LogInfo::new(stringify!($level), $message);
+            $(let entry = $crate::__log_field!(entry, $key, $value);)*
             $crate::log(entry);
         }
     }};
@@ -12,8 +12,8 @@ macro_rules! log {
     // Second case: With logger and key-value metadata
     ($logger:expr, $level:ident, $message:expr $(, $key:ident = $value:expr)* $(,)?) => {{
         if $logger.is_level_enabled_fast(stringify!($level)) {
-            let entry = $crate::format::LogInfo::new(stringify!($level), $message)
-                $(.with_meta(stringify!($key), $value))*;
+            let entry = $crate::format::LogInfo::new(stringify!($level), $message);
+            $(let entry = $crate::__log_field!(entry, $key, $value);)*
             $logger.log(entry);
         }
     }};
@@ -35,6 +35,44 @@ macro_rules! log {
             $crate::log(entry);
         }
     }};
+
+    // Fifth case: With logger and positional arguments for the `splat()` format
+    // (e.g., log!(logger, info, "%s has %d items"; user, count))
+    ($logger:expr, $level:ident, $message:expr; $($arg:expr),+ $(,)?) => {{
+        if $logger.is_level_enabled_fast(stringify!($level)) {
+            let entry = $crate::format::LogInfo::new(stringify!($level), $message);
+            $(let entry = entry.with_splat($arg);)+
+            $logger.log(entry);
+        }
+    }};
+
+    // Sixth case: No logger and with positional arguments for the `splat()` format
+    ($level:ident, $message:expr; $($arg:expr),+ $(,)?) => {{
+        if $crate::is_level_enabled_fast(stringify!($level)) {
+            let entry = $crate::format::LogInfo::new(stringify!($level), $message);
+            $(let entry = entry.with_splat($arg);)+
+            $crate::log(entry);
+        }
+    }};
+}
+
+/// Adds one `key = value` pair from `log!` to an entry.
+///
+/// An `err` value that is an error (`std::error::Error`, `anyhow::Error`,
+/// `Box<dyn Error>`) is recorded as a structured `error` object via
+/// `LogInfo::with_error`; any other `err` value is stored under `err` like
+/// other keys.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_field {
+    ($entry:expr, err, $value:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::log_field::{RecordDynError, RecordError, RecordSendError, RecordValue};
+        (&&&$crate::log_field::ErrorField(&$value)).record($entry, "err")
+    }};
+    ($entry:expr, $key:ident, $value:expr) => {
+        $entry.with_meta(stringify!($key), $value)
+    };
 }
 
 #[macro_export]
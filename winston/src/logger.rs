@@ -1,10 +1,12 @@
 use crate::{
     logger_builder::LoggerBuilder,
-    logger_options::{BackpressureStrategy, LoggerOptions},
+    logger_options::{
+        default_format, BackpressureStrategy, CallSiteHook, FormatReport, LoggerOptions,
+    },
     logger_transport::{IntoLoggerTransport, LoggerTransport},
 };
 use crossbeam_channel::{bounded, Receiver, Sender, TrySendError};
-use logform::LogInfo;
+use logform::{Format, FormatError, LogInfo};
 use parking_lot::RwLock;
 use std::{
     collections::VecDeque,
@@ -96,6 +98,16 @@ pub struct Logger {
     min_required_severity_cache: AtomicU8,
     // Cached backpressure strategy: 0 = Block, 1 = DropOldest, 2 = DropCurrent.
     backpressure_cache: AtomicU8,
+    // Kept outside the shared state so `log` runs it without taking the lock.
+    call_site_hook: Option<DebugHook>,
+}
+
+struct DebugHook(CallSiteHook);
+
+impl std::fmt::Debug for DebugHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("<Fn(&mut LogInfo)>")
+    }
 }
 
 impl Logger {
@@ -109,6 +121,7 @@ impl Logger {
         // Pre-compute effective levels and cache values before options is moved.
         let min_required_severity = Self::compute_min_severity(&options);
         let bp_cache = Self::encode_backpressure(options.backpressure_strategy.as_ref());
+        let call_site_hook = options.call_site_hook.clone().map(DebugHook);
         let shared_state = Arc::new(RwLock::new(SharedState {
             options,
             min_required_severity,
@@ -143,6 +156,7 @@ impl Logger {
             is_closed: AtomicBool::new(false),
             min_required_severity_cache: AtomicU8::new(severity_cache),
             backpressure_cache: AtomicU8::new(bp_cache),
+            call_site_hook,
         }
     }
 
@@ -320,19 +334,53 @@ impl Logger {
                     }
                 }
 
-                let formatted_message = match (transport.get_format(), &options.format) {
-                    (Some(tf), Some(_lf)) => tf.transform((**entry).clone()),
-                    (Some(tf), None) => tf.transform((**entry).clone()),
-                    (None, Some(lf)) => lf.transform((**entry).clone()),
-                    (None, None) => Some((**entry).clone()),
+                let format = transport
+                    .get_format()
+                    .or_else(|| options.format.clone())
+                    // The transport's finalizer serializes the entry; the
+                    // default `json()` would only build a string it ignores
+                    .filter(|format| {
+                        !(Arc::ptr_eq(format, default_format())
+                            && transport.get_transport().has_finalizer())
+                    });
+                let Some(format) = format else {
+                    transport.get_transport().log((**entry).clone());
+                    continue;
                 };
-                if let Some(msg) = formatted_message {
-                    transport.get_transport().log(msg);
+
+                // Lets colorizing formats leave colors out for non-terminals
+                let terminal = transport.get_transport().is_terminal();
+                let formatted =
+                    logform::with_terminal(terminal, || format.try_transform((**entry).clone()));
+                match formatted {
+                    Ok(Some(msg)) => transport.get_transport().log(msg),
+                    Ok(None) => Self::report_format(state, &*format, entry, None),
+                    Err(e) => Self::report_format(state, &*format, entry, Some(e)),
                 }
             }
         }
     }
 
+    fn report_format(
+        state: &SharedState,
+        format: &(dyn Format<Input = LogInfo> + Send + Sync),
+        entry: &LogInfo,
+        error: Option<FormatError>,
+    ) {
+        match (&state.options.format_report_hook, error) {
+            (Some(hook), error) => hook(&FormatReport {
+                format: format.describe(),
+                level: entry.level.to_string(),
+                message: entry.message.clone(),
+                error,
+            }),
+            (None, Some(error)) => {
+                eprintln!("[winston] Format '{}' failed: {}", format.describe(), error)
+            }
+            (None, None) => {}
+        }
+    }
+
     fn is_level_enabled(entry_level: &str, state: &SharedState) -> bool {
         if let Some(min_required) = state.min_required_severity {
             if let Some(levels) = &state.options.levels {
@@ -377,10 +425,15 @@ impl Logger {
         Ok(results)
     }
 
-    pub fn log(&self, entry: LogInfo) {
+    pub fn log(&self, mut entry: LogInfo) {
         if !self.is_level_enabled_fast(&entry.level) {
             return;
         }
+        // Stamp before queueing so entries that wait in the channel keep their call-site time.
+        entry.timestamp.get_or_insert_with(chrono::Utc::now);
+        if let Some(DebugHook(hook)) = &self.call_site_hook {
+            hook(&mut entry);
+        }
         let entry = Arc::new(entry);
         match self.sender.try_send(LogMessage::Entry(entry)) {
             Ok(_) => {}
@@ -415,7 +468,8 @@ impl Logger {
         }
     }
 
-    pub fn logi(&self, entry: LogInfo) {
+    pub fn logi(&self, mut entry: LogInfo) {
+        entry.timestamp.get_or_insert_with(chrono::Utc::now);
         let entry = Arc::new(entry);
         let _ = self.sender.send(LogMessage::Entry(entry));
     }
@@ -513,7 +567,7 @@ impl Logger {
     /// new options -> existing options -> defaults. Always clears existing transports
     /// and processes buffered entries after updating.
     ///
-    /// Note: The backpressure strategy and channel capacity are not reconfigured, as they are only used during logger creation.
+    /// Note: The backpressure strategy, channel capacity and call-site hook are not reconfigured, as they are only used during logger creation.
     ///
     /// # Arguments
     /// * `new_options` - Optional new configuration. If `None`, the existing configuration is retained.
@@ -538,6 +592,10 @@ impl Logger {
                 .level
                 .or_else(|| state.options.level.take().or(default_options.level));
 
+            if options.format_report_hook.is_some() {
+                state.options.format_report_hook = options.format_report_hook;
+            }
+
             // Add all transports we have been provided
             if let Some(transports) = options.transports {
                 state.options.transports = Some(transports);
@@ -644,28 +702,20 @@ impl Log for Logger {
 
     fn log(&self, record: &Record) {
         // Convert log::Record to LogInfo
-        let mut meta = std::collections::HashMap::new();
-        // Add timestamp
-        meta.insert(
-            "timestamp".to_string(),
-            serde_json::Value::String(chrono::Utc::now().to_rfc3339()),
-        );
+        let mut meta = logform::Meta::with_capacity(4);
         // Add target (module path)
         meta.insert(
-            "target".to_string(),
+            "target",
             serde_json::Value::String(record.target().to_string()),
         );
         // Add file location if available
         if let Some(file) = record.file() {
-            meta.insert(
-                "file".to_string(),
-                serde_json::Value::String(file.to_string()),
-            );
+            meta.insert("file", serde_json::Value::String(file.to_string()));
         }
         // Add line number if available
         if let Some(line) = record.line() {
             meta.insert(
-                "line".to_string(),
+                "line",
                 serde_json::Value::Number(serde_json::Number::from(line)),
             );
         }
@@ -673,7 +723,7 @@ impl Log for Logger {
         if let Some(module_path) = record.module_path() {
             if module_path != record.target() {
                 meta.insert(
-                    "module_path".to_string(),
+                    "module_path",
                     serde_json::Value::String(module_path.to_string()),
                 );
             }
@@ -690,11 +740,15 @@ impl Log for Logger {
             }
         }
 
-        let log_info = LogInfo::from_parts(
-            record.level().as_str().to_lowercase(),
-            record.args().to_string(),
-            meta,
-        );
+        let level = match record.level() {
+            log::Level::Error => "error",
+            log::Level::Warn => "warn",
+            log::Level::Info => "info",
+            log::Level::Debug => "debug",
+            log::Level::Trace => "trace",
+        };
+        let log_info = LogInfo::from_parts(level, record.args().to_string(), meta)
+            .with_timestamp(chrono::Utc::now());
 
         self.log(log_info);
     }
@@ -936,6 +990,28 @@ mod tests {
         assert_eq!(logs[0].message, "Passes transport filter");
     }
 
+    #[test]
+    fn test_log_captures_timestamp_before_queueing() {
+        let logger = Logger::builder().format(logform::passthrough()).build();
+        let transport = TestTransport::new();
+
+        let before = chrono::Utc::now();
+        logger.log(LogInfo::new("info", "Buffered until a transport exists"));
+        logger.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        logger.add_transport(transport.clone());
+        logger.log(LogInfo::new("info", "Direct"));
+        logger.flush().unwrap();
+
+        let logs = transport.get_logs();
+        assert_eq!(logs.len(), 2);
+        let buffered = logs[0].timestamp.unwrap();
+        let direct = logs[1].timestamp.unwrap();
+        assert!(buffered >= before);
+        assert!(direct - buffered >= chrono::Duration::milliseconds(20));
+    }
+
     #[test]
     fn test_empty_message_handling() {
         let logger = Logger::new(None);
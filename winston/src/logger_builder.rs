@@ -1,5 +1,5 @@
 use crate::{
-    logger_options::{BackpressureStrategy, LoggerOptions},
+    logger_options::{BackpressureStrategy, FormatReport, LoggerOptions},
     logger_transport::IntoLoggerTransport,
     Logger,
 };
@@ -65,6 +65,22 @@ impl LoggerBuilder {
         self
     }
 
+    pub fn call_site_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut LogInfo) + Send + Sync + 'static,
+    {
+        self.options = self.options.call_site_hook(hook);
+        self
+    }
+
+    pub fn format_report_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&FormatReport) + Send + Sync + 'static,
+    {
+        self.options = self.options.format_report_hook(hook);
+        self
+    }
+
     pub fn build(self) -> Logger {
         Logger::new(Some(self.options))
     }
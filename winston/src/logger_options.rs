@@ -3,10 +3,16 @@ use crate::{
     logger_levels::LoggerLevels,
     logger_transport::{IntoLoggerTransport, LoggerTransport},
 };
-use logform::{json, Format, LogInfo};
-use std::{collections::HashMap, sync::Arc};
+use logform::{json, Format, FormatError, LogInfo};
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
 
+/// Settings for a [`Logger`](crate::Logger). Build them with `LoggerOptions::new()`
+/// and the setters below, or assign fields on `LoggerOptions::default()`.
 #[derive(Clone)]
+#[non_exhaustive]
 pub struct LoggerOptions {
     pub levels: Option<LoggerLevels>,
     pub format: Option<Arc<dyn Format<Input = LogInfo> + Send + Sync>>,
@@ -14,6 +20,28 @@ pub struct LoggerOptions {
     pub transports: Option<Vec<(TransportHandle, LoggerTransport<LogInfo>)>>,
     pub channel_capacity: Option<usize>,
     pub backpressure_strategy: Option<BackpressureStrategy>,
+    pub call_site_hook: Option<CallSiteHook>,
+    pub format_report_hook: Option<FormatReportHook>,
+}
+
+/// Runs in `Logger::log` on the calling thread, before the entry is queued for
+/// the worker thread that applies formats.
+pub type CallSiteHook = Arc<dyn Fn(&mut LogInfo) + Send + Sync>;
+
+/// Runs on the worker thread for each entry a format drops or fails on.
+pub type FormatReportHook = Arc<dyn Fn(&FormatReport) + Send + Sync>;
+
+/// An entry that a format did not pass on to its transport.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct FormatReport {
+    /// `describe()` of the transport's (or else the logger's) format.
+    pub format: String,
+    pub level: String,
+    pub message: String,
+    /// Why the format failed, or `None` if it dropped the entry on purpose
+    /// (e.g. a `filter`).
+    pub error: Option<FormatError>,
 }
 
 impl LoggerOptions {
@@ -145,6 +173,48 @@ impl LoggerOptions {
         self.backpressure_strategy = Some(strategy);
         self
     }
+
+    /// Sets a hook that runs on the calling thread for every entry that passes
+    /// the level filter, before it is queued.
+    ///
+    /// Formats run on the logger's worker thread, so anything tied to the
+    /// call site (e.g. the thread, via `logform::capture_thread`) must be
+    /// recorded here.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - A function that can add to or modify the entry.
+    pub fn call_site_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut LogInfo) + Send + Sync + 'static,
+    {
+        self.call_site_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets a hook that is told about every entry a format drops or fails on,
+    /// and which format it was.
+    ///
+    /// Without a hook, failures are printed to stderr and drops are silent.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - A function receiving the [`FormatReport`].
+    pub fn format_report_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&FormatReport) + Send + Sync + 'static,
+    {
+        self.format_report_hook = Some(Arc::new(hook));
+        self
+    }
+}
+
+/// The `json()` format `LoggerOptions::default` starts with. It is shared so
+/// the logger can tell it from a format the user set, and leave it off
+/// transports that write entries through their own finalizer.
+pub(crate) fn default_format() -> &'static Arc<dyn Format<Input = LogInfo> + Send + Sync> {
+    static FORMAT: OnceLock<Arc<dyn Format<Input = LogInfo> + Send + Sync>> = OnceLock::new();
+    FORMAT.get_or_init(|| Arc::new(json()))
 }
 
 impl Default for LoggerOptions {
@@ -154,7 +224,8 @@ impl Default for LoggerOptions {
     /// - A default set of logging levels.
     /// - The logging level set to "info".
     /// - No default transports.
-    /// - The JSON format for log entries.
+    /// - The JSON format for log entries, except on transports with their own
+    ///   finalizer (see `Transport::has_finalizer`).
     /// - A channel capacity of 1024.
     /// - A backpressure strategy set to `BackpressureStrategy::Block`, meaning the logger will block on overflow until space is available.
     fn default() -> Self {
@@ -162,9 +233,11 @@ impl Default for LoggerOptions {
             levels: Some(LoggerLevels::default()),
             level: Some("info".to_string()),
             transports: Some(Vec::new()),
-            format: Some(Arc::new(json())),
+            format: Some(default_format().clone()),
             channel_capacity: Some(1024),
             backpressure_strategy: Some(BackpressureStrategy::Block),
+            call_site_hook: None,
+            format_report_hook: None,
         }
     }
 }
@@ -179,6 +252,17 @@ impl std::fmt::Debug for LoggerOptions {
             .field("backpressure_strategy", &self.backpressure_strategy)
             // For the format field, just print a placeholder because it can't be debugged:
             .field("format", &"<Format trait object>")
+            .field(
+                "call_site_hook",
+                &self.call_site_hook.as_ref().map(|_| "<Fn(&mut LogInfo)>"),
+            )
+            .field(
+                "format_report_hook",
+                &self
+                    .format_report_hook
+                    .as_ref()
+                    .map(|_| "<Fn(&FormatReport)>"),
+            )
             .finish()
     }
 }
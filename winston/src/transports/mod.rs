@@ -1,13 +1,13 @@
-use std::io;
+use std::io::{self, IsTerminal};
 pub use winston_file::FileTransport as File;
 pub use winston_transport::transport_adapters::WriterTransport;
 pub use winston_transport::*;
 
 // Convenience functions
 pub fn stdout() -> WriterTransport<io::Stdout, LogInfo> {
-    WriterTransport::new(io::stdout())
+    WriterTransport::new(io::stdout()).with_terminal(io::stdout().is_terminal())
 }
 
 pub fn stderr() -> WriterTransport<io::Stderr, LogInfo> {
-    WriterTransport::new(io::stderr())
+    WriterTransport::new(io::stderr()).with_terminal(io::stderr().is_terminal())
 }
@@ -13,6 +13,7 @@ pub struct MockConfig {
     pub should_fail_log: bool,
     pub should_fail_flush: bool,
     pub level: Option<String>,
+    pub has_finalizer: bool,
 }
 
 impl Default for MockConfig {
@@ -22,6 +23,7 @@ impl Default for MockConfig {
             should_fail_log: false,
             should_fail_flush: false,
             level: None,
+            has_finalizer: false,
         }
     }
 }
@@ -97,6 +99,10 @@ impl Transport<LogInfo> for MockTransport {
         self.logs.lock().unwrap().push(info);
     }
 
+    fn has_finalizer(&self) -> bool {
+        self.config.has_finalizer
+    }
+
     fn flush(&self) -> Result<(), String> {
         if self.config.should_fail_flush {
             Err("Mock flush failure".to_string())
@@ -111,7 +117,9 @@ impl Transport<LogInfo> for MockTransport {
             .iter()
             .filter(|log| {
                 // Check level filtering
-                if !options.levels.is_empty() && !options.levels.contains(&log.level) {
+                if !options.levels.is_empty()
+                    && !options.levels.iter().any(|level| *level == log.level)
+                {
                     return false;
                 }
 
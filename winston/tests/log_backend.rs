@@ -104,7 +104,7 @@ fn test_log_backend_metadata_capture() {
     assert_eq!(logs.len(), 1);
 
     // Should capture timestamp and target metadata
-    assert!(logs[0].meta.contains_key("timestamp"));
+    assert!(logs[0].timestamp.is_some());
     assert!(logs[0].meta.contains_key("target"));
 }
 
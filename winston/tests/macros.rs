@@ -151,6 +151,86 @@ fn test_log_macro_with_complex_metadata_values() {
     assert!(logs[0].meta.contains_key("data"));
 }
 
+#[test]
+fn test_log_macro_with_error_value() {
+    let transport = MockTransport::new();
+    let logger = Logger::builder()
+        .format(logform::passthrough())
+        .transport(transport.clone())
+        .build();
+
+    let e = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "access denied");
+    log!(
+        logger,
+        error,
+        "Failed to open file",
+        err = e,
+        path = "/etc/app.toml"
+    );
+    logger.flush().unwrap();
+
+    let logs = transport.get_logs();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].meta["error"]["message"], "access denied");
+    assert_eq!(logs[0].meta["path"], "/etc/app.toml");
+    assert!(!logs[0].meta.contains_key("err"));
+}
+
+#[test]
+fn test_log_macro_err_accepts_boxed_and_anyhow_errors() {
+    let transport = MockTransport::new();
+    let logger = Logger::builder()
+        .format(logform::passthrough())
+        .transport(transport.clone())
+        .build();
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> = "boxed failure".into();
+    log!(logger, error, "Boxed", err = boxed);
+    let local: Box<dyn std::error::Error> = "local failure".into();
+    log!(logger, error, "Local", err = local);
+    let io = std::io::Error::other("inner");
+    let dynamic: &dyn std::error::Error = &io;
+    log!(logger, error, "Dynamic", err = dynamic);
+    let wrapped = anyhow::Error::new(std::io::Error::other("disk full")).context("save failed");
+    log!(logger, error, "Anyhow", err = wrapped);
+    logger.flush().unwrap();
+
+    let logs = transport.get_logs();
+    assert_eq!(logs.len(), 4);
+    assert_eq!(logs[0].meta["error"]["message"], "boxed failure");
+    assert!(logs[0].meta["error"].get("type").is_none());
+    assert_eq!(logs[1].meta["error"]["message"], "local failure");
+    assert_eq!(logs[2].meta["error"]["message"], "inner");
+    assert_eq!(logs[3].meta["error"]["message"], "save failed");
+    assert_eq!(logs[3].meta["error"]["chain"][0], "disk full");
+    assert!(logs.iter().all(|log| !log.meta.contains_key("err")));
+}
+
+#[test]
+fn test_log_macro_err_keeps_plain_values() {
+    let transport = MockTransport::new();
+    let logger = Logger::builder()
+        .format(logform::passthrough())
+        .transport(transport.clone())
+        .build();
+
+    log!(logger, warn, "Retrying", err = "timeout", attempt = 2);
+    log!(logger, warn, "Retrying", err = String::from("reset"));
+    // Map keys must be strings in JSON
+    let unserializable = std::collections::HashMap::from([((1, 2), "pair")]);
+    log!(logger, warn, "Retrying", err = unserializable);
+    logger.flush().unwrap();
+
+    let logs = transport.get_logs();
+    assert_eq!(logs[0].meta["err"], "timeout");
+    assert_eq!(logs[1].meta["err"], "reset");
+    assert_eq!(
+        logs[2].meta["err"],
+        "failed to serialize: key must be a string"
+    );
+    assert!(!logs[0].meta.contains_key("error"));
+}
+
 // Tests for global logger with macros
 #[test]
 #[serial]
@@ -224,3 +304,29 @@ fn test_log_macro_with_global_and_meta_macro() {
 
     ////winston::close();
 }
+
+#[test]
+fn test_log_macro_with_positional_args() {
+    let transport = MockTransport::new();
+    let logger = Logger::builder()
+        .format(logform::splat())
+        .transport(transport.clone())
+        .build();
+
+    let user = "Alice";
+    log!(
+        logger,
+        info,
+        "%s has %d items";
+        user,
+        5,
+        serde_json::json!({"cart_id": 7}),
+    );
+    logger.flush().unwrap();
+
+    let logs = transport.get_logs();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].message, "Alice has 5 items");
+    assert_eq!(logs[0].meta["cart_id"], serde_json::json!(7));
+    assert!(!logs[0].meta.contains_key("splat"));
+}
@@ -1,6 +1,6 @@
 mod common;
 
-use common::{wait_for_logs, MockTransport};
+use common::{wait_for_logs, MockConfig, MockTransport};
 use logform::LogInfo;
 use winston::{log, BackpressureStrategy, Logger, LoggerOptions};
 
@@ -37,6 +37,74 @@ fn test_logger_options_api() {
     assert_eq!(transport.log_count(), 1);
 }
 
+#[test]
+fn test_call_site_hook_captures_calling_thread() {
+    let transport = MockTransport::new();
+    let logger = std::sync::Arc::new(
+        Logger::builder()
+            .format(logform::enrich())
+            .call_site_hook(logform::capture_thread)
+            .transport(transport.clone())
+            .build(),
+    );
+
+    let caller = std::sync::Arc::clone(&logger);
+    std::thread::Builder::new()
+        .name("request-handler".to_string())
+        .spawn(move || caller.log(LogInfo::new("info", "Handled")))
+        .unwrap()
+        .join()
+        .unwrap();
+    wait_for_logs(&logger);
+
+    let logs = transport.get_logs();
+    assert_eq!(logs[0].meta["thread_name"], "request-handler");
+    assert!(logs[0].meta.contains_key("hostname"));
+}
+
+#[test]
+fn test_format_report_hook_names_dropping_and_failing_formats() {
+    use logform::{filter, Format, FormatError};
+    use std::sync::{Arc, Mutex};
+
+    struct Broken;
+    impl Format for Broken {
+        type Input = LogInfo;
+
+        fn transform(&self, info: LogInfo) -> Option<LogInfo> {
+            self.try_transform(info).ok().flatten()
+        }
+
+        fn try_transform(&self, _info: LogInfo) -> Result<Option<LogInfo>, FormatError> {
+            Err(FormatError::new(self.name(), "cannot encode"))
+        }
+    }
+
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&reports);
+    let transport = MockTransport::new();
+    let logger = Logger::builder()
+        .format(filter(|info| info.message != "noise").chain(Broken))
+        .format_report_hook(move |report| sink.lock().unwrap().push(report.clone()))
+        .transport(transport.clone())
+        .build();
+
+    logger.log(LogInfo::new("info", "noise"));
+    logger.log(LogInfo::new("warn", "signal"));
+    wait_for_logs(&logger);
+
+    let reports = reports.lock().unwrap();
+    assert!(transport.get_logs().is_empty());
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].format, "FilterFormat | Broken");
+    assert_eq!(reports[0].message, "noise");
+    assert!(reports[0].error.is_none());
+    let error = reports[1].error.as_ref().unwrap();
+    assert_eq!(error.format(), "Broken");
+    assert_eq!(error.message(), "cannot encode");
+    assert_eq!(reports[1].level, "warn");
+}
+
 #[test]
 fn test_log_macro_with_logger_instance() {
     let transport = MockTransport::new();
@@ -259,3 +327,37 @@ fn test_empty_message_filtered() {
 
     assert_eq!(transport.log_count(), 0);
 }
+
+#[test]
+fn test_default_format_skipped_for_finalizing_transports() {
+    let plain = MockTransport::new();
+    let finalizing = MockTransport::with_config(MockConfig {
+        has_finalizer: true,
+        ..Default::default()
+    });
+    let logger = Logger::builder()
+        .transport(plain.clone())
+        .transport(finalizing.clone())
+        .build();
+
+    logger.log(LogInfo::new("info", "Handled"));
+    wait_for_logs(&logger);
+
+    assert!(plain.get_logs()[0].formatted.is_some());
+    assert_eq!(finalizing.get_logs()[0].formatted, None);
+
+    // A format the user set runs as given
+    let finalizing = MockTransport::with_config(MockConfig {
+        has_finalizer: true,
+        ..Default::default()
+    });
+    let logger = Logger::builder()
+        .format(logform::json())
+        .transport(finalizing.clone())
+        .build();
+
+    logger.log(LogInfo::new("info", "Handled"));
+    wait_for_logs(&logger);
+
+    assert!(finalizing.get_logs()[0].formatted.is_some());
+}
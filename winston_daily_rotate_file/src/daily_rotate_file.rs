@@ -1,6 +1,6 @@
 use chrono::{DateTime, Local, Utc};
 use flate2::{write::GzEncoder, Compression};
-use logform::{Format, LogInfo};
+use logform::{Finalizer, Format, LogInfo};
 use std::cell::RefCell;
 use std::fmt::Write as FmtWrite;
 use std::fs::{create_dir_all, read_dir, File, OpenOptions};
@@ -9,6 +9,10 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use winston_transport::Transport;
 
+/// Options for [`DailyRotateFile::new`]. Start from `Default::default()` and
+/// set the fields you need, or use [`DailyRotateFile::builder`]; fields may be
+/// added in minor releases.
+#[non_exhaustive]
 pub struct DailyRotateFileOptions {
     pub level: Option<String>,
     pub format: Option<Arc<dyn Format<Input = LogInfo> + Send + Sync>>,
@@ -19,6 +23,29 @@ pub struct DailyRotateFileOptions {
     pub dirname: Option<PathBuf>,
     pub zipped_archive: bool,
     pub utc: bool,
+    /// Written as the first line of every file, including rotated ones.
+    pub header: Option<String>,
+    /// Writes entries instead of their message, e.g. `json()`; see
+    /// `DailyRotateFileBuilder::finalizer`.
+    pub finalizer: Option<Arc<dyn Finalizer + Send + Sync>>,
+}
+
+impl Default for DailyRotateFileOptions {
+    fn default() -> Self {
+        Self {
+            level: None,
+            format: None,
+            filename: PathBuf::new(),
+            date_pattern: String::from("%Y-%m-%d"),
+            max_files: None,
+            max_size: None,
+            dirname: None,
+            zipped_archive: false,
+            utc: false,
+            header: None,
+            finalizer: None,
+        }
+    }
 }
 
 pub struct DailyRotateFile {
@@ -60,7 +87,11 @@ impl DailyRotateFile {
         let parent = full_path.parent().unwrap_or(log_dir);
         create_dir_all(parent)?;
 
-        Self::create_unique_file(log_dir, &filename)
+        let (mut file, path) = Self::create_unique_file(log_dir, &filename)?;
+        if let Some(header) = &options.header {
+            writeln!(file, "{}", header)?;
+        }
+        Ok((file, path))
     }
 
     fn create_unique_file(log_dir: &Path, filename: &Path) -> std::io::Result<(File, PathBuf)> {
@@ -360,8 +391,56 @@ impl DailyRotateFile {
     }
 }
 
+thread_local! {
+    static BUF: RefCell<String> = const { RefCell::new(String::new()) };
+    static BYTES: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+impl DailyRotateFile {
+    /// Writes whole lines, rotating first if they would overflow the file.
+    fn write_lines(&self, lines: &[u8]) {
+        if self.should_rotate(lines.len()) {
+            self.rotate();
+        }
+
+        let mut file = match self.file.lock() {
+            Ok(f) => f,
+            Err(_) => {
+                eprintln!("Failed to acquire file lock for batch logging");
+                return;
+            }
+        };
+
+        if let Err(e) = file.write_all(lines) {
+            eprintln!("Failed to write log batch: {}", e);
+        }
+    }
+
+    /// Serializes entries through the finalizer into a reused buffer, which
+    /// also gives their size for the rotation check.
+    fn write_finalized<'a>(
+        &self,
+        finalizer: &dyn Finalizer,
+        infos: impl IntoIterator<Item = &'a LogInfo>,
+    ) {
+        BYTES.with(|bytes| {
+            let mut bytes = bytes.borrow_mut();
+            bytes.clear();
+            for info in infos {
+                if let Err(e) = finalizer.append_line(info, &mut bytes) {
+                    eprintln!("Failed to format log: {}", e);
+                }
+            }
+            self.write_lines(&bytes);
+        });
+    }
+}
+
 impl Transport<LogInfo> for DailyRotateFile {
     fn log(&self, info: LogInfo) {
+        if let Some(finalizer) = &self.options.finalizer {
+            return self.write_finalized(&**finalizer, [&info]);
+        }
         let entry_size = format!("{}\n", info.message).len();
 
         if self.should_rotate(entry_size) {
@@ -390,9 +469,8 @@ impl Transport<LogInfo> for DailyRotateFile {
         if infos.is_empty() {
             return;
         }
-
-        thread_local! {
-            static BUF: RefCell<String> = const { RefCell::new(String::new()) };
+        if let Some(finalizer) = &self.options.finalizer {
+            return self.write_finalized(&**finalizer, &infos);
         }
 
         BUF.with(|buf| {
@@ -401,25 +479,14 @@ impl Transport<LogInfo> for DailyRotateFile {
             for info in &infos {
                 let _ = writeln!(buf, "{}", info.message);
             }
-
-            if self.should_rotate(buf.len()) {
-                self.rotate();
-            }
-
-            let mut file = match self.file.lock() {
-                Ok(f) => f,
-                Err(_) => {
-                    eprintln!("Failed to acquire file lock for batch logging");
-                    return;
-                }
-            };
-
-            if let Err(e) = file.write_all(buf.as_bytes()) {
-                eprintln!("Failed to write log batch: {}", e);
-            }
+            self.write_lines(buf.as_bytes());
         });
     }
 
+    fn has_finalizer(&self) -> bool {
+        self.options.finalizer.is_some()
+    }
+
     fn flush(&self) -> Result<(), String> {
         let mut file = self.file.lock().unwrap();
         file.flush().map_err(|e| format!("Failed to flush: {}", e))
@@ -436,6 +503,8 @@ pub struct DailyRotateFileBuilder {
     dirname: Option<PathBuf>,
     zipped_archive: bool,
     utc: bool,
+    header: Option<String>,
+    finalizer: Option<Arc<dyn Finalizer + Send + Sync>>,
 }
 
 impl Default for DailyRotateFileBuilder {
@@ -456,6 +525,8 @@ impl DailyRotateFileBuilder {
             dirname: None,
             zipped_archive: false,
             utc: false,
+            header: None,
+            finalizer: None,
         }
     }
 
@@ -504,6 +575,26 @@ impl DailyRotateFileBuilder {
         self
     }
 
+    /// Sets a line written first in every new file, such as the column names
+    /// from `logform::csv().header()`.
+    pub fn header<T: Into<String>>(mut self, header: T) -> Self {
+        self.header = Some(header.into());
+        self
+    }
+
+    /// Writes entries with `finalizer` (e.g. `logform::json()`) instead of
+    /// their message, serialized straight into a reused buffer; the format
+    /// should then run everything but the finalizer. A `Logger` leaves its
+    /// default `json()` format off this transport, but runs a format set on the
+    /// logger as given.
+    pub fn finalizer<F>(mut self, finalizer: F) -> Self
+    where
+        F: Finalizer + Send + Sync + 'static,
+    {
+        self.finalizer = Some(Arc::new(finalizer));
+        self
+    }
+
     pub fn build(self) -> Result<DailyRotateFile, String> {
         let filename = self.filename.ok_or("Filename is required")?;
 
@@ -517,6 +608,8 @@ impl DailyRotateFileBuilder {
             dirname: self.dirname,
             zipped_archive: self.zipped_archive,
             utc: self.utc,
+            header: self.header,
+            finalizer: self.finalizer,
         };
 
         Ok(DailyRotateFile::new(options))
@@ -589,6 +682,63 @@ mod tests {
         assert_eq!(files.len(), 2, "Expected two log files after date rotation");
     }
 
+    #[test]
+    fn test_header_in_every_file() {
+        let temp_dir = setup_temp_dir();
+        let transport = DailyRotateFile::builder()
+            .filename(temp_dir.path().join("audit.csv"))
+            .max_size(60)
+            .header("level,message")
+            .build()
+            .expect("Failed to create transport");
+
+        for _ in 0..3 {
+            transport.log(LogInfo::new("info", "info,A fairly long audit message"));
+        }
+        transport.flush().expect("Failed to flush");
+
+        let files: Vec<_> = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .collect();
+        assert!(files.len() > 1, "Expected rotation");
+        for file in files {
+            let contents = fs::read_to_string(file.path()).unwrap();
+            assert!(contents.starts_with("level,message\n"));
+            assert_eq!(contents.matches("level,message").count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_finalizer_writes_entries() {
+        let temp_dir = setup_temp_dir();
+        let transport = DailyRotateFile::builder()
+            .filename(temp_dir.path().join("app.log"))
+            .finalizer(logform::json())
+            .build()
+            .expect("Failed to create transport");
+
+        transport.log(LogInfo::new("info", "first").with_meta("id", 1));
+        transport.log_batch(vec![
+            LogInfo::new("warn", "second"),
+            LogInfo::new("error", "third"),
+        ]);
+        transport.flush().expect("Failed to flush");
+
+        let path = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let contents = fs::read_to_string(path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with('{') && lines[0].contains(r#""message":"first""#));
+        assert!(lines[0].contains(r#""id":1"#));
+        assert!(lines[2].contains(r#""level":"error""#));
+    }
+
     #[test]
     fn test_size_based_rotation() {
         let temp_dir = setup_temp_dir();
@@ -1,10 +1,12 @@
 //use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use dateparser::parse;
-use logform::{Format, LogInfo};
+use logform::{
+    Finalizer, Format, LogInfo, Meta,
+    parse::{self as log_parse, Parser},
+};
 use serde_json::Value;
 use std::cell::RefCell;
-use std::collections::HashMap;
 use std::fmt::Write as FmtWrite;
 use std::fs::{File, OpenOptions};
 use std::io::{BufRead, BufReader, BufWriter, Write};
@@ -13,10 +15,23 @@ use std::sync::{Arc, Mutex};
 use winston_proxy_transport::Proxy;
 use winston_transport::{LogQuery, Order, Transport};
 
+/// Options for [`FileTransport::new`]. Start from `Default::default()` and set
+/// the fields you need, or use [`FileTransport::builder`]; fields may be added
+/// in minor releases.
+#[non_exhaustive]
+#[derive(Default)]
 pub struct FileTransportOptions {
     pub level: Option<String>,
     pub format: Option<Arc<dyn Format<Input = LogInfo> + Send + Sync>>,
     pub filename: Option<PathBuf>,
+    /// Reads lines back for `query` and `proxy`; should match `format`.
+    /// Defaults to the JSON parser.
+    pub parser: Option<Arc<dyn Parser + Send + Sync>>,
+    /// Written as the first line of a new or empty file, e.g. `csv().header()`.
+    pub header: Option<String>,
+    /// Writes entries instead of their `Display` output, e.g. `json()`; see
+    /// `FileTransportBuilder::finalizer`.
+    pub finalizer: Option<Arc<dyn Finalizer + Send + Sync>>,
     /*
     unused yet
     pub dirname: Option<String>,
@@ -49,7 +64,13 @@ impl FileTransport {
             .append(true)
             .open(file_path)
             .expect("Failed to open log file");
-        let writer = BufWriter::new(file);
+        let is_empty = file.metadata().map(|m| m.len() == 0).unwrap_or(false);
+        let mut writer = BufWriter::new(file);
+        if let (true, Some(header)) = (is_empty, &options.header)
+            && let Err(e) = writeln!(writer, "{}", header)
+        {
+            eprintln!("Failed to write log file header: {}", e);
+        }
 
         FileTransport {
             file: Mutex::new(writer),
@@ -71,42 +92,43 @@ impl FileTransport {
 }
 
 impl FileTransport {
-    fn parse_log_entry(&self, line: &str) -> Option<LogInfo> {
-        let parsed: serde_json::Value = serde_json::from_str(line).ok()?;
-        //println!("Parsed log entry: {:?}", parsed); // Debug print
-
-        let level = parsed["level"].as_str()?;
-        let message = parsed["message"].as_str()?;
-        let meta = parsed
-            .as_object()?
-            .iter()
-            //.map(|(k, v)| (k.clone(), v.clone()))
-            .filter_map(|(k, v)| {
-                if k != "level" && k != "message" {
-                    Some((k.clone(), v.clone()))
-                } else {
-                    None
-                }
-            })
-            .collect::<HashMap<_, _>>(); // Collect all metadata
+    fn parser(&self) -> &dyn Parser {
+        match &self.options.parser {
+            Some(parser) => parser.as_ref(),
+            None => &log_parse::JsonParser,
+        }
+    }
 
-        Some(LogInfo::from_parts(level, message, meta))
+    fn parse_log_entry(&self, record: &str) -> Option<LogInfo> {
+        let mut entry = self.parser().parse(record).ok()?;
+        // Parse the stored timestamp once here so filtering and sorting don't re-parse it.
+        if let Some(timestamp) = Self::parse_meta_timestamp(&entry) {
+            entry.timestamp = Some(timestamp);
+        }
+        Some(entry)
     }
 
-    /// Extracts timestamp from a log entry's metadata.
-    /// Returns None if timestamp is missing or cannot be parsed.
-    fn extract_timestamp(entry: &LogInfo) -> Option<DateTime<Utc>> {
+    fn parse_meta_timestamp(entry: &LogInfo) -> Option<DateTime<Utc>> {
         entry.meta.get("timestamp").and_then(|value| match value {
             Value::String(ts_str) => parse(ts_str).ok().map(|dt| dt.with_timezone(&Utc)),
             _ => None,
         })
     }
 
+    /// Extracts timestamp from a log entry, preferring the first-class field and
+    /// falling back to its metadata.
+    /// Returns None if timestamp is missing or cannot be parsed.
+    fn extract_timestamp(entry: &LogInfo) -> Option<DateTime<Utc>> {
+        entry
+            .timestamp
+            .or_else(|| Self::parse_meta_timestamp(entry))
+    }
+
     /// Checks if a log entry matches all query criteria.
     /// Evaluates level, timestamp range, search term, and DSL filter.
     fn matches_query(&self, query: &LogQuery, entry: &LogInfo) -> bool {
         // Check level
-        if !query.levels.is_empty() && !query.levels.contains(&entry.level) {
+        if !query.levels.is_empty() && !query.levels.iter().any(|level| *level == entry.level) {
             return false;
         }
 
@@ -161,6 +183,32 @@ impl FileTransport {
     }
 }
 
+thread_local! {
+    static BUF: RefCell<String> = const { RefCell::new(String::new()) };
+    static BYTES: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+impl FileTransport {
+    /// Serializes entries through the finalizer into a reused buffer, outside
+    /// the file lock, then writes them in one call.
+    fn write_finalized<'a>(
+        &self,
+        finalizer: &dyn Finalizer,
+        logs: impl IntoIterator<Item = &'a LogInfo>,
+    ) -> std::io::Result<()> {
+        BYTES.with(|bytes| {
+            let mut bytes = bytes.borrow_mut();
+            bytes.clear();
+            for info in logs {
+                if let Err(e) = finalizer.append_line(info, &mut bytes) {
+                    eprintln!("Failed to format log entry: {}", e);
+                }
+            }
+            self.file.lock().unwrap().write_all(&bytes)
+        })
+    }
+}
+
 impl Transport<LogInfo> for FileTransport {
     /*fn log(&self, message: &str, _level: &str) {
         let mut file = self.file.lock().unwrap();
@@ -169,6 +217,12 @@ impl Transport<LogInfo> for FileTransport {
     } */
 
     fn log(&self, info: LogInfo) {
+        if let Some(finalizer) = &self.options.finalizer {
+            if let Err(e) = self.write_finalized(&**finalizer, [&info]) {
+                eprintln!("Failed to write to log file: {}", e);
+            }
+            return;
+        }
         let mut file = self.file.lock().unwrap();
         if let Err(e) = writeln!(file, "{}", info) {
             eprintln!("Failed to write to log file: {}", e);
@@ -179,8 +233,11 @@ impl Transport<LogInfo> for FileTransport {
         if logs.is_empty() {
             return;
         }
-        thread_local! {
-            static BUF: RefCell<String> = const { RefCell::new(String::new()) };
+        if let Some(finalizer) = &self.options.finalizer {
+            if let Err(e) = self.write_finalized(&**finalizer, &logs) {
+                eprintln!("Failed to write to log file in batch: {}", e);
+            }
+            return;
         }
         BUF.with(|buf| {
             let mut buf = buf.borrow_mut();
@@ -195,6 +252,10 @@ impl Transport<LogInfo> for FileTransport {
         });
     }
 
+    fn has_finalizer(&self) -> bool {
+        self.options.finalizer.is_some()
+    }
+
     fn flush(&self) -> Result<(), String> {
         let mut file = self.file.lock().unwrap();
         //println!("Flushing file transport");
@@ -206,7 +267,7 @@ impl Transport<LogInfo> for FileTransport {
     fn query(&self, query: &LogQuery) -> Result<Vec<LogInfo>, String> {
         let file = File::open(self.options.filename.as_ref().unwrap())
             .map_err(|e| format!("Failed to open log file: {}", e))?;
-        let reader = BufReader::new(file);
+        let mut reader = BufReader::new(file);
 
         let mut results = Vec::new();
 
@@ -214,13 +275,35 @@ impl Transport<LogInfo> for FileTransport {
         let start = query.start.unwrap_or(0);
         let limit = query.limit.unwrap_or(usize::MAX);
 
-        for (index, line) in reader.lines().enumerate() {
-            let line = line.map_err(|e| format!("Failed to read line {}: {}", index, e))?;
-            if let Some(entry) = self.parse_log_entry(&line)
+        let parser = self.parser();
+        let mut buffer = String::new();
+        let mut first_record = true;
+        let mut records = 0;
+        for line_index in 0.. {
+            // Read whole lines, line breaks included, until the record is
+            // complete: a record may span lines, e.g. a quoted CSV field
+            if reader
+                .read_line(&mut buffer)
+                .map_err(|e| format!("Failed to read line {}: {}", line_index, e))?
+                == 0
+            {
+                break;
+            }
+            if !parser.is_complete(&buffer) {
+                continue;
+            }
+            let line = std::mem::take(&mut buffer);
+            let record = line.strip_suffix('\n').unwrap_or(&line);
+            let record = record.strip_suffix('\r').unwrap_or(record);
+            if std::mem::replace(&mut first_record, false) && parser.is_header(record) {
+                continue;
+            }
+            records += 1;
+            if let Some(entry) = self.parse_log_entry(record)
                 && self.matches_query(query, &entry)
             {
-                // Skip lines until the start position
-                if index >= start {
+                // Skip records until the start position
+                if records > start {
                     results.push(entry);
                 }
 
@@ -243,11 +326,15 @@ impl Transport<LogInfo> for FileTransport {
                     let normalized_fields: Vec<String> =
                         query.fields.iter().map(|f| f.to_lowercase()).collect();
 
-                    LogInfo::from_parts(
+                    let timestamp = entry
+                        .timestamp
+                        .filter(|_| normalized_fields.contains(&"timestamp".to_string()));
+
+                    let mut projected = LogInfo::from_parts(
                         if normalized_fields.contains(&"level".to_string()) {
                             entry.level
                         } else {
-                            String::new()
+                            "".into()
                         },
                         if normalized_fields.contains(&"message".to_string()) {
                             entry.message
@@ -258,8 +345,10 @@ impl Transport<LogInfo> for FileTransport {
                             .meta
                             .into_iter()
                             .filter(|(k, _)| normalized_fields.contains(&k.to_lowercase()))
-                            .collect(),
-                    )
+                            .collect::<Meta>(),
+                    );
+                    projected.timestamp = timestamp;
+                    projected
                 })
                 .collect()
         } else {
@@ -375,8 +464,16 @@ impl Proxy<LogInfo> for FileTransport {
                 .unwrap_or(Some(log))
                 .ok_or_else(|| "Transform failed".to_string())?;
 
-            writeln!(file, "{}", formatted_log)
-                .map_err(|e| format!("Failed to write log: {}", e))?;
+            match &self.options.finalizer {
+                Some(finalizer) => BYTES.with(|bytes| {
+                    let mut bytes = bytes.borrow_mut();
+                    bytes.clear();
+                    finalizer.append_line(&formatted_log, &mut bytes)?;
+                    file.write_all(&bytes)
+                }),
+                None => writeln!(file, "{}", formatted_log),
+            }
+            .map_err(|e| format!("Failed to write log: {}", e))?;
         }
 
         // Flush after writing batch
@@ -390,6 +487,9 @@ pub struct FileTransportBuilder {
     level: Option<String>,
     format: Option<Arc<dyn Format<Input = LogInfo> + Send + Sync>>,
     filename: Option<PathBuf>,
+    parser: Option<Arc<dyn Parser + Send + Sync>>,
+    header: Option<String>,
+    finalizer: Option<Arc<dyn Finalizer + Send + Sync>>,
 }
 
 impl Default for FileTransportBuilder {
@@ -404,6 +504,9 @@ impl FileTransportBuilder {
             level: None,
             format: None,
             filename: None,
+            parser: None,
+            header: None,
+            finalizer: None,
         }
     }
 
@@ -425,11 +528,47 @@ impl FileTransportBuilder {
         self
     }
 
+    /// Sets the parser used to read the file back, e.g. `logform::parse::simple()`
+    /// when writing with `simple()`.
+    pub fn parser<P>(mut self, parser: P) -> Self
+    where
+        P: Parser + Send + Sync + 'static,
+    {
+        self.parser = Some(Arc::new(parser));
+        self
+    }
+
+    /// Sets a line written first whenever the file is created (or found empty),
+    /// such as the column names from `logform::csv().header()`. Queries skip the
+    /// file's first line when the parser recognises it as a header
+    /// (`Parser::is_header`), as `CsvParser` does.
+    pub fn header<T: Into<String>>(mut self, header: T) -> Self {
+        self.header = Some(header.into());
+        self
+    }
+
+    /// Writes entries with `finalizer` (e.g. `logform::json()`) straight into
+    /// a reused buffer instead of through the `formatted` string, so the
+    /// format should run everything but the finalizer: `.format(timestamp())`
+    /// with `.finalizer(json())` writes what `timestamp().chain(json())` would.
+    /// A `Logger` leaves its default `json()` format off this transport, but
+    /// runs a format set on the logger as given.
+    pub fn finalizer<F>(mut self, finalizer: F) -> Self
+    where
+        F: Finalizer + Send + Sync + 'static,
+    {
+        self.finalizer = Some(Arc::new(finalizer));
+        self
+    }
+
     pub fn build(self) -> FileTransport {
         let options = FileTransportOptions {
             level: self.level,
             format: self.format,
             filename: self.filename,
+            parser: self.parser,
+            header: self.header,
+            finalizer: self.finalizer,
             // Set other fields as needed
         };
         FileTransport::new(options)
@@ -439,7 +578,7 @@ impl FileTransportBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use logform::{json, timestamp};
+    use logform::{json, simple, timestamp};
     use std::sync::Arc;
     use std::thread;
     use std::time::Duration;
@@ -494,4 +633,162 @@ mod tests {
         let _ = std::fs::remove_file("test_target.log");
         Ok(())
     }
+
+    #[test]
+    fn test_query_populates_and_sorts_by_timestamp() -> Result<(), String> {
+        let path = "test_query_timestamp.log";
+        let _ = std::fs::remove_file(path);
+
+        let transport = FileTransport::builder().filename(path).build();
+        let now = Utc::now();
+        for (offset, message) in [(30, "older"), (10, "newer")] {
+            let info = LogInfo::new("info", message)
+                .with_timestamp(now - chrono::Duration::seconds(offset));
+            let info = timestamp().chain(json()).transform(info).unwrap();
+            transport.log(info);
+        }
+        transport.flush()?;
+
+        let results = transport.query(&LogQuery::new().order("desc"))?;
+        let _ = std::fs::remove_file(path);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].message, "newer");
+        assert_eq!(results[1].message, "older");
+        assert!(results.iter().all(|entry| entry.timestamp.is_some()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_reads_simple_format_with_parser() -> Result<(), String> {
+        let path = "test_query_simple.log";
+        let _ = std::fs::remove_file(path);
+
+        let transport = FileTransport::builder()
+            .filename(path)
+            .parser(log_parse::simple())
+            .build();
+        for (level, user_id) in [("info", 1), ("error", 2)] {
+            let info = LogInfo::new(level, "Request handled").with_meta("user_id", user_id);
+            let info = timestamp().chain(simple()).transform(info).unwrap();
+            transport.log(info);
+        }
+        transport.flush()?;
+
+        let results = transport.query(&LogQuery::new().levels(vec!["error"]))?;
+        let _ = std::fs::remove_file(path);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].message, "Request handled");
+        assert_eq!(results[0].meta["user_id"], serde_json::json!(2));
+        assert!(results[0].timestamp.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_header_written_once_and_skipped_by_query() -> Result<(), String> {
+        let path = "test_csv_header.csv";
+        let _ = std::fs::remove_file(path);
+
+        let format = logform::csv().with_columns(&["timestamp", "level", "message", "order.id"]);
+        for run in 0..2 {
+            let transport = FileTransport::builder()
+                .filename(path)
+                .format(format.clone())
+                .parser(format.parser())
+                .header(format.header())
+                .build();
+            let info = LogInfo::new("info", "Order placed")
+                .with_meta("order", serde_json::json!({"id": run}));
+            transport.log(timestamp().chain(format.clone()).transform(info).unwrap());
+            transport.flush()?;
+        }
+
+        let transport = FileTransport::builder()
+            .filename(path)
+            .parser(format.parser())
+            .build();
+        let results = transport.query(&LogQuery::new())?;
+        let contents = std::fs::read_to_string(path).unwrap();
+        let _ = std::fs::remove_file(path);
+
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "timestamp,level,message,order.id");
+        assert!(lines[2].ends_with(",info,Order placed,1"));
+        assert_eq!(results.len(), 2);
+        assert!(
+            results
+                .iter()
+                .any(|entry| entry.meta["order"] == serde_json::json!({"id": 1}))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_reads_multi_line_csv_records() -> Result<(), String> {
+        let path = "test_csv_multi_line.csv";
+        let _ = std::fs::remove_file(path);
+
+        let format = logform::csv().with_columns(&["timestamp", "level", "message"]);
+        let transport = FileTransport::builder()
+            .filename(path)
+            .format(format.clone())
+            .parser(format.parser())
+            .header(format.header())
+            .build();
+        for info in [
+            LogInfo::new("error", "Query failed:\n  \"timeout\"\r\nretrying"),
+            LogInfo::new("level", "message"),
+            LogInfo::new("info", "Recovered"),
+        ] {
+            transport.log(timestamp().chain(format.clone()).transform(info).unwrap());
+        }
+        transport.flush()?;
+
+        let results = transport.query(&LogQuery::new())?;
+        let _ = std::fs::remove_file(path);
+
+        let entries: Vec<(&str, &str)> = results
+            .iter()
+            .map(|entry| (entry.level.as_ref(), entry.message.as_str()))
+            .collect();
+        assert_eq!(entries.len(), 3);
+        assert!(entries.contains(&("error", "Query failed:\n  \"timeout\"\r\nretrying")));
+        assert!(entries.contains(&("level", "message")));
+        assert!(entries.contains(&("info", "Recovered")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_finalizer_writes_lines_query_reads_back() -> Result<(), String> {
+        let path = "test_finalizer.log";
+        let _ = std::fs::remove_file(path);
+
+        let transport = FileTransport::builder()
+            .filename(path)
+            .finalizer(logform::json())
+            .build();
+        let info = LogInfo::new("info", "Streamed").with_meta("id", 1);
+        transport.log(timestamp().transform(info).unwrap());
+        transport.log_batch(vec![
+            timestamp()
+                .transform(LogInfo::new("warn", "Batched"))
+                .unwrap(),
+        ]);
+        transport.flush()?;
+
+        let results = transport.query(&LogQuery::new())?;
+        let contents = std::fs::read_to_string(path).unwrap();
+        let _ = std::fs::remove_file(path);
+
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(
+            lines[0].starts_with(r#"{"level":"info","message":"Streamed","id":1,"timestamp":"#)
+        );
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().any(|entry| entry.message == "Batched"));
+        Ok(())
+    }
 }
@@ -2,7 +2,7 @@ mod to_mongodb_filter;
 
 use chrono::{DateTime, Utc};
 use futures::StreamExt;
-use logform::LogInfo;
+use logform::{LogInfo, Meta};
 use mongodb::{
     bson::{self, doc, Document},
     options::{FindOptions, IndexOptions},
@@ -10,7 +10,6 @@ use mongodb::{
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
     future::Future,
     pin::Pin,
     sync::{
@@ -30,7 +29,7 @@ struct LogDocument {
     level: String,
     message: String,
     #[serde(flatten)]
-    meta: HashMap<String, serde_json::Value>,
+    meta: Meta,
 }
 
 pub struct MongoDBTransport {
@@ -363,12 +362,14 @@ impl MongoDBTransport {
                     // Apply user-requested-field Projection (Response Filtering)
                     if !query.fields.is_empty() {
                         if !normalized_fields.contains(&"level".to_string()) {
-                            log_info.level.clear();
+                            log_info.level = "".into();
                         }
                         if !normalized_fields.contains(&"message".to_string()) {
                             log_info.message.clear();
                         }
-                        log_info.meta.retain(|k, _| normalized_fields.contains(k));
+                        log_info
+                            .meta
+                            .retain(|k, _| query.fields.iter().any(|field| field == k.as_str()));
                     }
 
                     results.push(log_info);
@@ -384,11 +385,25 @@ impl MongoDBTransport {
 fn document_to_loginfo(doc: LogDocument) -> LogInfo {
     let mut meta = doc.meta;
     meta.insert(
-        "timestamp".to_string(),
-        serde_json::Value::from(doc.timestamp.to_rfc3339()),
+        "timestamp",
+        serde_json::Value::from(LogInfo::format_timestamp(&doc.timestamp)),
     );
 
-    LogInfo::from_parts(doc.level, doc.message, meta)
+    LogInfo::from_parts(doc.level, doc.message, meta).with_timestamp(doc.timestamp)
+}
+
+impl From<LogInfo> for LogDocument {
+    fn from(mut info: LogInfo) -> Self {
+        // The document's own `timestamp` field is authoritative; a `timestamp` meta key
+        // (e.g. from the `timestamp()` format) would collide with it once flattened.
+        info.meta.remove("timestamp");
+        LogDocument {
+            timestamp: info.timestamp.unwrap_or_else(Utc::now),
+            level: info.level.into_owned(),
+            message: info.message,
+            meta: info.meta,
+        }
+    }
 }
 
 async fn create_indexes(collection: &Collection<LogDocument>) -> Result<(), mongodb::error::Error> {
@@ -411,12 +426,7 @@ async fn create_indexes(collection: &Collection<LogDocument>) -> Result<(), mong
 
 impl Transport<LogInfo> for MongoDBTransport {
     fn log(&self, info: LogInfo) {
-        let doc = LogDocument {
-            timestamp: Utc::now(),
-            level: info.level,
-            message: info.message,
-            meta: info.meta,
-        };
+        let doc = LogDocument::from(info);
 
         if let Err(e) = self.sender.send(MongoDBThreadMessage::Log(doc)) {
             eprintln!("Failed to send log to the logging thread: {}", e);
@@ -424,15 +434,7 @@ impl Transport<LogInfo> for MongoDBTransport {
     }
 
     fn log_batch(&self, logs: Vec<LogInfo>) {
-        let docs: Vec<LogDocument> = logs
-            .into_iter()
-            .map(|info| LogDocument {
-                timestamp: Utc::now(),
-                level: info.level,
-                message: info.message,
-                meta: info.meta,
-            })
-            .collect();
+        let docs: Vec<LogDocument> = logs.into_iter().map(LogDocument::from).collect();
 
         if let Err(e) = self.sender.send(MongoDBThreadMessage::LogBatch(docs)) {
             eprintln!("Failed to send log batch to the logging thread: {}", e);
@@ -504,6 +506,21 @@ mod tests {
         collection.delete_one(filter).await.unwrap();
     }
 
+    #[test]
+    fn test_log_document_uses_captured_timestamp() {
+        let captured = Utc::now() - chrono::Duration::minutes(5);
+        let info = LogInfo::new("info", "queued")
+            .with_meta("timestamp", "12:30")
+            .with_timestamp(captured);
+
+        let doc = LogDocument::from(info);
+        assert_eq!(doc.timestamp, captured);
+        assert!(!doc.meta.contains_key("timestamp"));
+
+        let info = document_to_loginfo(doc);
+        assert_eq!(info.timestamp, Some(captured));
+    }
+
     // TODO: test doesn't clean up inserted entries between runs — stale data accumulates
     // in the shared collection and causes the count assertion to fail on repeat runs.
     // Fix: delete inserted entries before asserting, following test_logging_persists_to_mongodb.
@@ -560,7 +577,7 @@ mod tests {
         // Assert that the query results match expected conditions
         assert_eq!(results.len(), 1, "Query should return 1 result");
 
-        let levels: Vec<String> = results.iter().map(|log| log.level.clone()).collect();
+        let levels: Vec<String> = results.iter().map(|log| log.level.to_string()).collect();
         let messages: Vec<String> = results.iter().map(|log| log.message.clone()).collect();
 
         // Assert that the logs contain the correct levels and messages based on the query
@@ -129,7 +129,7 @@ fn field_path_to_string(
 ) -> String {
     use winston_transport::query_dsl::dlc::alpha::a::field_path::PathSegment;
 
-    path.segments
+    path.segments()
         .iter()
         .map(|segment| match segment {
             PathSegment::Field(name) => name.clone(),
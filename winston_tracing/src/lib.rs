@@ -1,10 +1,10 @@
-use logform::LogInfo;
-use std::{collections::HashMap, sync::Arc};
+use logform::{LogInfo, Meta};
+use std::sync::Arc;
 use tracing::{Event, Level, Subscriber};
 use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
 use winston::Logger;
 
-struct SpanFields(HashMap<String, serde_json::Value>);
+struct SpanFields(Meta);
 
 /// A [`tracing_subscriber::Layer`] that routes tracing events into a Winston [`Logger`].
 ///
@@ -93,12 +93,9 @@ where
         ctx: Context<'_, S>,
     ) {
         let span = ctx.span(id).expect("span not found, this is a bug");
-        let mut fields = HashMap::new();
+        let mut fields = Meta::new();
         // Seed with the span name so child events know which span they fired in.
-        fields.insert(
-            "span".to_string(),
-            serde_json::Value::String(span.name().to_string()),
-        );
+        fields.insert("span", serde_json::Value::String(span.name().to_string()));
         attrs.record(&mut FieldVisitor(&mut fields));
         span.extensions_mut().insert(SpanFields(fields));
     }
@@ -139,9 +136,9 @@ where
     }
 
     fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
-        let level = map_level(event.metadata().level()).to_string();
+        let level = map_level(event.metadata().level());
 
-        let mut fields: HashMap<String, serde_json::Value> = HashMap::new();
+        let mut fields = Meta::new();
 
         // Walk ancestor spans outermost → innermost so that more specific
         // (closer) spans override broader context.
@@ -169,18 +166,15 @@ where
             .unwrap_or_default();
 
         fields.insert(
-            "target".to_string(),
+            "target",
             serde_json::Value::String(event.metadata().target().to_string()),
         );
 
         if let Some(file) = event.metadata().file() {
-            fields.insert(
-                "file".to_string(),
-                serde_json::Value::String(file.to_string()),
-            );
+            fields.insert("file", serde_json::Value::String(file.to_string()));
         }
         if let Some(line) = event.metadata().line() {
-            fields.insert("line".to_string(), serde_json::Value::Number(line.into()));
+            fields.insert("line", serde_json::Value::Number(line.into()));
         }
 
         self.logger.log(LogInfo::from_parts(level, message, fields));
@@ -197,54 +191,43 @@ fn map_level(level: &Level) -> &'static str {
     }
 }
 
-struct FieldVisitor<'a>(&'a mut HashMap<String, serde_json::Value>);
+struct FieldVisitor<'a>(&'a mut Meta);
 
 impl tracing::field::Visit for FieldVisitor<'_> {
     fn record_f64(&mut self, field: &tracing::field::Field, value: f64) {
         let number = serde_json::Number::from_f64(value).unwrap_or_else(|| 0.into());
         self.0
-            .insert(field.name().to_string(), serde_json::Value::Number(number));
+            .insert(field.name(), serde_json::Value::Number(number));
     }
 
     fn record_i64(&mut self, field: &tracing::field::Field, value: i64) {
-        self.0.insert(
-            field.name().to_string(),
-            serde_json::Value::Number(value.into()),
-        );
+        self.0
+            .insert(field.name(), serde_json::Value::Number(value.into()));
     }
 
     fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
-        self.0.insert(
-            field.name().to_string(),
-            serde_json::Value::Number(value.into()),
-        );
+        self.0
+            .insert(field.name(), serde_json::Value::Number(value.into()));
     }
 
     fn record_i128(&mut self, field: &tracing::field::Field, value: i128) {
         // serde_json::Number doesn't support i128; store as string to avoid silent truncation.
-        self.0.insert(
-            field.name().to_string(),
-            serde_json::Value::String(value.to_string()),
-        );
+        self.0
+            .insert(field.name(), serde_json::Value::String(value.to_string()));
     }
 
     fn record_u128(&mut self, field: &tracing::field::Field, value: u128) {
-        self.0.insert(
-            field.name().to_string(),
-            serde_json::Value::String(value.to_string()),
-        );
+        self.0
+            .insert(field.name(), serde_json::Value::String(value.to_string()));
     }
 
     fn record_bool(&mut self, field: &tracing::field::Field, value: bool) {
-        self.0
-            .insert(field.name().to_string(), serde_json::Value::Bool(value));
+        self.0.insert(field.name(), serde_json::Value::Bool(value));
     }
 
     fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
-        self.0.insert(
-            field.name().to_string(),
-            serde_json::Value::String(value.to_string()),
-        );
+        self.0
+            .insert(field.name(), serde_json::Value::String(value.to_string()));
     }
 
     fn record_error(
@@ -252,15 +235,13 @@ impl tracing::field::Visit for FieldVisitor<'_> {
         field: &tracing::field::Field,
         value: &(dyn std::error::Error + 'static),
     ) {
-        self.0.insert(
-            field.name().to_string(),
-            serde_json::Value::String(value.to_string()),
-        );
+        self.0
+            .insert(field.name(), serde_json::Value::String(value.to_string()));
     }
 
     fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
         self.0.insert(
-            field.name().to_string(),
+            field.name(),
             serde_json::Value::String(format!("{value:?}")),
         );
     }
@@ -370,7 +351,7 @@ mod tests {
         logger.flush().unwrap();
 
         let logs = captured.lock().unwrap();
-        let levels: Vec<&str> = logs.iter().map(|l| l.level.as_str()).collect();
+        let levels: Vec<&str> = logs.iter().map(|l| l.level.as_ref()).collect();
         assert_eq!(levels, ["error", "warn", "info", "debug", "trace"]);
     }
 }
@@ -45,6 +45,8 @@ where
 {
     sender: Sender<BatchMessage<L>>,
     thread_handle: Option<JoinHandle<()>>,
+    terminal: Option<bool>,
+    finalizer: bool,
     config: BatchConfig,
     _phantom: PhantomData<(T, L)>,
 }
@@ -62,6 +64,8 @@ where
     /// Creates a new BatchedTransport with custom configuration
     pub fn with_config(transport: T, config: BatchConfig) -> Self {
         let (sender, receiver) = mpsc::channel();
+        let terminal = transport.is_terminal();
+        let finalizer = transport.has_finalizer();
         let batch_config = config.clone();
 
         let thread_handle = thread::spawn(move || {
@@ -71,6 +75,8 @@ where
         Self {
             sender,
             thread_handle: Some(thread_handle),
+            terminal,
+            finalizer,
             config,
             _phantom: PhantomData,
         }
@@ -79,6 +85,8 @@ where
     /// Creates a BatchedTransport with a custom thread name
     pub fn with_thread_name(transport: T, config: BatchConfig, thread_name: String) -> Self {
         let (sender, receiver) = mpsc::channel();
+        let terminal = transport.is_terminal();
+        let finalizer = transport.has_finalizer();
         let batch_config = config.clone();
 
         let thread_handle = thread::Builder::new()
@@ -91,6 +99,8 @@ where
         Self {
             sender,
             thread_handle: Some(thread_handle),
+            terminal,
+            finalizer,
             config,
             _phantom: PhantomData,
         }
@@ -195,6 +205,14 @@ where
         let _ = self.sender.send(BatchMessage::Log(info));
     }
 
+    fn is_terminal(&self) -> Option<bool> {
+        self.terminal
+    }
+
+    fn has_finalizer(&self) -> bool {
+        self.finalizer
+    }
+
     fn flush(&self) -> Result<(), String> {
         let (response_sender, response_receiver) = mpsc::channel();
 
@@ -247,6 +265,8 @@ where
         Self {
             sender: self.sender.clone(),
             thread_handle: None, // Don't clone thread handle because thread is owned by original
+            terminal: self.terminal,
+            finalizer: self.finalizer,
             config: self.config.clone(),
             _phantom: PhantomData,
         }
@@ -112,14 +112,16 @@ impl LogQuery {
     }
 
     fn extract_timestamp(entry: &LogInfo) -> Option<DateTime<Utc>> {
-        entry.meta.get("timestamp").and_then(|value| match value {
-            Value::String(ts_str) => parse(ts_str).ok().map(|dt| dt.with_timezone(&Utc)),
-            _ => None,
+        entry.timestamp.or_else(|| {
+            entry.meta.get("timestamp").and_then(|value| match value {
+                Value::String(ts_str) => parse(ts_str).ok().map(|dt| dt.with_timezone(&Utc)),
+                _ => None,
+            })
         })
     }
 
     pub fn matches(&self, entry: &LogInfo) -> bool {
-        if !self.levels.is_empty() && !self.levels.contains(&entry.level) {
+        if !self.levels.is_empty() && !self.levels.iter().any(|level| *level == entry.level) {
             return false;
         }
 
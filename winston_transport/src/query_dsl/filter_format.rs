@@ -0,0 +1,56 @@
+use super::dlc::alpha::a::QueryNode;
+use logform::{Format, LogInfo};
+
+/// A format that keeps entries matching a query and drops the rest.
+///
+/// The query is evaluated against [`LogInfo::to_flat_value`], the same view
+/// transports query, so meta fields are addressed without a `meta.` prefix.
+#[derive(Debug, Clone)]
+pub struct QueryFilter {
+    query: QueryNode,
+}
+
+impl QueryFilter {
+    pub fn new(query: impl Into<QueryNode>) -> Self {
+        Self {
+            query: query.into(),
+        }
+    }
+}
+
+impl Format for QueryFilter {
+    type Input = LogInfo;
+
+    fn transform(&self, info: LogInfo) -> Option<Self::Input> {
+        self.query.evaluate(&info.to_flat_value()).then_some(info)
+    }
+}
+
+pub fn filter_query(query: impl Into<QueryNode>) -> QueryFilter {
+    QueryFilter::new(query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query_dsl::dlc::alpha::a::field_comparisons::prelude::*;
+    use crate::{and, field_query};
+
+    #[test]
+    fn test_filter_query_keeps_matching_entries() {
+        let format = filter_query(and!(
+            field_query!("level", eq("info")),
+            field_query!("http.status", lt(500))
+        ));
+
+        let ok = LogInfo::new("info", "GET /users")
+            .with_meta("http", serde_json::json!({"status": 200}));
+        let failed = LogInfo::new("info", "GET /users")
+            .with_meta("http", serde_json::json!({"status": 503}));
+        let error = LogInfo::new("error", "Boom");
+
+        assert!(format.transform(ok).is_some());
+        assert!(format.transform(failed).is_none());
+        assert!(format.transform(error).is_none());
+    }
+}
@@ -1 +1,4 @@
 pub mod dlc;
+mod filter_format;
+
+pub use filter_format::{filter_query, QueryFilter};
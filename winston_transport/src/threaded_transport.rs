@@ -25,6 +25,8 @@ where
 {
     sender: Sender<TransportMessage<L>>,
     thread_handle: Option<JoinHandle<()>>,
+    terminal: Option<bool>,
+    finalizer: bool,
     _phantom_data: PhantomData<(T, L)>,
 }
 
@@ -36,6 +38,8 @@ where
     /// Creates a new ThreadedTransport that wraps the given transport
     pub fn new(transport: T) -> Self {
         let (sender, receiver) = mpsc::channel();
+        let terminal = transport.is_terminal();
+        let finalizer = transport.has_finalizer();
 
         let thread_handle = thread::spawn(move || {
             Self::run_transport_thread(transport, receiver);
@@ -44,6 +48,8 @@ where
         Self {
             sender,
             thread_handle: Some(thread_handle),
+            terminal,
+            finalizer,
             _phantom_data: PhantomData,
         }
     }
@@ -51,6 +57,8 @@ where
     /// Creates a new ThreadedTransport with a custom thread name
     pub fn with_thread_name(transport: T, thread_name: String) -> Self {
         let (sender, receiver) = mpsc::channel();
+        let terminal = transport.is_terminal();
+        let finalizer = transport.has_finalizer();
 
         let thread_handle = thread::Builder::new()
             .name(thread_name)
@@ -62,6 +70,8 @@ where
         Self {
             sender,
             thread_handle: Some(thread_handle),
+            terminal,
+            finalizer,
             _phantom_data: PhantomData,
         }
     }
@@ -112,6 +122,14 @@ where
         let _ = self.sender.send(TransportMessage::Log(info));
     }
 
+    fn is_terminal(&self) -> Option<bool> {
+        self.terminal
+    }
+
+    fn has_finalizer(&self) -> bool {
+        self.finalizer
+    }
+
     fn flush(&self) -> Result<(), String> {
         let (response_sender, response_receiver) = mpsc::channel();
 
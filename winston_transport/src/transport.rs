@@ -16,4 +16,17 @@ pub trait Transport<L> {
     fn query(&self, _options: &LogQuery) -> Result<Vec<L>, String> {
         Ok(Vec::new())
     }
+
+    /// Whether entries end up on a terminal, if the transport knows. Formats
+    /// use this to decide on colors.
+    fn is_terminal(&self) -> Option<bool> {
+        None
+    }
+
+    /// Whether the transport serializes entries itself, through a
+    /// `logform::Finalizer`. The logger then skips its default `json()` format
+    /// for this transport.
+    fn has_finalizer(&self) -> bool {
+        false
+    }
 }
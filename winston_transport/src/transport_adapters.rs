@@ -12,6 +12,7 @@
 //! All adapters are completely generic over the log type `L`.
 
 use crate::Transport;
+use logform::{Finalizer, LogInfo};
 use std::{
     cell::RefCell,
     fmt::{Display, Write as FmtWrite},
@@ -179,6 +180,9 @@ where
     }
 }
 
+/// Appends one entry and a line break to a buffer; see `with_finalizer`.
+type AppendLine<L> = dyn Fn(&L, &mut Vec<u8>) -> io::Result<()> + Send + Sync;
+
 /// owned adapter to use a Writer as a Transport.
 /// Generic over any log type `L` that implements `Display`.
 pub struct WriterTransport<W, L>
@@ -187,6 +191,8 @@ where
     L: Display,
 {
     pub writer: Mutex<W>,
+    terminal: Option<bool>,
+    finalizer: Option<Box<AppendLine<L>>>,
     _phantom: std::marker::PhantomData<L>,
 }
 
@@ -198,9 +204,68 @@ where
     pub fn new(writer: W) -> Self {
         Self {
             writer: Mutex::new(writer),
+            terminal: None,
+            finalizer: None,
             _phantom: std::marker::PhantomData,
         }
     }
+
+    /// Records whether the writer is a terminal, reported by `is_terminal()`.
+    pub fn with_terminal(mut self, terminal: bool) -> Self {
+        self.terminal = Some(terminal);
+        self
+    }
+
+    /// Serializes entries through the finalizer into a buffer reused across
+    /// calls, then writes the buffer in one call while holding the lock.
+    fn write_finalized<'a>(&self, infos: impl IntoIterator<Item = &'a L>)
+    where
+        L: 'a,
+    {
+        let Some(append) = &self.finalizer else {
+            return;
+        };
+        thread_local! {
+            static BYTES: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+        }
+        BYTES.with(|bytes| {
+            let mut bytes = bytes.borrow_mut();
+            bytes.clear();
+            for info in infos {
+                if let Err(e) = append(info, &mut bytes) {
+                    eprintln!("[winston_transport] Failed to finalize log entry: {}", e);
+                }
+            }
+            self.write_all(&bytes);
+        });
+    }
+
+    fn write_all(&self, bytes: &[u8]) {
+        if let Ok(mut writer) = self.writer.lock() {
+            if let Err(e) = writer.write_all(bytes) {
+                eprintln!("[winston_transport] Failed to write log entry: {}", e);
+            }
+        }
+    }
+}
+
+impl<W> WriterTransport<W, LogInfo>
+where
+    W: Write,
+{
+    /// Writes entries with `finalizer` (e.g. `logform::json()`) instead of
+    /// their `Display` output, skipping the `formatted` string. The logger's
+    /// or transport's format then runs everything but the finalizer; a
+    /// `Logger` leaves its default `json()` format off on its own.
+    pub fn with_finalizer<F>(mut self, finalizer: F) -> Self
+    where
+        F: Finalizer + Send + Sync + 'static,
+    {
+        self.finalizer = Some(Box::new(move |info, buffer| {
+            finalizer.append_line(info, buffer)
+        }));
+        self
+    }
 }
 
 impl<W, L> Transport<L> for WriterTransport<W, L>
@@ -208,7 +273,18 @@ where
     W: Write,
     L: Display,
 {
+    fn is_terminal(&self) -> Option<bool> {
+        self.terminal
+    }
+
+    fn has_finalizer(&self) -> bool {
+        self.finalizer.is_some()
+    }
+
     fn log(&self, info: L) {
+        if self.finalizer.is_some() {
+            return self.write_finalized([&info]);
+        }
         thread_local! {
             static BUF: RefCell<String> = const { RefCell::new(String::new()) };
         }
@@ -216,9 +292,7 @@ where
             let mut buf = buf.borrow_mut();
             buf.clear();
             let _ = writeln!(buf, "{}", info);
-            if let Ok(mut writer) = self.writer.lock() {
-                let _ = writer.write_all(buf.as_bytes());
-            }
+            self.write_all(buf.as_bytes());
         });
     }
 
@@ -226,6 +300,9 @@ where
         if infos.is_empty() {
             return;
         }
+        if self.finalizer.is_some() {
+            return self.write_finalized(&infos);
+        }
         thread_local! {
             static BUF: RefCell<String> = const { RefCell::new(String::new()) };
         }
@@ -235,9 +312,7 @@ where
             for info in infos {
                 let _ = writeln!(buf, "{}", info);
             }
-            if let Ok(mut writer) = self.writer.lock() {
-                let _ = writer.write_all(buf.as_bytes());
-            }
+            self.write_all(buf.as_bytes());
         });
     }
 
@@ -553,4 +628,23 @@ mod tests {
         assert!(contents.contains("Borrowed log 1"));
         assert!(contents.contains("Borrowed log 2"));
     }
+
+    #[test]
+    fn test_writer_transport_with_finalizer() {
+        let transport = WriterTransport::new(Vec::new()).with_finalizer(logform::json());
+
+        transport.log(LogInfo::new("info", "One").with_meta("id", 1));
+        transport.log_batch(vec![
+            LogInfo::new("warn", "Two"),
+            LogInfo::new("error", "Three"),
+        ]);
+
+        let writer = transport.writer.lock().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&writer),
+            "{\"level\":\"info\",\"message\":\"One\",\"id\":1}\n\
+             {\"level\":\"warn\",\"message\":\"Two\"}\n\
+             {\"level\":\"error\",\"message\":\"Three\"}\n"
+        );
+    }
 }